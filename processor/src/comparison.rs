@@ -0,0 +1,129 @@
+//! Runs the same workload with several schedulers and compares them.
+//!
+//! Each scheduler runs the workload in its own simulation, and the
+//! [`metrics`] of every simulation are shown in a single table.
+
+use std::fmt::Display;
+
+use scheduler::Scheduler;
+
+use crate::metrics::{self, Metrics};
+use crate::{Log, Process, Processor};
+
+/// The scheduler type that every configuration of a comparison uses.
+pub type AnyScheduler = Box<dyn Scheduler>;
+
+/// The schedulers that run the workload of a comparison.
+#[derive(Default)]
+pub struct Comparison {
+    schedulers: Vec<(String, AnyScheduler)>,
+}
+
+impl Comparison {
+    /// Creates a comparison without any schedulers.
+    pub fn new() -> Comparison {
+        Comparison {
+            schedulers: Vec::new(),
+        }
+    }
+
+    /// Adds a scheduler to the comparison.
+    ///
+    /// * `name` - the name of the scheduler's row, usually with its parameters.
+    pub fn scheduler<S: Scheduler + 'static>(mut self, name: &str, scheduler: S) -> Comparison {
+        self.schedulers
+            .push((String::from(name), Box::new(scheduler)));
+        self
+    }
+
+    /// Runs the workload `f` as the process with PID 1 with each of the
+    /// schedulers, in the order in which they were added.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::comparison::Comparison;
+    /// use scheduler::{cfs, round_robin};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let table = Comparison::new()
+    ///     .scheduler("Round Robin 3", round_robin(NonZeroUsize::new(3).unwrap(), 1))
+    ///     .scheduler("CFS 10", cfs(NonZeroUsize::new(10).unwrap(), 1))
+    ///     .run(|process| {
+    ///         process.fork(|process| process.exec(), 0);
+    ///         process.exec();
+    ///     });
+    ///
+    /// assert_eq!(table.rows.len(), 2);
+    /// println!("{}", table);
+    /// ```
+    pub fn run<F>(self, f: F) -> Table
+    where
+        F: Fn(&Process<AnyScheduler>) + Sync,
+    {
+        let rows = self
+            .schedulers
+            .into_iter()
+            .map(|(name, scheduler)| {
+                let logs = Processor::run(scheduler, |process| f(process));
+                let metrics = metrics::measure(&logs);
+                Row {
+                    name,
+                    logs,
+                    metrics,
+                }
+            })
+            .collect();
+        Table { rows }
+    }
+}
+
+/// The results of running the workload with one of the schedulers.
+#[derive(Debug)]
+pub struct Row {
+    /// The name of the scheduler.
+    pub name: String,
+
+    /// The logs of the simulation.
+    pub logs: Vec<Log>,
+
+    /// The metrics of the simulation.
+    pub metrics: Metrics,
+}
+
+/// The results of a comparison, one row for each scheduler.
+#[derive(Debug)]
+pub struct Table {
+    /// The rows, in the order in which the schedulers were added.
+    pub rows: Vec<Row>,
+}
+
+impl Display for Table {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "SCHEDULER\tRESPONSE\tWAITING\tTURNAROUND\tUTILIZATION\tTHROUGHPUT\tSWITCHES\tFAIRNESS"
+        )?;
+        for row in self.rows.iter() {
+            let metrics = &row.metrics;
+            writeln!(
+                f,
+                "{}\t{}\t\t{}\t{}\t\t{:.3}\t\t{:.3}\t\t{}\t\t{}",
+                row.name,
+                optional(metrics.average_response_time()),
+                optional(metrics.average_waiting_time()),
+                optional(metrics.average_turnaround_time()),
+                metrics.utilization(),
+                metrics.throughput(),
+                metrics.context_switches(),
+                optional(metrics.fairness())
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats a value that may be missing.
+fn optional(value: Option<f64>) -> String {
+    value.map_or(String::from("-"), |value| format!("{:.3}", value))
+}
@@ -0,0 +1,143 @@
+use std::collections::HashSet;
+use std::fmt::Display;
+
+use scheduler::{Blocker, Pid};
+
+/// A process that cannot run until another process wakes it up.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BlockedProcess {
+    /// The PID of the process.
+    pub pid: Pid,
+
+    /// What the process is blocked on.
+    pub blocker: Blocker,
+
+    /// The processes that could wake up the process, like the owner of
+    /// the lock it waits for, or the other blocked processes that can still
+    /// signal the event it waits for. A process waiting for a process that
+    /// has exited has no wakers.
+    pub wakers: Vec<Pid>,
+}
+
+/// The wait-for graph of the processes, built when the scheduler
+/// returns a [`scheduler::SchedulingDecision::Deadlock`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnosis {
+    /// The blocked processes, sorted by their PIDs.
+    pub blocked: Vec<BlockedProcess>,
+
+    /// The groups of processes that wait for each other, the strongly connected
+    /// components of the graph. A process that waits for itself, like a process
+    /// that acquires a lock it holds, is a group by itself.
+    pub cycles: Vec<Vec<Pid>>,
+}
+
+impl Diagnosis {
+    /// Builds the wait-for graph from what each process is blocked on.
+    ///
+    /// `wakes` returns `true` if a process can signal the event or post the
+    /// semaphore of a blocker.
+    pub(crate) fn new<F: Fn(Pid, Blocker) -> bool>(
+        mut blockers: Vec<(Pid, Blocker)>,
+        wakes: F,
+    ) -> Diagnosis {
+        blockers.sort_by_key(|(pid, _)| *pid);
+        let pids = blockers.iter().map(|(pid, _)| *pid).collect::<Vec<Pid>>();
+        let blocked = blockers
+            .into_iter()
+            .map(|(pid, blocker)| {
+                let wakers = match blocker {
+                    Blocker::Event(_) | Blocker::Semaphore(_) => pids
+                        .iter()
+                        .copied()
+                        .filter(|waker| *waker != pid && wakes(*waker, blocker))
+                        .collect(),
+                    // a process that is not blocked has exited
+                    Blocker::Child(other) | Blocker::Lock { owner: other, .. } => pids
+                        .iter()
+                        .copied()
+                        .filter(|waker| *waker == other)
+                        .collect(),
+                };
+                BlockedProcess {
+                    pid,
+                    blocker,
+                    wakers,
+                }
+            })
+            .collect::<Vec<BlockedProcess>>();
+
+        let reachable = blocked
+            .iter()
+            .map(|process| Diagnosis::reachable(&blocked, process.pid))
+            .collect::<Vec<HashSet<Pid>>>();
+        let mut cycles = Vec::<Vec<Pid>>::new();
+        for (index, process) in blocked.iter().enumerate() {
+            if !reachable[index].contains(&process.pid)
+                || cycles.iter().flatten().any(|pid| *pid == process.pid)
+            {
+                continue;
+            }
+            // the processes that can reach this process and can be reached from it
+            let cycle = blocked
+                .iter()
+                .zip(reachable.iter())
+                .filter(|(other, other_reachable)| {
+                    reachable[index].contains(&other.pid) && other_reachable.contains(&process.pid)
+                })
+                .map(|(other, _)| other.pid)
+                .collect();
+            cycles.push(cycle);
+        }
+
+        Diagnosis { blocked, cycles }
+    }
+
+    /// Returns the processes that `pid` waits for, directly or through other processes.
+    fn reachable(blocked: &[BlockedProcess], pid: Pid) -> HashSet<Pid> {
+        let mut reachable = HashSet::new();
+        let mut stack = vec![pid];
+        while let Some(pid) = stack.pop() {
+            if let Some(process) = blocked.iter().find(|process| process.pid == pid) {
+                for waker in process.wakers.iter() {
+                    if reachable.insert(*waker) {
+                        stack.push(*waker);
+                    }
+                }
+            }
+        }
+        reachable
+    }
+}
+
+impl Display for Diagnosis {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for process in self.blocked.iter() {
+            write!(
+                f,
+                "Deadlock: process {} waits for {}, ",
+                process.pid, process.blocker
+            )?;
+            if process.wakers.is_empty() {
+                writeln!(f, "nothing can wake it up")?;
+            } else {
+                writeln!(f, "can be woken up by {}", join(&process.wakers))?;
+            }
+        }
+        for cycle in self.cycles.iter() {
+            match cycle.as_slice() {
+                [pid] => writeln!(f, "Deadlock: process {} waits for itself", pid)?,
+                _ => writeln!(f, "Deadlock: processes {} wait for each other", join(cycle))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Joins a list of PIDs with commas.
+fn join(pids: &[Pid]) -> String {
+    pids.iter()
+        .map(|pid| pid.to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
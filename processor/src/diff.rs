@@ -0,0 +1,663 @@
+//! Semantic comparison of logs, and the parser of the text written by
+//! [`crate::format_logs`], so that golden files can be compared as logs.
+
+use std::collections::HashMap;
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use scheduler::{
+    Blocker, Pid, ProcessState, SchedulingDecision, StopReason, SyncObject, Syscall, SyscallResult,
+};
+
+use crate::{BlockedProcess, Diagnosis, Log, ParseError, ProcessInfo};
+
+/// A field that has a different value in the two logs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Change {
+    /// The process whose field differs, [`None`] for the fields of the iteration.
+    pub pid: Option<Pid>,
+
+    /// The name of the field, like `decision`, `state` or `exit status`.
+    pub field: &'static str,
+
+    /// The expected value, as it is displayed by [`crate::format_logs`].
+    pub expected: String,
+
+    /// The actual value, as it is displayed by [`crate::format_logs`].
+    pub actual: String,
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(pid) = self.pid {
+            write!(f, "PID {} ", pid)?;
+        }
+        write!(
+            f,
+            "{}: expected `{}`, found `{}`",
+            self.field, self.expected, self.actual
+        )
+    }
+}
+
+/// The first iteration at which two runs of a simulation diverge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LogDiff {
+    /// The index of the iteration in the logs.
+    pub iteration: usize,
+
+    /// The expected decision, [`None`] if the expected logs have ended.
+    pub expected: Option<SchedulingDecision>,
+
+    /// The actual decision, [`None`] if the actual logs have ended.
+    pub actual: Option<SchedulingDecision>,
+
+    /// The fields of the iteration that differ.
+    pub changes: Vec<Change>,
+
+    /// The reason that the process of the previous iteration has stopped,
+    /// which is the same in both logs and has led to the difference.
+    /// [`None`] for the first iteration or if the previous iteration
+    /// has not run a process.
+    pub cause: Option<(StopReason, SyscallResult)>,
+}
+
+impl Display for LogDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let decision = |decision: Option<SchedulingDecision>| {
+            decision.map_or(String::from("the end of the logs"), |decision| {
+                decision.to_string()
+            })
+        };
+        if self.expected == self.actual {
+            writeln!(
+                f,
+                "Iteration {}: {}",
+                self.iteration + 1,
+                decision(self.actual)
+            )?;
+        } else {
+            writeln!(
+                f,
+                "Iteration {}: expected {}, found {}",
+                self.iteration + 1,
+                decision(self.expected),
+                decision(self.actual)
+            )?;
+        }
+        for change in self.changes.iter() {
+            writeln!(f, "  {}", change)?;
+        }
+        if let Some((reason, result)) = self.cause {
+            writeln!(f, "after {} -> {:?}", reason, result)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the change of a field if its values differ.
+fn change<T: PartialEq, D: Display>(
+    pid: Option<Pid>,
+    field: &'static str,
+    expected: T,
+    actual: T,
+    display: impl Fn(T) -> D,
+) -> Option<Change> {
+    (expected != actual).then(|| Change {
+        pid,
+        field,
+        expected: display(expected).to_string(),
+        actual: display(actual).to_string(),
+    })
+}
+
+/// Returns the fields of a process that differ.
+fn process_changes(pid: Pid, expected: &ProcessInfo, actual: &ProcessInfo) -> Vec<Change> {
+    let optional = |value: Option<String>| value.unwrap_or(String::from("-"));
+    [
+        change(Some(pid), "state", expected.state, actual.state, |state| {
+            state
+        }),
+        change(
+            Some(pid),
+            "priority",
+            expected.priority,
+            actual.priority,
+            |priority| priority,
+        ),
+        change(
+            Some(pid),
+            "total time",
+            expected.timings.0,
+            actual.timings.0,
+            |time| time,
+        ),
+        change(
+            Some(pid),
+            "syscall time",
+            expected.timings.1,
+            actual.timings.1,
+            |time| time,
+        ),
+        change(
+            Some(pid),
+            "execution time",
+            expected.timings.2,
+            actual.timings.2,
+            |time| time,
+        ),
+        change(
+            Some(pid),
+            "exit status",
+            expected.exit_status,
+            actual.exit_status,
+            |status| optional(status.map(|status| status.to_string())),
+        ),
+        change(Some(pid), "ppid", expected.ppid, actual.ppid, |ppid| {
+            optional(ppid.map(|ppid| ppid.to_string()))
+        }),
+        change(
+            Some(pid),
+            "extra",
+            &expected.extra,
+            &actual.extra,
+            |extra| extra,
+        ),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Returns the fields of an iteration that differ.
+fn log_changes(expected: &Log, actual: &Log) -> Vec<Change> {
+    let stop_reason = |stop_reason: Option<(StopReason, SyscallResult)>| match stop_reason {
+        Some((reason, result)) => format!("{} -> {:?}", reason, result),
+        None => String::from("-"),
+    };
+    let mut changes = [
+        change(
+            None,
+            "decision",
+            expected.decision,
+            actual.decision,
+            |decision| decision,
+        ),
+        change(
+            None,
+            "stop reason",
+            expected.stop_reason,
+            actual.stop_reason,
+            stop_reason,
+        ),
+        change(None, "cpu", expected.cpu, actual.cpu, |cpu| cpu),
+        change(None, "cpus", &expected.cpus, &actual.cpus, |cpus| {
+            format!("{:?}", cpus)
+        }),
+        change(None, "events", &expected.events, &actual.events, |events| {
+            events.join(", ")
+        }),
+        change(
+            None,
+            "deadlock",
+            &expected.deadlock,
+            &actual.deadlock,
+            |deadlock| match deadlock {
+                Some(deadlock) => deadlock.to_string().trim_end().replace('\n', "; "),
+                None => String::from("-"),
+            },
+        ),
+        change(None, "start", expected.start, actual.start, |time| time),
+        change(None, "end", expected.end, actual.end, |time| time),
+    ]
+    .into_iter()
+    .flatten()
+    .collect::<Vec<Change>>();
+
+    let mut pids = expected
+        .processes
+        .keys()
+        .chain(actual.processes.keys())
+        .copied()
+        .collect::<Vec<Pid>>();
+    pids.sort();
+    pids.dedup();
+    for pid in pids {
+        match (expected.processes.get(&pid), actual.processes.get(&pid)) {
+            (Some(expected), Some(actual)) => {
+                changes.extend(process_changes(pid, expected, actual))
+            }
+            (expected, actual) => changes.push(Change {
+                pid: Some(pid),
+                field: "process",
+                expected: String::from(if expected.is_some() { "listed" } else { "-" }),
+                actual: String::from(if actual.is_some() { "listed" } else { "-" }),
+            }),
+        }
+    }
+    changes
+}
+
+/// Compares two runs of a simulation iteration by iteration and returns the
+/// first iteration at which they diverge, [`None`] if the logs are equal.
+///
+/// The difference reports the decisions of the iteration, every field that has
+/// changed, including the fields of each process, and the reason that the
+/// process of the previous iteration, the last one on which both runs agree,
+/// has stopped.
+///
+/// * `expected` - the logs of the reference run, like a golden file loaded with [`parse_logs`];
+/// * `actual` - the logs of the run that is checked.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{diff_logs, Program, Simulator};
+/// use std::num::NonZeroUsize;
+///
+/// let program = Program::new().fork(0, Program::new().exec(4)).exec(4);
+/// let expected = Simulator::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), &program);
+/// let actual = Simulator::run(scheduler::round_robin(NonZeroUsize::new(3).unwrap(), 1), &program);
+///
+/// let diff = diff_logs(&expected, &actual).unwrap();
+/// println!("{}", diff);
+/// ```
+pub fn diff_logs(expected: &[Log], actual: &[Log]) -> Option<LogDiff> {
+    let iteration = (0..expected.len().max(actual.len())).find(|iteration| {
+        match (expected.get(*iteration), actual.get(*iteration)) {
+            (Some(expected), Some(actual)) => expected != actual,
+            _ => true,
+        }
+    })?;
+    let changes = match (expected.get(iteration), actual.get(iteration)) {
+        (Some(expected), Some(actual)) => log_changes(expected, actual),
+        _ => Vec::new(),
+    };
+    let cause = iteration
+        .checked_sub(1)
+        .and_then(|previous| expected[previous].stop_reason);
+    Some(LogDiff {
+        iteration,
+        expected: expected.get(iteration).map(|log| log.decision),
+        actual: actual.get(iteration).map(|log| log.decision),
+        changes,
+        cause,
+    })
+}
+
+/// Splits a value written with [`Debug`], like `Fork(0)`, `Success` or
+/// `ForkBurst { priority: 0, burst: 4 }`, into its variant and the values of
+/// its fields.
+fn variant(text: &str) -> Result<(&str, Vec<&str>), String> {
+    let text = text.trim();
+    if let Some((name, fields)) = text.split_once(" { ") {
+        let fields = fields
+            .strip_suffix(" }")
+            .ok_or_else(|| format!("unfinished `{}`", text))?;
+        let values = fields
+            .split(", ")
+            .map(|field| field.split_once(": ").map_or(field, |(_, value)| value))
+            .collect();
+        Ok((name, values))
+    } else if let Some((name, fields)) = text.split_once('(') {
+        let fields = fields
+            .strip_suffix(')')
+            .ok_or_else(|| format!("unfinished `{}`", text))?;
+        Ok((name, fields.split(", ").collect()))
+    } else {
+        Ok((text, Vec::new()))
+    }
+}
+
+/// Parses a number.
+fn number<T: FromStr>(text: &str) -> Result<T, String> {
+    text.trim()
+        .parse::<T>()
+        .map_err(|_| format!("expected a number, found `{}`", text))
+}
+
+/// Parses a PID.
+fn pid(text: &str) -> Result<Pid, String> {
+    let pid = number::<usize>(text)?;
+    if pid == 0 {
+        return Err(String::from("0 is not a PID"));
+    }
+    Ok(Pid::new(pid))
+}
+
+/// Returns the field `index` of a variant.
+fn field<'a>(name: &str, fields: &[&'a str], index: usize) -> Result<&'a str, String> {
+    fields
+        .get(index)
+        .copied()
+        .ok_or_else(|| format!("missing a field of `{}`", name))
+}
+
+/// Parses a decision written by [`SchedulingDecision`]'s `Display`.
+fn decision(text: &str) -> Result<SchedulingDecision, String> {
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+    match words[..] {
+        ["Run", pid_text, "for", timeslice, "slices"] => Ok(SchedulingDecision::Run {
+            pid: pid(pid_text)?,
+            timeslice: number(timeslice)?,
+        }),
+        ["Sleep", "for", time, "slices"] => Ok(SchedulingDecision::Sleep(number(time)?)),
+        _ if text == SchedulingDecision::Deadlock.to_string() => Ok(SchedulingDecision::Deadlock),
+        _ if text == SchedulingDecision::Panic.to_string() => Ok(SchedulingDecision::Panic),
+        _ if text == SchedulingDecision::Done.to_string() => Ok(SchedulingDecision::Done),
+        _ => Err(format!("unknown decision `{}`", text)),
+    }
+}
+
+/// Parses a system call written with [`Debug`].
+fn syscall(text: &str) -> Result<Syscall, String> {
+    let (name, fields) = variant(text)?;
+    let field = |index: usize| field(name, &fields, index);
+    match name {
+        "Fork" => Ok(Syscall::Fork(number(field(0)?)?)),
+        "ForkDeadline" => Ok(Syscall::ForkDeadline {
+            priority: number(field(0)?)?,
+            deadline: number(field(1)?)?,
+            period: number(field(2)?)?,
+        }),
+        "ForkBurst" => Ok(Syscall::ForkBurst {
+            priority: number(field(0)?)?,
+            burst: number(field(1)?)?,
+        }),
+        "Sleep" => Ok(Syscall::Sleep(number(field(0)?)?)),
+        "Wait" => Ok(Syscall::Wait(number(field(0)?)?)),
+        "Signal" => Ok(Syscall::Signal(number(field(0)?)?)),
+        "Exit" => Ok(Syscall::Exit(number(field(0)?)?)),
+        "Kill" => Ok(Syscall::Kill(pid(field(0)?)?)),
+        "Join" => Ok(Syscall::Join(pid(field(0)?)?)),
+        "Lock" => Ok(Syscall::Lock(number(field(0)?)?)),
+        "Unlock" => Ok(Syscall::Unlock(number(field(0)?)?)),
+        "SemWait" => Ok(Syscall::SemWait(number(field(0)?)?)),
+        "SemPost" => Ok(Syscall::SemPost(number(field(0)?)?)),
+        _ => Err(format!("unknown system call `{}`", text)),
+    }
+}
+
+/// Parses the result of a system call written with [`Debug`].
+fn syscall_result(text: &str) -> Result<SyscallResult, String> {
+    let (name, fields) = variant(text)?;
+    match name {
+        "Pid" => Ok(SyscallResult::Pid(pid(field(name, &fields, 0)?)?)),
+        "Success" => Ok(SyscallResult::Success),
+        "NoRunningProcess" => Ok(SyscallResult::NoRunningProcess),
+        "NoSuchProcess" => Ok(SyscallResult::NoSuchProcess),
+        "ExitStatus" => Ok(SyscallResult::ExitStatus(number(field(name, &fields, 0)?)?)),
+        "NotOwner" => Ok(SyscallResult::NotOwner),
+        "Pending" => Ok(SyscallResult::Pending),
+        _ => Err(format!("unknown system call result `{}`", text)),
+    }
+}
+
+/// Parses the line of the reason that a process has stopped, like
+/// `Syscall Fork(0), remaining 2 -> Pid(2)`.
+fn stop_reason(line: &str) -> Result<(StopReason, SyscallResult), String> {
+    let (reason, result) = line
+        .rsplit_once(" -> ")
+        .ok_or_else(|| format!("expected a stop reason, found `{}`", line))?;
+    let reason = match reason.strip_prefix("Syscall ") {
+        Some(reason) => {
+            let (call, remaining) = reason
+                .rsplit_once(", remaining ")
+                .ok_or_else(|| format!("missing the remaining time of `{}`", reason))?;
+            StopReason::Syscall {
+                syscall: syscall(call)?,
+                remaining: number(remaining)?,
+            }
+        }
+        None if reason == "Expired" => StopReason::Expired,
+        None => return Err(format!("unknown stop reason `{}`", reason)),
+    };
+    Ok((reason, syscall_result(result)?))
+}
+
+/// Parses a state written by [`ProcessState`]'s `Display`.
+fn process_state(text: &str) -> Result<ProcessState, String> {
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+    match words[..] {
+        ["READY"] => Ok(ProcessState::Ready),
+        ["RUNNING"] => Ok(ProcessState::Running),
+        ["SLEEP"] => Ok(ProcessState::Waiting { event: None }),
+        ["EVENT", event] => Ok(ProcessState::Waiting {
+            event: Some(number(event)?),
+        }),
+        ["LOCK", lock] => Ok(ProcessState::Blocked {
+            object: SyncObject::Lock(number(lock)?),
+        }),
+        ["SEM", semaphore] => Ok(ProcessState::Blocked {
+            object: SyncObject::Semaphore(number(semaphore)?),
+        }),
+        ["JOIN", pid] => Ok(ProcessState::Blocked {
+            object: SyncObject::Child(Pid::new(number(pid)?)),
+        }),
+        ["ZOMBIE"] => Ok(ProcessState::Zombie),
+        _ => Err(format!("unknown process state `{}`", text)),
+    }
+}
+
+/// Parses a line of the table of processes.
+fn process(line: &str) -> Result<ProcessInfo, String> {
+    // the state is followed by two tabs and the extra details may have tabs
+    let columns = line.splitn(9, '\t').collect::<Vec<&str>>();
+    let [pid_text, state, "", priority, total, syscall, execute, status, extra] = columns[..]
+    else {
+        return Err(format!("expected a process, found `{}`", line));
+    };
+    Ok(ProcessInfo {
+        pid: pid(pid_text)?,
+        state: process_state(state)?,
+        timings: (number(total)?, number(syscall)?, number(execute)?),
+        priority: number(priority)?,
+        extra: String::from(extra),
+        exit_status: match status {
+            "-" => None,
+            status => Some(number(status)?),
+        },
+        ppid: None,
+    })
+}
+
+/// Parses a list of PIDs joined with commas.
+fn pids(text: &str) -> Result<Vec<Pid>, String> {
+    text.split(", ").map(pid).collect()
+}
+
+/// Parses what a process is blocked on, written by [`Blocker`]'s `Display`.
+fn blocker(text: &str) -> Result<Blocker, String> {
+    let words = text.split_whitespace().collect::<Vec<&str>>();
+    match words[..] {
+        ["event", event] => Ok(Blocker::Event(number(event)?)),
+        ["child", child, "to", "exit"] => Ok(Blocker::Child(pid(child)?)),
+        ["lock", lock, "held", "by", owner] => Ok(Blocker::Lock {
+            lock: number(lock)?,
+            owner: pid(owner)?,
+        }),
+        ["semaphore", semaphore] => Ok(Blocker::Semaphore(number(semaphore)?)),
+        _ => Err(format!("unknown blocker `{}`", text)),
+    }
+}
+
+/// Adds a line written by [`Diagnosis`]'s `Display` to the diagnosis.
+fn diagnosis_line(diagnosis: &mut Diagnosis, line: &str) -> Result<(), String> {
+    if let Some(cycle) = line
+        .strip_prefix("processes ")
+        .and_then(|line| line.strip_suffix(" wait for each other"))
+    {
+        diagnosis.cycles.push(pids(cycle)?);
+    } else if let Some(cycle) = line
+        .strip_prefix("process ")
+        .and_then(|line| line.strip_suffix(" waits for itself"))
+    {
+        diagnosis.cycles.push(vec![pid(cycle)?]);
+    } else if let Some((process, rest)) = line
+        .strip_prefix("process ")
+        .and_then(|line| line.split_once(" waits for "))
+    {
+        let (blocked_on, wakers) =
+            if let Some(blocked_on) = rest.strip_suffix(", nothing can wake it up") {
+                (blocked_on, Vec::new())
+            } else {
+                let (blocked_on, wakers) = rest
+                    .split_once(", can be woken up by ")
+                    .ok_or_else(|| format!("unknown deadlock line `{}`", line))?;
+                (blocked_on, pids(wakers)?)
+            };
+        diagnosis.blocked.push(BlockedProcess {
+            pid: pid(process)?,
+            blocker: blocker(blocked_on)?,
+            wakers,
+        });
+    } else {
+        return Err(format!("unknown deadlock line `{}`", line));
+    }
+    Ok(())
+}
+
+/// Parses the lines of an iteration, whose header is on the line `header`.
+fn iteration(header: usize, lines: &[(usize, &str)]) -> Result<Log, ParseError> {
+    let mut lines = lines.iter().copied().peekable();
+    let error = |line: usize, message: String| ParseError { line, message };
+    let Some((first, decision_line)) = lines.next() else {
+        return Err(error(header, String::from("missing the decision")));
+    };
+
+    // with several CPUs, the decision is followed by the table of the CPUs
+    let (cpu, decision, cpus) = match decision_line
+        .strip_prefix("CPU ")
+        .and_then(|line| line.split_once(": "))
+    {
+        Some((cpu, decision_text)) => {
+            let cpu = number(cpu).map_err(|message| error(first, message))?;
+            let decision = decision(decision_text).map_err(|message| error(first, message))?;
+            match lines.next() {
+                Some((_, "CPU\tACTION")) => {}
+                Some((line, text)) => {
+                    return Err(error(line, format!("expected the CPUs, found `{}`", text)))
+                }
+                None => return Err(error(first, String::from("missing the CPUs"))),
+            }
+            let mut cpus = Vec::new();
+            while let Some((line, text)) =
+                lines.next_if(|(_, text)| text.starts_with(|c: char| c.is_ascii_digit()))
+            {
+                let (_, action) = text
+                    .split_once('\t')
+                    .ok_or_else(|| error(line, format!("expected a CPU, found `{}`", text)))?;
+                cpus.push(match action {
+                    "-" => None,
+                    action => Some(self::decision(action).map_err(|message| error(line, message))?),
+                });
+            }
+            (cpu, decision, cpus)
+        }
+        None => {
+            let decision = decision(decision_line).map_err(|message| error(first, message))?;
+            (0, decision, vec![Some(decision)])
+        }
+    };
+
+    let mut events = Vec::new();
+    while let Some((_, text)) = lines.next_if(|(_, text)| text.starts_with("Event: ")) {
+        events.push(String::from(&text["Event: ".len()..]));
+    }
+
+    let mut deadlock = None;
+    while let Some((line, text)) = lines.next_if(|(_, text)| text.starts_with("Deadlock: ")) {
+        let diagnosis = deadlock.get_or_insert(Diagnosis {
+            blocked: Vec::new(),
+            cycles: Vec::new(),
+        });
+        diagnosis_line(diagnosis, &text["Deadlock: ".len()..])
+            .map_err(|message| error(line, message))?;
+    }
+
+    match lines.next() {
+        Some((_, text)) if text.starts_with("PID\tSTATE") => {}
+        Some((line, text)) => {
+            return Err(error(
+                line,
+                format!("expected the processes, found `{}`", text),
+            ))
+        }
+        None => return Err(error(first, String::from("missing the processes"))),
+    }
+    let mut processes = HashMap::new();
+    let mut stop = None;
+    for (line, text) in lines {
+        if stop.is_some() {
+            return Err(error(line, format!("unexpected `{}`", text)));
+        }
+        if text.starts_with(|c: char| c.is_ascii_digit()) {
+            let process = process(text).map_err(|message| error(line, message))?;
+            processes.insert(process.pid, process);
+        } else {
+            stop = Some(stop_reason(text).map_err(|message| error(line, message))?);
+        }
+    }
+
+    Ok(Log {
+        decision,
+        stop_reason: stop,
+        processes,
+        cpu,
+        cpus,
+        events,
+        deadlock,
+        start: 0,
+        end: 0,
+    })
+}
+
+/// Parses the text written by [`crate::format_logs`], like the golden files
+/// of the runner, back into logs.
+///
+/// The text does not have the times of the iterations and the parents of
+/// the processes, so the `start` and `end` of the logs are 0 and the `ppid`
+/// of the processes is [`None`]. The logs of a simulation that are compared
+/// with parsed logs should be parsed from their text as well.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{format_logs, parse_logs, Program, Simulator};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Simulator::run(
+///     scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1),
+///     &Program::new().fork(0, Program::new().exec(3)).exec(3),
+/// );
+///
+/// let parsed = parse_logs(&format_logs(&logs)).unwrap();
+/// assert_eq!(parsed.len(), logs.len());
+/// assert_eq!(parsed[0].decision, logs[0].decision);
+/// ```
+pub fn parse_logs(text: &str) -> Result<Vec<Log>, ParseError> {
+    let mut logs = Vec::new();
+    let mut lines = Vec::<(usize, &str)>::new();
+    let mut header = None;
+    for (index, text) in text.lines().enumerate() {
+        if text.starts_with("===== Iteration: ") {
+            if let Some(header) = header {
+                logs.push(iteration(header, &lines)?);
+            }
+            header = Some(index + 1);
+            lines.clear();
+        } else if !text.is_empty() {
+            if header.is_none() {
+                return Err(ParseError {
+                    line: index + 1,
+                    message: format!("expected an iteration, found `{}`", text),
+                });
+            }
+            lines.push((index + 1, text));
+        }
+    }
+    if let Some(header) = header {
+        logs.push(iteration(header, &lines)?);
+    }
+    Ok(logs)
+}
@@ -0,0 +1,279 @@
+//! The simulation loop shared by the processor engines.
+
+use std::collections::HashMap;
+
+use scheduler::{Blocker, Pid, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult};
+
+use crate::{Diagnosis, Log, ProcessInfo};
+
+/// An action that a process asks the processor to perform.
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum Action {
+    /// Execute one unit of time.
+    Exec,
+
+    /// Send a system call to the scheduler.
+    Syscall(Syscall),
+}
+
+/// The way an engine runs the instructions of the processes.
+pub(crate) trait Executor {
+    /// Runs the process `pid` until it reports its next action, which
+    /// the processor performs at time `time`.
+    fn fetch(&mut self, pid: Pid, time: usize) -> Action;
+
+    /// Returns the result of a system call to the process `pid`.
+    fn complete(&mut self, pid: Pid, syscall: Syscall, result: SyscallResult);
+
+    /// Forgets the process `pid`, which has been killed and will not run again.
+    fn finish(&mut self, pid: Pid);
+
+    /// Returns `true` if the process `pid` can still signal the event or
+    /// post the semaphore that `blocker` waits for.
+    fn wakes(&self, pid: Pid, blocker: Blocker) -> bool;
+}
+
+/// The state of one of the processor's CPUs.
+#[derive(Debug, Default, Copy, Clone)]
+struct Cpu {
+    /// The process running on the CPU.
+    process: Option<Pid>,
+
+    /// The time units left from the timeslice of the running process
+    /// or, if no process is running, from the sleep of the CPU.
+    remaining: usize,
+
+    /// The log entry of the action the CPU is performing.
+    log: Option<usize>,
+
+    /// `true` if the running process has been killed by a process running
+    /// on another CPU. The process finishes when it stops running.
+    killed: bool,
+}
+
+/// The simulation of the processor's CPUs.
+pub(crate) struct Engine<S: Scheduler> {
+    scheduler: S,
+    next: fn(&mut S, usize) -> SchedulingDecision,
+    stop: fn(&mut S, usize, StopReason) -> SyscallResult,
+    cpus: Vec<Cpu>,
+    logs: Vec<Log>,
+    running: bool,
+    time: usize,
+    /// `true` if the logs list the processes after each decision.
+    listed: bool,
+}
+
+impl<S: Scheduler> Engine<S> {
+    /// Creates a new engine with `cpus` CPUs.
+    ///
+    /// * `next` - asks the scheduler for the action of a CPU.
+    /// * `stop` - informs the scheduler that the process running on a CPU has stopped.
+    pub(crate) fn new(
+        scheduler: S,
+        next: fn(&mut S, usize) -> SchedulingDecision,
+        stop: fn(&mut S, usize, StopReason) -> SyscallResult,
+        cpus: usize,
+    ) -> Engine<S> {
+        if cpus == 0 {
+            panic!("The processor needs at least one CPU");
+        }
+        Engine {
+            scheduler,
+            next,
+            stop,
+            cpus: vec![Cpu::default(); cpus],
+            logs: vec![],
+            running: true,
+            time: 0,
+            listed: true,
+        }
+    }
+
+    /// Leaves the processes out of the logs.
+    ///
+    /// Listing the processes after each decision takes a time proportional
+    /// to the number of processes, which makes long simulations of many
+    /// processes slow.
+    pub(crate) fn unlisted(mut self) -> Engine<S> {
+        self.listed = false;
+        self
+    }
+
+    /// Creates the process with PID 1 and returns its PID.
+    pub(crate) fn start(&mut self) -> Pid {
+        let SyscallResult::Pid(pid) = self.stop_process(0, StopReason::syscall(Syscall::Fork(0)))
+        else {
+            panic!("Fork did not return a pid");
+        };
+
+        if pid != 1 {
+            panic!("Scheduler did not return PID 1 for the first process");
+        }
+        pid
+    }
+
+    /// Runs the processes until the scheduler stops the simulation and
+    /// returns the logs.
+    ///
+    /// Time advances one unit at a time, in which every CPU that runs a
+    /// process executes one instruction of that process, in the order
+    /// of the CPUs. When all the CPUs sleep, time advances to the moment
+    /// the first of them wakes up.
+    pub(crate) fn run<E: Executor>(&mut self, executor: &mut E) -> Vec<Log> {
+        while self.running {
+            for cpu in 0..self.cpus.len() {
+                if self.running && self.cpus[cpu].process.is_none() && self.cpus[cpu].remaining == 0
+                {
+                    self.schedule(executor, cpu);
+                }
+            }
+            if !self.running {
+                break;
+            }
+
+            if self.cpus.iter().all(|cpu| cpu.process.is_none()) {
+                // all the CPUs are sleeping, wake up the first one
+                let time = self.cpus.iter().map(|cpu| cpu.remaining).min().unwrap_or(0);
+                for cpu in self.cpus.iter_mut() {
+                    cpu.remaining -= time;
+                }
+                self.time += time;
+            } else {
+                self.time += 1;
+                for cpu in 0..self.cpus.len() {
+                    match self.cpus[cpu].process {
+                        Some(pid) => self.step(executor, cpu, pid),
+                        None => {
+                            self.cpus[cpu].remaining = self.cpus[cpu].remaining.saturating_sub(1)
+                        }
+                    }
+                }
+            }
+        }
+
+        // the processes that were still running stop with the simulation
+        for cpu in self.cpus.iter() {
+            if let (Some(_), Some(log)) = (cpu.process, cpu.log) {
+                self.logs[log].end = self.time;
+            }
+        }
+        std::mem::take(&mut self.logs)
+    }
+
+    /// Asks the scheduler for the next action of the CPU `cpu`.
+    fn schedule<E: Executor>(&mut self, executor: &E, cpu: usize) {
+        let next = (self.next)(&mut self.scheduler, cpu);
+        let mut process_map = HashMap::new();
+        let processes = if self.listed {
+            self.scheduler.list()
+        } else {
+            vec![]
+        };
+        for process in processes {
+            process_map.insert(
+                process.pid(),
+                ProcessInfo::new(
+                    process.pid(),
+                    process.state(),
+                    process.timings(),
+                    process.priority(),
+                    process.extra(),
+                    process.exit_status(),
+                    process.ppid(),
+                ),
+            );
+        }
+        let mut decisions = match self.logs.last() {
+            Some(log) => log.cpus.clone(),
+            None => vec![None; self.cpus.len()],
+        };
+        decisions[cpu] = Some(next);
+        let end = match next {
+            SchedulingDecision::Sleep(time) => self.time + time.get(),
+            _ => self.time,
+        };
+        let mut log = Log::new(next, None, process_map, cpu, decisions, self.time, end);
+        log.events = self.scheduler.events();
+        if next == SchedulingDecision::Deadlock {
+            log.deadlock = Some(Diagnosis::new(self.scheduler.blockers(), |pid, blocker| {
+                executor.wakes(pid, blocker)
+            }));
+        }
+        self.logs.push(log);
+        self.cpus[cpu].log = Some(self.logs.len() - 1);
+        // println!("{}", next);
+        match next {
+            SchedulingDecision::Run { pid, timeslice } => {
+                self.cpus[cpu].process = Some(pid);
+                self.cpus[cpu].remaining = timeslice.into();
+            }
+            SchedulingDecision::Sleep(time) => {
+                println!("SLEEP {time}");
+                self.cpus[cpu].remaining = time.into();
+            }
+            SchedulingDecision::Deadlock => {
+                println!("DEADLOCK");
+                self.running = false;
+            }
+            SchedulingDecision::Panic => {
+                println!("PANIC");
+                self.running = false;
+            }
+            SchedulingDecision::Done => {
+                println!("DONE");
+                self.running = false;
+            }
+        }
+    }
+
+    /// Lets the process running on the CPU `cpu` execute one instruction.
+    fn step<E: Executor>(&mut self, executor: &mut E, cpu: usize, pid: Pid) {
+        // the clock has already moved past the unit of this instruction
+        let action = executor.fetch(pid, self.time - 1);
+
+        self.cpus[cpu].remaining -= 1;
+        match action {
+            Action::Exec => {
+                if self.cpus[cpu].remaining == 0 {
+                    println!("PREEMPTED {}", pid);
+                    self.stop_process(cpu, StopReason::expired());
+                }
+            }
+            Action::Syscall(syscall) => {
+                let mut reason = StopReason::syscall(syscall);
+                reason.set_remaining(self.cpus[cpu].remaining);
+                let result = self.stop_process(cpu, reason);
+                if result != SyscallResult::Pending {
+                    executor.complete(pid, syscall, result);
+                }
+                if let (Syscall::Kill(target), SyscallResult::Success) = (syscall, result) {
+                    match self.cpus.iter_mut().find(|cpu| cpu.process == Some(target)) {
+                        Some(running) => running.killed = true,
+                        None => executor.finish(target),
+                    }
+                }
+            }
+        }
+        if self.cpus[cpu].process.is_none() && std::mem::take(&mut self.cpus[cpu].killed) {
+            executor.finish(pid);
+        }
+
+        // the system calls that have blocked other processes may have completed
+        for (pid, syscall, result) in self.scheduler.completions() {
+            executor.complete(pid, syscall, result);
+        }
+    }
+
+    /// Informs the scheduler that the process running on the CPU `cpu` has stopped.
+    fn stop_process(&mut self, cpu: usize, reason: StopReason) -> SyscallResult {
+        let result = (self.stop)(&mut self.scheduler, cpu, reason);
+        if let Some(log) = self.cpus[cpu].log.and_then(|log| self.logs.get_mut(log)) {
+            log.stop_reason = Some((reason, result));
+            log.end = self.time;
+        }
+        self.cpus[cpu].process = None;
+        self.cpus[cpu].remaining = 0;
+        result
+    }
+}
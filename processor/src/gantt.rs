@@ -0,0 +1,332 @@
+//! Gantt charts of the processes of a simulation.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use scheduler::{Pid, ProcessState, SchedulingDecision};
+
+use crate::Log;
+
+/// The number of time units of a row of the terminal chart, longer
+/// simulations are drawn in several sections.
+const SECTION: usize = 100;
+
+/// The character that draws a process running on a CPU that has no digit.
+const HIGH_CPU: char = '+';
+
+/// The width, in pixels, of a time unit in the SVG chart.
+const UNIT_WIDTH: usize = 10;
+
+/// The height, in pixels, of a row in the SVG chart.
+const ROW_HEIGHT: usize = 20;
+
+/// The width, in pixels, of the labels of the SVG chart.
+const LABEL_WIDTH: usize = 70;
+
+/// What a process does during a unit of time.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Slot {
+    /// The process runs on a CPU.
+    Running(usize),
+
+    /// The process is ready to run.
+    Ready,
+
+    /// The process sleeps.
+    Sleeping,
+
+    /// The process waits for an event.
+    Waiting,
+
+    /// The process is blocked on a lock or a semaphore, or waits for a child to exit.
+    Blocked,
+
+    /// The process has exited and has not been joined yet.
+    Zombie,
+}
+
+impl Slot {
+    /// The slots shown in the legend.
+    const LEGEND: [Slot; 6] = [
+        Slot::Running(0),
+        Slot::Ready,
+        Slot::Sleeping,
+        Slot::Waiting,
+        Slot::Blocked,
+        Slot::Zombie,
+    ];
+
+    /// Returns the name of the slot's state.
+    fn name(&self) -> &'static str {
+        match self {
+            Slot::Running(_) => "running",
+            Slot::Ready => "ready",
+            Slot::Sleeping => "sleeping",
+            Slot::Waiting => "waiting",
+            Slot::Blocked => "blocked",
+            Slot::Zombie => "zombie",
+        }
+    }
+
+    /// Returns the character that draws the slot, the CPU number instead
+    /// of the running character if there are several CPUs.
+    ///
+    /// Only CPUs 0 to 9 have a digit, so that the CPUs cannot be mistaken
+    /// for the letters of the other states.
+    fn glyph(&self, cpus: usize) -> char {
+        match self {
+            Slot::Running(cpu) if cpus > 1 => u32::try_from(*cpu)
+                .ok()
+                .and_then(|cpu| char::from_digit(cpu, 10))
+                .unwrap_or(HIGH_CPU),
+            Slot::Running(_) => '█',
+            Slot::Ready => '░',
+            Slot::Sleeping => 'z',
+            Slot::Waiting => 'w',
+            Slot::Blocked => 'b',
+            Slot::Zombie => '·',
+        }
+    }
+
+    /// Returns the color that draws the slot in an SVG chart.
+    fn color(&self) -> &'static str {
+        match self {
+            Slot::Running(_) => "#2e7d32",
+            Slot::Ready => "#c8e6c9",
+            Slot::Sleeping => "#90caf9",
+            Slot::Waiting => "#ffcc80",
+            Slot::Blocked => "#ef9a9a",
+            Slot::Zombie => "#e0e0e0",
+        }
+    }
+}
+
+/// The slots of every process for each unit of time of a simulation.
+struct Timeline {
+    /// The number of time units of the simulation.
+    duration: usize,
+
+    /// The number of CPUs of the processor.
+    cpus: usize,
+
+    /// The slots of each process, [`None`] while the process does not exist.
+    processes: BTreeMap<Pid, Vec<Option<Slot>>>,
+}
+
+impl Timeline {
+    /// Builds the timeline from the logs.
+    ///
+    /// A process runs during the time of the [`SchedulingDecision::Run`] decisions
+    /// that name it, otherwise it has the state listed by the scheduler at the
+    /// last decision.
+    fn new(logs: &[Log]) -> Timeline {
+        let duration = logs.iter().map(|log| log.end).max().unwrap_or(0);
+        let cpus = logs.first().map_or(1, |log| log.cpus.len());
+        let mut processes = BTreeMap::<Pid, Vec<Option<Slot>>>::new();
+
+        let mut next = 0;
+        for time in 0..duration {
+            while next + 1 < logs.len() && logs[next + 1].start <= time {
+                next += 1;
+            }
+            for (pid, info) in logs[next].processes.iter() {
+                let slot = match info.state {
+                    // a process that does not run on any CPU waits for one
+                    ProcessState::Ready | ProcessState::Running => Slot::Ready,
+                    ProcessState::Waiting { event: None } => Slot::Sleeping,
+                    ProcessState::Waiting { event: Some(_) } => Slot::Waiting,
+                    ProcessState::Blocked { .. } => Slot::Blocked,
+                    ProcessState::Zombie => Slot::Zombie,
+                };
+                processes.entry(*pid).or_insert(vec![None; duration])[time] = Some(slot);
+            }
+        }
+
+        for log in logs.iter() {
+            if let SchedulingDecision::Run { pid, .. } = log.decision {
+                let slots = processes.entry(pid).or_insert(vec![None; duration]);
+                for slot in slots[log.start..log.end].iter_mut() {
+                    *slot = Some(Slot::Running(log.cpu));
+                }
+            }
+        }
+
+        Timeline {
+            duration,
+            cpus,
+            processes,
+        }
+    }
+}
+
+/// Format the [`crate::Processor`]'s logs as a Gantt chart, with a row for each
+/// process and a column for each unit of time.
+///
+/// A running process is drawn with `█`, or with the number of its CPU if
+/// there are several CPUs, CPUs 10 and above being drawn with `+`. Ready processes are drawn with `░`, sleeping
+/// or joining processes with `z`, processes waiting for an event with `w`,
+/// processes blocked on a lock or a semaphore with `b` and zombies with `·`.
+/// Long simulations are split in sections of 100 units of time.
+///
+/// * `logs` - the logs returned by the [`crate::Processor`].
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{format_gantt, Program, Simulator};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Simulator::run(
+///     scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1),
+///     &Program::new().fork(0, Program::new().exec(3)).exec(3),
+/// );
+///
+/// println!("{}", format_gantt(&logs));
+/// ```
+pub fn format_gantt(logs: &[Log]) -> String {
+    let timeline = Timeline::new(logs);
+    let label = |pid: &Pid| format!("PID {} ", pid);
+    let width = timeline
+        .processes
+        .keys()
+        .map(|pid| label(pid).chars().count())
+        .max()
+        .unwrap_or(0);
+
+    let mut s = String::new();
+    for start in (0..timeline.duration).step_by(SECTION) {
+        let end = (start + SECTION).min(timeline.duration);
+        // the time axis, with a mark every 10 units of time
+        let mut axis = String::new();
+        for time in (start..end).step_by(10) {
+            write!(axis, "{:<10}", time).unwrap();
+        }
+        writeln!(s, "{:width$}{}", "", axis.trim_end()).unwrap();
+        for (pid, slots) in timeline.processes.iter() {
+            let row = slots[start..end]
+                .iter()
+                .map(|slot| slot.map_or(' ', |slot| slot.glyph(timeline.cpus)))
+                .collect::<String>();
+            writeln!(s, "{:width$}{}", label(pid), row.trim_end()).unwrap();
+        }
+        writeln!(s).unwrap();
+    }
+
+    let legend = Slot::LEGEND
+        .iter()
+        .map(|slot| match slot {
+            Slot::Running(_) if timeline.cpus > 10 => {
+                format!(
+                    "N running on CPU N  {} running on CPU 10 or above",
+                    HIGH_CPU
+                )
+            }
+            Slot::Running(_) if timeline.cpus > 1 => String::from("N running on CPU N"),
+            slot => format!("{} {}", slot.glyph(1), slot.name()),
+        })
+        .collect::<Vec<String>>();
+    writeln!(s, "{}", legend.join("  ")).unwrap();
+    s
+}
+
+/// Format the [`crate::Processor`]'s logs as the SVG image of the Gantt chart
+/// drawn by [`format_gantt`].
+///
+/// * `logs` - the logs returned by the [`crate::Processor`].
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{format_gantt_svg, Program, Simulator};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Simulator::run(
+///     scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1),
+///     &Program::new().fork(0, Program::new().exec(3)).exec(3),
+/// );
+///
+/// let svg = format_gantt_svg(&logs);
+/// assert!(svg.starts_with("<svg"));
+/// ```
+pub fn format_gantt_svg(logs: &[Log]) -> String {
+    let timeline = Timeline::new(logs);
+    let rows = timeline.processes.len();
+    // the time axis, the processes and the legend
+    let legend_width = Slot::LEGEND.len() * 10 * UNIT_WIDTH;
+    let width = LABEL_WIDTH + ((timeline.duration + 1) * UNIT_WIDTH).max(legend_width);
+    let height = (rows + 3) * ROW_HEIGHT;
+
+    let mut s = String::new();
+    writeln!(
+        s,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         font-family=\"monospace\" font-size=\"12\">"
+    )
+    .unwrap();
+
+    for time in (0..=timeline.duration).step_by(10) {
+        let x = LABEL_WIDTH + time * UNIT_WIDTH;
+        writeln!(
+            s,
+            "<text x=\"{x}\" y=\"{}\">{time}</text>\n\
+             <line x1=\"{x}\" y1=\"{ROW_HEIGHT}\" x2=\"{x}\" y2=\"{}\" stroke=\"#bdbdbd\"/>",
+            ROW_HEIGHT - 5,
+            (rows + 1) * ROW_HEIGHT
+        )
+        .unwrap();
+    }
+
+    for (row, (pid, slots)) in timeline.processes.iter().enumerate() {
+        let y = (row + 1) * ROW_HEIGHT;
+        writeln!(
+            s,
+            "<text x=\"0\" y=\"{}\">PID {pid}</text>",
+            y + ROW_HEIGHT - 5
+        )
+        .unwrap();
+        // consecutive units of time with the same slot are drawn as a single rectangle
+        let mut start = 0;
+        while start < slots.len() {
+            let length = slots[start..]
+                .iter()
+                .take_while(|slot| **slot == slots[start])
+                .count();
+            if let Some(slot) = slots[start] {
+                write!(
+                    s,
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" fill=\"{}\">",
+                    LABEL_WIDTH + start * UNIT_WIDTH,
+                    y + 2,
+                    length * UNIT_WIDTH,
+                    ROW_HEIGHT - 4,
+                    slot.color()
+                )
+                .unwrap();
+                write!(s, "<title>{} {}-{}", slot.name(), start, start + length).unwrap();
+                if let Slot::Running(cpu) = slot {
+                    write!(s, " on CPU {cpu}").unwrap();
+                }
+                writeln!(s, "</title></rect>").unwrap();
+            }
+            start += length;
+        }
+    }
+
+    let y = (rows + 2) * ROW_HEIGHT;
+    for (index, slot) in Slot::LEGEND.iter().enumerate() {
+        let x = LABEL_WIDTH + index * 10 * UNIT_WIDTH;
+        writeln!(
+            s,
+            "<rect x=\"{x}\" y=\"{}\" width=\"{UNIT_WIDTH}\" height=\"{UNIT_WIDTH}\" fill=\"{}\"/>\n\
+             <text x=\"{}\" y=\"{}\">{}</text>",
+            y + 2,
+            slot.color(),
+            x + 2 * UNIT_WIDTH,
+            y + UNIT_WIDTH + 1,
+            slot.name()
+        )
+        .unwrap();
+    }
+    writeln!(s, "</svg>").unwrap();
+    s
+}
@@ -0,0 +1,324 @@
+//! A minimal JSON value, with its writer and its parser.
+
+use std::fmt::{self, Display, Write};
+use std::str::FromStr;
+
+/// A JSON value.
+///
+/// Numbers keep their text, so that they are parsed directly into the
+/// integer type of the field that they are read into.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Json>),
+    /// The members of an object, in the order they are written.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Returns a JSON number.
+    pub(crate) fn number<T: Display>(number: T) -> Json {
+        Json::Number(number.to_string())
+    }
+
+    /// Returns a JSON object with the members `members`.
+    pub(crate) fn object<const N: usize>(members: [(&str, Json); N]) -> Json {
+        Json::Object(
+            members
+                .into_iter()
+                .map(|(name, value)| (String::from(name), value))
+                .collect(),
+        )
+    }
+
+    /// Returns the member `name` of an object.
+    pub(crate) fn get(&self, name: &str) -> Result<&Json, String> {
+        let Json::Object(members) = self else {
+            return Err(format!(
+                "expected an object with `{}`, found {}",
+                name, self
+            ));
+        };
+        members
+            .iter()
+            .find(|(member, _)| member == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| format!("missing `{}`", name))
+    }
+
+    /// Reads an integer.
+    pub(crate) fn to_number<T: FromStr>(&self) -> Result<T, String> {
+        match self {
+            Json::Number(number) => number
+                .parse::<T>()
+                .map_err(|_| format!("{} is out of range", number)),
+            _ => Err(format!("expected a number, found {}", self)),
+        }
+    }
+
+    /// Reads a string.
+    pub(crate) fn to_str(&self) -> Result<&str, String> {
+        match self {
+            Json::String(string) => Ok(string),
+            _ => Err(format!("expected a string, found {}", self)),
+        }
+    }
+
+    /// Reads an array.
+    pub(crate) fn to_array(&self) -> Result<&[Json], String> {
+        match self {
+            Json::Array(values) => Ok(values),
+            _ => Err(format!("expected an array, found {}", self)),
+        }
+    }
+
+    /// Reads a value that may be `null`.
+    pub(crate) fn to_option(&self) -> Option<&Json> {
+        match self {
+            Json::Null => None,
+            value => Some(value),
+        }
+    }
+}
+
+/// Returns `text` as a JSON string.
+pub(crate) fn json_string(text: &str) -> String {
+    let mut s = String::from("\"");
+    for character in text.chars() {
+        match character {
+            '"' => s.push_str("\\\""),
+            '\\' => s.push_str("\\\\"),
+            '\n' => s.push_str("\\n"),
+            character if character.is_control() => {
+                write!(s, "\\u{:04x}", character as u32).unwrap()
+            }
+            character => s.push(character),
+        }
+    }
+    s.push('"');
+    s
+}
+
+/// Writes the value on a single line, without whitespace.
+impl Display for Json {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Json::Null => write!(f, "null"),
+            Json::Bool(value) => write!(f, "{}", value),
+            Json::Number(number) => write!(f, "{}", number),
+            Json::String(string) => write!(f, "{}", json_string(string)),
+            Json::Array(values) => {
+                write!(f, "[")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}", value)?;
+                }
+                write!(f, "]")
+            }
+            Json::Object(members) => {
+                write!(f, "{{")?;
+                for (index, (name, value)) in members.iter().enumerate() {
+                    if index > 0 {
+                        write!(f, ",")?;
+                    }
+                    write!(f, "{}:{}", json_string(name), value)?;
+                }
+                write!(f, "}}")
+            }
+        }
+    }
+}
+
+/// Reads a JSON value from a text.
+struct Reader<'a> {
+    text: &'a str,
+    next: usize,
+}
+
+impl Reader<'_> {
+    /// Returns the next character, without reading it.
+    fn peek(&self) -> Option<char> {
+        self.text[self.next..].chars().next()
+    }
+
+    /// Skips the whitespace before the next character.
+    fn skip_whitespace(&mut self) {
+        while let Some(character) = self.peek() {
+            if !character.is_whitespace() {
+                break;
+            }
+            self.next += character.len_utf8();
+        }
+    }
+
+    /// Reads the next character, which has to be `expected`.
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(character) if character == expected => {
+                self.next += character.len_utf8();
+                Ok(())
+            }
+            Some(character) => Err(format!("expected `{}`, found `{}`", expected, character)),
+            None => Err(format!(
+                "expected `{}`, found the end of the text",
+                expected
+            )),
+        }
+    }
+
+    /// Reads the `word` that starts the text.
+    fn word(&mut self, word: &str, value: Json) -> Result<Json, String> {
+        if self.text[self.next..].starts_with(word) {
+            self.next += word.len();
+            Ok(value)
+        } else {
+            Err(format!("unknown value at column {}", self.next + 1))
+        }
+    }
+
+    /// Reads a value.
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some('n') => self.word("null", Json::Null),
+            Some('t') => self.word("true", Json::Bool(true)),
+            Some('f') => self.word("false", Json::Bool(false)),
+            Some('"') => Ok(Json::String(self.string()?)),
+            Some('[') => {
+                self.expect('[')?;
+                let mut values = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some(']') {
+                    self.next += 1;
+                    return Ok(Json::Array(values));
+                }
+                loop {
+                    values.push(self.value()?);
+                    self.skip_whitespace();
+                    if self.peek() == Some(',') {
+                        self.next += 1;
+                    } else {
+                        self.expect(']')?;
+                        return Ok(Json::Array(values));
+                    }
+                }
+            }
+            Some('{') => {
+                self.expect('{')?;
+                let mut members = Vec::new();
+                self.skip_whitespace();
+                if self.peek() == Some('}') {
+                    self.next += 1;
+                    return Ok(Json::Object(members));
+                }
+                loop {
+                    self.skip_whitespace();
+                    let name = self.string()?;
+                    self.expect(':')?;
+                    members.push((name, self.value()?));
+                    self.skip_whitespace();
+                    if self.peek() == Some(',') {
+                        self.next += 1;
+                    } else {
+                        self.expect('}')?;
+                        return Ok(Json::Object(members));
+                    }
+                }
+            }
+            Some(character) if character == '-' || character.is_ascii_digit() => {
+                let start = self.next;
+                while let Some(character) = self.peek() {
+                    if !(character.is_ascii_digit() || "+-.eE".contains(character)) {
+                        break;
+                    }
+                    self.next += 1;
+                }
+                Ok(Json::Number(String::from(&self.text[start..self.next])))
+            }
+            Some(character) => Err(format!("unexpected `{}`", character)),
+            None => Err(String::from("expected a value, found the end of the text")),
+        }
+    }
+
+    /// Reads the four hexadecimal digits of a `\u` escape.
+    fn code_unit(&mut self) -> Result<u32, String> {
+        let digits = self
+            .text
+            .get(self.next..self.next + 4)
+            .ok_or_else(|| String::from("unfinished escape"))?;
+        self.next += 4;
+        u32::from_str_radix(digits, 16).map_err(|_| format!("invalid escape \\u{}", digits))
+    }
+
+    /// Reads a string.
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut s = String::new();
+        loop {
+            let Some(character) = self.peek() else {
+                return Err(String::from("unfinished string"));
+            };
+            self.next += character.len_utf8();
+            match character {
+                '"' => return Ok(s),
+                '\\' => {
+                    let Some(escape) = self.peek() else {
+                        return Err(String::from("unfinished escape"));
+                    };
+                    self.next += escape.len_utf8();
+                    match escape {
+                        '"' | '\\' | '/' => s.push(escape),
+                        'b' => s.push('\u{8}'),
+                        'f' => s.push('\u{c}'),
+                        'n' => s.push('\n'),
+                        'r' => s.push('\r'),
+                        't' => s.push('\t'),
+                        'u' => {
+                            let mut code = self.code_unit()?;
+                            // characters outside of the basic plane are written as surrogate pairs
+                            if (0xd800..0xdc00).contains(&code) {
+                                self.expect('\\')?;
+                                self.expect('u')?;
+                                let low = self.code_unit()?;
+                                if !(0xdc00..0xe000).contains(&low) {
+                                    return Err(format!(
+                                        "invalid surrogate pair {:x} {:x}",
+                                        code, low
+                                    ));
+                                }
+                                code = 0x10000 + ((code - 0xd800) << 10) + (low - 0xdc00);
+                            }
+                            s.push(
+                                char::from_u32(code)
+                                    .ok_or_else(|| format!("invalid character {:x}", code))?,
+                            );
+                        }
+                        escape => return Err(format!("invalid escape \\{}", escape)),
+                    }
+                }
+                character => s.push(character),
+            }
+        }
+    }
+}
+
+/// Parses a single JSON value.
+impl FromStr for Json {
+    type Err = String;
+
+    fn from_str(text: &str) -> Result<Json, String> {
+        let mut reader = Reader { text, next: 0 };
+        let value = reader.value()?;
+        reader.skip_whitespace();
+        match reader.peek() {
+            Some(character) => Err(format!("unexpected `{}` after the value", character)),
+            None => Ok(value),
+        }
+    }
+}
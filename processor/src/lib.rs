@@ -1,17 +1,53 @@
 //! A processor simulation library
 //!
 //! This is used for simulating scheduler from the [`scheduler`] crate.
+//!
+//! There are two engines that run the simulation:
+//! * the [`Processor`], which runs every process on its own thread
+//!   and describes processes as functions;
+//! * the [`Simulator`], which runs all the processes on a single thread
+//!   and describes processes as [`Program`]s.
 
 use std::collections::HashMap;
 use std::fmt::{self, Display};
-use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
-use std::sync::{Arc, Condvar, Mutex};
-use std::{mem, thread};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+use std::thread;
 
 use scheduler::{
-    Pid, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult,
+    Blocker, Pid, ProcessState, Scheduler, SchedulingDecision, SmpScheduler, StopReason, Syscall,
+    SyscallResult,
 };
 
+mod engine;
+use engine::{Action, Engine, Executor};
+
+mod program;
+pub use program::{Instruction, Program, Simulator};
+
+mod parser;
+pub use parser::ParseError;
+
+mod deadlock;
+pub use deadlock::{BlockedProcess, Diagnosis};
+
+mod gantt;
+pub use gantt::{format_gantt, format_gantt_svg};
+
+mod trace;
+pub use trace::format_chrome_trace;
+
+mod diff;
+pub use diff::{diff_logs, parse_logs, Change, LogDiff};
+
+mod json;
+
+mod records;
+pub use records::{format_logs_csv, format_logs_json, parse_logs_json};
+
+pub mod comparison;
+pub mod metrics;
+
 /// Running iteration log
 #[derive(Debug)]
 pub struct Log {
@@ -24,6 +60,29 @@ pub struct Log {
     /// The list of processes and their corresponding states
     /// returned by the scheduler.
     pub processes: HashMap<Pid, ProcessInfo>,
+
+    /// The CPU that the action was requested for.
+    pub cpu: usize,
+
+    /// The actions that each CPU performs after this iteration, indexed
+    /// by the CPU number. CPUs that have not received any action yet
+    /// are [`None`].
+    pub cpus: Vec<Option<SchedulingDecision>>,
+
+    /// The events reported by the scheduler before requesting the action.
+    pub events: Vec<String>,
+
+    /// The wait-for graph of the processes, if the action is a
+    /// [`SchedulingDecision::Deadlock`].
+    pub deadlock: Option<Diagnosis>,
+
+    /// The time at which the action was requested.
+    pub start: usize,
+
+    /// The time at which the action has ended. For a [`SchedulingDecision::Run`]
+    /// this is the time the process has stopped, for a [`SchedulingDecision::Sleep`]
+    /// the time the CPU has woken up.
+    pub end: usize,
 }
 
 impl Log {
@@ -31,24 +90,104 @@ impl Log {
         decision: SchedulingDecision,
         stop_reason: Option<(StopReason, SyscallResult)>,
         processes: HashMap<Pid, ProcessInfo>,
+        cpu: usize,
+        cpus: Vec<Option<SchedulingDecision>>,
+        start: usize,
+        end: usize,
     ) -> Log {
         Log {
             decision,
             stop_reason,
             processes,
+            cpu,
+            cpus,
+            events: Vec::new(),
+            deadlock: None,
+            start,
+            end,
         }
     }
+
+    /// Writes the process `pid` and, below it, the tree of the processes that it has forked.
+    ///
+    /// * `indent` - the prefix of the lines of the children of `pid`;
+    /// * `branch` - the prefix of the line of `pid`.
+    fn write_tree(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        pid: Pid,
+        indent: &str,
+        branch: &str,
+    ) -> std::fmt::Result {
+        let process = &self.processes[&pid];
+        write!(f, "{}{}", branch, process.pid)?;
+        process.write_columns(f)?;
+        writeln!(f)?;
+        let mut children = self
+            .processes
+            .values()
+            .filter(|child| child.ppid == Some(pid))
+            .map(|child| child.pid)
+            .collect::<Vec<Pid>>();
+        children.sort();
+        for (index, child) in children.iter().enumerate() {
+            let (branch, next) = if index + 1 == children.len() {
+                ("\u{2514}\u{2500}", "  ")
+            } else {
+                ("\u{251c}\u{2500}", "\u{2502} ")
+            };
+            self.write_tree(
+                f,
+                *child,
+                &format!("{}{}", indent, next),
+                &format!("{}{}", indent, branch),
+            )?;
+        }
+        Ok(())
+    }
 }
 
 impl Display for Log {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        writeln!(f, "{}", self.decision).unwrap();
+        if self.cpus.len() > 1 {
+            writeln!(f, "CPU {}: {}", self.cpu, self.decision).unwrap();
+            writeln!(f, "CPU\tACTION").unwrap();
+            for (cpu, decision) in self.cpus.iter().enumerate() {
+                match decision {
+                    Some(decision) => writeln!(f, "{}\t{}", cpu, decision).unwrap(),
+                    None => writeln!(f, "{}\t-", cpu).unwrap(),
+                }
+            }
+        } else {
+            writeln!(f, "{}", self.decision).unwrap();
+        }
+        for event in self.events.iter() {
+            writeln!(f, "Event: {}", event).unwrap();
+        }
+        if let Some(deadlock) = &self.deadlock {
+            write!(f, "{}", deadlock)?;
+        }
         // writeln!(f, "===== Processes =====");
-        writeln!(f, "PID\tSTATE\t\tPRI\tTOTAL\tSYSCALL\tEXECUTE\tEXTRA").unwrap();
+        writeln!(
+            f,
+            "PID\tSTATE\t\tPRI\tTOTAL\tSYSCALL\tEXECUTE\tSTATUS\tEXTRA"
+        )
+        .unwrap();
         let mut pids = self.processes.keys().collect::<Vec<&Pid>>();
         pids.sort();
-        for pid in pids.into_iter() {
-            writeln!(f, "{}", self.processes.get(pid).unwrap()).unwrap();
+        if f.alternate() {
+            // processes whose parent is no longer listed are drawn as roots
+            for pid in pids.into_iter().filter(|pid| {
+                self.processes[pid]
+                    .ppid
+                    .is_none_or(|ppid| !self.processes.contains_key(&ppid))
+            }) {
+                self.write_tree(f, *pid, "", "")?;
+            }
+        } else {
+            for pid in pids.into_iter() {
+                writeln!(f, "{}", self.processes.get(pid).unwrap()).unwrap();
+            }
         }
         if let Some(log) = self.stop_reason {
             writeln!(f, "{} -> {:?}", log.0, (log.1)).unwrap();
@@ -62,6 +201,12 @@ impl PartialEq<Log> for Log {
         self.decision == other.decision
             && self.stop_reason == other.stop_reason
             && self.processes == other.processes
+            && self.cpu == other.cpu
+            && self.cpus == other.cpus
+            && self.events == other.events
+            && self.deadlock == other.deadlock
+            && self.start == other.start
+            && self.end == other.end
     }
 }
 
@@ -82,6 +227,12 @@ pub struct ProcessInfo {
 
     /// Extra details about the process
     pub extra: String,
+
+    /// The exit status of the process, [`None`] if the process has not exited.
+    pub exit_status: Option<i32>,
+
+    /// The PID of the process that has forked the process, [`None`] for the first process.
+    pub ppid: Option<Pid>,
 }
 
 impl ProcessInfo {
@@ -91,6 +242,8 @@ impl ProcessInfo {
         timings: (usize, usize, usize),
         priority: i8,
         extra: String,
+        exit_status: Option<i32>,
+        ppid: Option<Pid>,
     ) -> ProcessInfo {
         ProcessInfo {
             pid,
@@ -98,32 +251,60 @@ impl ProcessInfo {
             timings,
             priority,
             extra,
+            exit_status,
+            ppid,
         }
     }
 }
 
-impl Display for ProcessInfo {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+impl ProcessInfo {
+    /// Writes the columns that follow the PID.
+    fn write_columns(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{}\t{}\t\t{}\t{}\t{}\t{}\t{}",
-            self.pid,
-            self.state,
-            self.priority,
-            self.timings.0,
-            self.timings.1,
-            self.timings.2,
-            self.extra
-        )
+            "\t{}\t\t{}\t{}\t{}\t{}\t",
+            self.state, self.priority, self.timings.0, self.timings.1, self.timings.2
+        )?;
+        match self.exit_status {
+            Some(status) => write!(f, "{}", status)?,
+            None => write!(f, "-")?,
+        }
+        write!(f, "\t{}", self.extra)
+    }
+}
+
+impl Display for ProcessInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.pid)?;
+        self.write_columns(f)
     }
 }
 
+/// The hand-off point between the processor and the process threads.
+///
+/// The processor allows one process at a time to execute its next
+/// instruction and waits until the process reports the instruction.
+#[derive(Default)]
+struct Switch {
+    /// The process allowed to execute its next instruction.
+    current_process: Option<Pid>,
+
+    /// The action reported by the current process.
+    action: Option<Action>,
+
+    /// The time at which the processor performs the action of the current process.
+    time: usize,
+
+    /// The results of the system calls that processes wait for.
+    results: HashMap<Pid, SyscallResult>,
+}
+
 /// The processor simulator.
+///
+/// Every process runs on its own thread.
 pub struct Processor<S: Scheduler + 'static> {
-    scheduler: Arc<Mutex<S>>,
-    current_process: Arc<(Mutex<Option<Pid>>, Condvar)>,
-    remaining: AtomicUsize,
-    logs: Mutex<Vec<Log>>,
+    engine: Mutex<Engine<S>>,
+    switch: (Mutex<Switch>, Condvar),
     running: AtomicBool,
 }
 
@@ -132,7 +313,7 @@ impl<S: Scheduler + 'static> Processor<S> {
     ///
     /// * `scheduler` - the scheduler to use for the simulation.
     /// * `f` - a function with the instructions for the process with
-    ///         PID 1.
+    ///   PID 1.
     ///
     /// ## Example
     ///
@@ -162,127 +343,130 @@ impl<S: Scheduler + 'static> Processor<S> {
     where
         F: FnOnce(&Process<S>) + Send,
     {
+        Processor::simulate(
+            Engine::new(
+                scheduler,
+                |scheduler, _| scheduler.next(),
+                |scheduler, _, reason| scheduler.stop(reason),
+                1,
+            ),
+            f,
+        )
+    }
+
+    fn simulate<F>(mut engine: Engine<S>, f: F) -> Vec<Log>
+    where
+        F: FnOnce(&Process<S>) + Send,
+    {
+        let pid = engine.start();
         let processor = Arc::new(Processor {
-            scheduler: Arc::new(Mutex::new(scheduler)),
-            current_process: Arc::new((Mutex::new(None), Condvar::new())),
-            remaining: AtomicUsize::new(1),
-            logs: Mutex::new(vec![]),
+            engine: Mutex::new(engine),
+            switch: (Mutex::new(Switch::default()), Condvar::new()),
             running: AtomicBool::new(true),
         });
 
-        let SyscallResult::Pid(pid) = processor.scheduler(StopReason::syscall(Syscall::Fork(0))) else {
-            panic!("Fork did not return a pid");
-        };
-
-        if pid != 1 {
-            panic!("Scheduler did not return PID 1 for the first process");
-        }
-
-        let mutex = processor.current_process.clone();
         thread::scope(|s| {
+            let process = Process {
+                pid,
+                processor: processor.clone(),
+            };
             s.spawn(move || {
-                let process = Process {
-                    pid,
-                    mutex,
-                    processor,
-                };
-                process.suspend();
                 f(&process);
-                process.exit();
-                process.processor.get_logs()
-            })
-            .join()
-            .unwrap()
+                process.exit(0);
+            });
+            let logs = processor.engine.lock().unwrap().run(&mut &*processor);
+            processor.stop();
+            logs
         })
     }
 
-    fn exec(&self) -> bool {
-        if self.is_running() {
-            self.remaining.fetch_sub(1, Ordering::Relaxed);
-            self.remaining.load(Ordering::Relaxed) != 0
-        } else {
-            true
-        }
+    fn stop(&self) {
+        self.running.store(false, Ordering::Relaxed);
+        let (switch, condvar) = &self.switch;
+        let _switch = switch.lock().unwrap();
+        condvar.notify_all();
     }
 
-    fn scheduler(&self, mut reason: StopReason) -> SyscallResult {
-        if self.is_running() {
-            self.remaining.fetch_sub(1, Ordering::Relaxed);
-            let mut scheduler = self.scheduler.lock().unwrap();
-            reason.set_remaining(self.remaining.load(Ordering::Relaxed));
-            let result = scheduler.stop(reason);
-            {
-                let mut logs = self.logs.lock().unwrap();
-                let len = logs.len();
-                if len > 0 {
-                    if let Some(log) = logs.get_mut(len - 1) {
-                        log.stop_reason = Some((reason, result));
-                    };
-                }
-            }
+    fn is_running(&self) -> bool {
+        self.running.load(Ordering::Relaxed)
+    }
+}
 
-            let mut current_process = self.current_process.0.lock().unwrap();
-            *current_process = None;
-            while self.is_running() && current_process.is_none() {
-                let next = scheduler.next();
-                let mut process_map = HashMap::new();
-                for process in scheduler.list() {
-                    process_map.insert(
-                        process.pid(),
-                        ProcessInfo::new(
-                            process.pid(),
-                            process.state(),
-                            process.timings(),
-                            process.priority(),
-                            process.extra(),
-                        ),
-                    );
-                }
-                (*self.logs.lock().unwrap()).push(Log::new(next, None, process_map));
-                // println!("{}", next);
-                match next {
-                    SchedulingDecision::Run { pid, timeslice } => {
-                        self.remaining.store(timeslice.into(), Ordering::Relaxed);
-                        *current_process = Some(pid);
-                        self.current_process.1.notify_all();
-                    }
-                    SchedulingDecision::Sleep(time) => {
-                        println!("SLEEP {time}");
-                    }
-                    SchedulingDecision::Deadlock => {
-                        println!("DEADLOCK");
-                        self.stop();
-                    }
-                    SchedulingDecision::Panic => {
-                        println!("PANIC");
-                        self.stop();
-                    }
-                    SchedulingDecision::Done => {
-                        println!("DONE");
-                        self.stop();
-                    }
-                }
+impl<S: Scheduler + 'static> Executor for &Processor<S> {
+    fn fetch(&mut self, pid: Pid, time: usize) -> Action {
+        let (switch, condvar) = &self.switch;
+        let mut switch = switch.lock().unwrap();
+        switch.current_process = Some(pid);
+        switch.time = time;
+        condvar.notify_all();
+        loop {
+            if let Some(action) = switch.action.take() {
+                return action;
             }
-            result
-        } else {
-            SyscallResult::NoRunningProcess
+            switch = condvar.wait(switch).unwrap();
         }
     }
 
-    fn get_logs(&self) -> Vec<Log> {
-        let mut logs = self.logs.lock().unwrap();
-        let mut res = vec![];
-        mem::swap(&mut res, &mut *logs);
-        res
+    fn complete(&mut self, pid: Pid, _syscall: Syscall, result: SyscallResult) {
+        let (switch, condvar) = &self.switch;
+        switch.lock().unwrap().results.insert(pid, result);
+        condvar.notify_all();
     }
 
-    fn stop(&self) {
-        self.running.store(false, Ordering::Relaxed);
-        self.current_process.1.notify_all();
+    fn finish(&mut self, _pid: Pid) {
+        // the thread of a killed process waits until the simulation ends
     }
 
-    fn is_running(&self) -> bool {
-        self.running.load(Ordering::Relaxed)
+    fn wakes(&self, _pid: Pid, _blocker: Blocker) -> bool {
+        // the instructions of a process are hidden in its function,
+        // any other process may signal the event
+        true
+    }
+}
+
+impl<S: SmpScheduler + 'static> Processor<S> {
+    /// Start a new processor simulation with several CPUs.
+    ///
+    /// All the CPUs share the same time. In every time unit, each CPU
+    /// that runs a process executes one instruction of that process.
+    ///
+    /// * `cpus` - the number of CPUs of the processor.
+    /// * `scheduler` - the scheduler to use for the simulation.
+    /// * `f` - a function with the instructions for the process with
+    ///   PID 1.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use scheduler::{Pid, SchedulingDecision};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let timeslice = NonZeroUsize::new(3).unwrap();
+    /// let logs = Processor::run_smp(2, scheduler::smp_round_robin(timeslice, 1), |process| {
+    ///     process.fork(
+    ///         |process| {
+    ///             process.exec();
+    ///             process.exec();
+    ///         },
+    ///         0,
+    ///     );
+    ///     process.exec();
+    ///     process.exec();
+    /// });
+    ///
+    /// // the two processes run at the same time
+    /// assert!(logs.iter().any(|log| log.cpus
+    ///     == [
+    ///         Some(SchedulingDecision::Run { pid: Pid::new(1), timeslice: NonZeroUsize::new(2).unwrap() }),
+    ///         Some(SchedulingDecision::Run { pid: Pid::new(2), timeslice }),
+    ///     ]));
+    /// ```
+    pub fn run_smp<F>(cpus: usize, scheduler: S, f: F) -> Vec<Log>
+    where
+        F: FnOnce(&Process<S>) + Send,
+    {
+        Processor::simulate(Engine::new(scheduler, S::next_cpu, S::stop_cpu, cpus), f)
     }
 }
 
@@ -291,29 +475,72 @@ pub struct Process<S: Scheduler + 'static> {
     /// The PID of the process.
     pub pid: Pid,
     processor: Arc<Processor<S>>,
-    mutex: Arc<(Mutex<Option<Pid>>, Condvar)>,
 }
 
 impl<S: Scheduler + 'static> Process<S> {
-    fn suspend(&self) {
-        let mut wait = self.mutex.0.lock().unwrap();
-        while self.processor.is_running() && *wait != Some(self.pid) {
-            // println!("SUSPENDED {}", self.pid);
-            wait = self.mutex.1.wait(wait).unwrap();
+    /// Waits for the processor to schedule the process and executes
+    /// an instruction, returning the result of the system call.
+    fn execute(&self, action: Action) -> SyscallResult {
+        let (_, condvar) = &self.processor.switch;
+        let mut switch = self.wait_turn();
+        if !self.processor.is_running() {
+            return SyscallResult::NoRunningProcess;
+        }
+        switch.current_process = None;
+        switch.action = Some(action);
+        condvar.notify_all();
+
+        if let Action::Syscall(_) = action {
+            while self.processor.is_running() {
+                if let Some(result) = switch.results.remove(&self.pid) {
+                    return result;
+                }
+                switch = condvar.wait(switch).unwrap();
+            }
+            SyscallResult::NoRunningProcess
+        } else {
+            SyscallResult::Success
         }
-        if self.processor.is_running() {
-            println!("RUNNING {}", self.pid);
+    }
+
+    /// Waits until the processor allows the process to execute its next
+    /// instruction or until the simulation stops.
+    fn wait_turn(&self) -> MutexGuard<'_, Switch> {
+        let (switch, condvar) = &self.processor.switch;
+        let mut switch = switch.lock().unwrap();
+        while self.processor.is_running() && switch.current_process != Some(self.pid) {
+            switch = condvar.wait(switch).unwrap();
         }
+        switch
+    }
+
+    /// Returns the current time of the processor.
+    ///
+    /// This is the time at which the process executes its next instruction.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::Processor;
+    /// use std::num::NonZeroUsize;
+    ///
+    /// Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///     assert_eq!(process.now(), 0);
+    ///     process.exec();
+    ///     process.exec();
+    ///     assert_eq!(process.now(), 2);
+    ///     process.sleep(5);
+    ///     assert_eq!(process.now(), 8);
+    /// });
+    /// ```
+    pub fn now(&self) -> usize {
+        self.wait_turn().time
     }
 
     /// Execute one unit of time.
     pub fn exec(&self) {
         println!("{}: EXEC", self.pid);
-        if !self.processor.exec() {
-            println!("PREEMPTED {}", self.pid);
-            self.processor.scheduler(StopReason::expired());
-            self.suspend();
-        }
+        self.execute(Action::Exec);
     }
 
     /// Send a [`Syscall::Fork`] system call.
@@ -321,26 +548,56 @@ impl<S: Scheduler + 'static> Process<S> {
     where
         F: FnOnce(&Process<S>) + Send + 'static,
     {
-        let SyscallResult::Pid(pid) = self.processor.scheduler(StopReason::syscall(Syscall::Fork(priority))) else {
+        self.spawn(f, Syscall::Fork(priority))
+    }
+
+    /// Send a [`Syscall::ForkDeadline`] system call.
+    ///
+    /// * `deadline` - the amount of time in which each job of the new process has to finish.
+    /// * `period` - the amount of time between the releases of two jobs, `0` for a job
+    ///   every time the process becomes ready.
+    pub fn fork_deadline<F>(&self, f: F, priority: i8, deadline: usize, period: usize) -> Pid
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        self.spawn(
+            f,
+            Syscall::ForkDeadline {
+                priority,
+                deadline,
+                period,
+            },
+        )
+    }
+
+    /// Send a [`Syscall::ForkBurst`] system call.
+    ///
+    /// * `burst` - the expected length of each CPU burst of the new process.
+    pub fn fork_burst<F>(&self, f: F, priority: i8, burst: usize) -> Pid
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        self.spawn(f, Syscall::ForkBurst { priority, burst })
+    }
+
+    /// Sends a fork system call and runs `f` in the new process.
+    fn spawn<F>(&self, f: F, syscall: Syscall) -> Pid
+    where
+        F: FnOnce(&Process<S>) + Send + 'static,
+    {
+        let SyscallResult::Pid(pid) = self.execute(Action::Syscall(syscall)) else {
             panic!("Fork did not return a pid");
         };
 
         println!("{}: FORK {}", self.pid, pid);
 
-        let mutex = self.mutex.clone();
         let processor = self.processor.clone();
 
         thread::spawn(move || {
-            let process = Process {
-                pid,
-                mutex,
-                processor,
-            };
-            process.suspend();
+            let process = Process { pid, processor };
             f(&process);
-            process.exit();
+            process.exit(0);
         });
-        self.suspend();
         pid
     }
 
@@ -349,9 +606,7 @@ impl<S: Scheduler + 'static> Process<S> {
     /// * `event` - the event number to wait for.
     pub fn wait(&self, event: usize) {
         println!("{}: WAIT {}", self.pid, event);
-        self.processor
-            .scheduler(StopReason::syscall(Syscall::Wait(event)));
-        self.suspend();
+        self.execute(Action::Syscall(Syscall::Wait(event)));
     }
 
     /// Send a [`Syscall::Signal`] system call.
@@ -359,9 +614,7 @@ impl<S: Scheduler + 'static> Process<S> {
     /// * `event` - the event number to signal.
     pub fn signal(&self, event: usize) {
         println!("{}: SIGNAL {}", self.pid, event);
-        self.processor
-            .scheduler(StopReason::syscall(Syscall::Signal(event)));
-        self.suspend();
+        self.execute(Action::Syscall(Syscall::Signal(event)));
     }
 
     /// Send a [`Syscall::Sleep`] system call.
@@ -369,14 +622,76 @@ impl<S: Scheduler + 'static> Process<S> {
     /// * `timeslice` - the amout of time to sleep.
     pub fn sleep(&self, timeslice: usize) {
         println!("{}: SLEEP {}", self.pid, timeslice);
-        self.processor
-            .scheduler(StopReason::syscall(Syscall::Sleep(timeslice)));
-        self.suspend();
+        self.execute(Action::Syscall(Syscall::Sleep(timeslice)));
+    }
+
+    /// Send a [`Syscall::Kill`] system call.
+    ///
+    /// * `pid` - the PID of the process to finish.
+    ///
+    /// Returns `false` if there is no process with the PID `pid`.
+    pub fn kill(&self, pid: Pid) -> bool {
+        println!("{}: KILL {}", self.pid, pid);
+        self.execute(Action::Syscall(Syscall::Kill(pid))) == SyscallResult::Success
+    }
+
+    /// Send a [`Syscall::Join`] system call.
+    ///
+    /// * `pid` - the PID of the child process to wait for.
+    ///
+    /// Returns the exit status of the child, or [`None`] if the process has
+    /// no child with the PID `pid`.
+    pub fn join(&self, pid: Pid) -> Option<i32> {
+        println!("{}: JOIN {}", self.pid, pid);
+        match self.execute(Action::Syscall(Syscall::Join(pid))) {
+            SyscallResult::ExitStatus(status) => Some(status),
+            _ => None,
+        }
+    }
+
+    /// Send a [`Syscall::Lock`] system call.
+    ///
+    /// * `lock` - the lock number, the call returns once the process holds the lock.
+    pub fn lock(&self, lock: usize) {
+        println!("{}: LOCK {}", self.pid, lock);
+        self.execute(Action::Syscall(Syscall::Lock(lock)));
+    }
+
+    /// Send a [`Syscall::Unlock`] system call.
+    ///
+    /// * `lock` - the lock number.
+    ///
+    /// Returns `false` if the process does not hold the lock.
+    pub fn unlock(&self, lock: usize) -> bool {
+        println!("{}: UNLOCK {}", self.pid, lock);
+        self.execute(Action::Syscall(Syscall::Unlock(lock))) == SyscallResult::Success
+    }
+
+    /// Send a [`Syscall::SemWait`] system call.
+    ///
+    /// * `semaphore` - the semaphore number, the call returns once the semaphore is decremented.
+    pub fn sem_wait(&self, semaphore: usize) {
+        println!("{}: SEMWAIT {}", self.pid, semaphore);
+        self.execute(Action::Syscall(Syscall::SemWait(semaphore)));
+    }
+
+    /// Send a [`Syscall::SemPost`] system call.
+    ///
+    /// * `semaphore` - the semaphore number.
+    pub fn sem_post(&self, semaphore: usize) {
+        println!("{}: SEMPOST {}", self.pid, semaphore);
+        self.execute(Action::Syscall(Syscall::SemPost(semaphore)));
     }
 
-    fn exit(&self) {
-        println!("{}: EXIT", self.pid);
-        self.processor.scheduler(StopReason::syscall(Syscall::Exit));
+    /// Send a [`Syscall::Exit`] system call.
+    ///
+    /// * `status` - the exit status returned to the parent when it joins the process.
+    ///
+    /// The process is never scheduled again. A process that returns from its
+    /// function exits with the status `0`.
+    pub fn exit(&self, status: i32) {
+        println!("{}: EXIT {}", self.pid, status);
+        self.execute(Action::Syscall(Syscall::Exit(status)));
     }
 }
 
@@ -408,3 +723,34 @@ pub fn format_logs(logs: &[Log]) -> String {
     }
     s
 }
+
+/// Format the [`Processor`]'s logs to a [`String`], drawing the processes
+/// of every iteration as the tree of the processes that have forked them,
+/// like `pstree`.
+///
+/// * `logs` - the logs returned by the [`Processor`].
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::Processor;
+/// use std::num::NonZeroUsize;
+/// use processor::format_logs_tree;
+///
+/// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+///     /* ... */
+/// });
+///
+/// println!("{}", format_logs_tree(&logs));
+/// ```
+pub fn format_logs_tree(logs: &[Log]) -> String {
+    let mut s = String::new();
+    for (iteration, log) in logs.iter().enumerate() {
+        fmt::write(
+            &mut s,
+            format_args!("===== Iteration: {} =====\n{:#}\n", iteration + 1, log),
+        )
+        .unwrap();
+    }
+    s
+}
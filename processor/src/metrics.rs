@@ -0,0 +1,304 @@
+//! Scheduling metrics computed from the logs of a simulation.
+//!
+//! The metrics are used to compare how schedulers handle the same
+//! workload, like the time the processes wait for the processor or
+//! how evenly the processor is shared between them.
+
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use scheduler::{Pid, ProcessState, SchedulingDecision, StopReason, SyscallResult};
+
+use crate::Log;
+
+/// The metrics of a single process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessMetrics {
+    /// The PID of the process.
+    pub pid: Pid,
+
+    /// The time at which the process has been forked.
+    pub arrival: usize,
+
+    /// The time at which the process has run for the first time,
+    /// [`None`] if it has never run.
+    pub first_run: Option<usize>,
+
+    /// The time at which the process has exited or has been killed,
+    /// [`None`] if it has not finished before the end of the simulation.
+    pub completion: Option<usize>,
+
+    /// The amount of time the process has spent on a CPU.
+    pub cpu_time: usize,
+
+    /// The amount of time the process has been ready, waiting for a CPU.
+    pub waiting_time: usize,
+
+    /// The number of times a CPU has switched to the process from
+    /// another process or from sleeping.
+    pub context_switches: usize,
+}
+
+impl ProcessMetrics {
+    /// Returns the time between the fork and the completion of the
+    /// process, [`None`] if the process has not finished.
+    pub fn turnaround_time(&self) -> Option<usize> {
+        self.completion.map(|completion| completion - self.arrival)
+    }
+
+    /// Returns the time between the fork and the first run of the
+    /// process, [`None`] if the process has never run.
+    pub fn response_time(&self) -> Option<usize> {
+        self.first_run.map(|first_run| first_run - self.arrival)
+    }
+
+    /// Returns the fraction of the time since its fork that the process
+    /// has spent on a CPU, until its completion or the end of the simulation.
+    fn cpu_share(&self, end: usize) -> Option<f64> {
+        let lifetime = self.completion.unwrap_or(end) - self.arrival;
+        (lifetime > 0).then(|| self.cpu_time as f64 / lifetime as f64)
+    }
+}
+
+/// The metrics of a simulation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Metrics {
+    /// The metrics of every process, sorted by their PIDs.
+    pub processes: Vec<ProcessMetrics>,
+
+    /// The number of CPUs of the processor.
+    pub cpus: usize,
+
+    /// The time at which the simulation has ended.
+    pub duration: usize,
+
+    /// The amount of time the CPUs have spent running processes.
+    pub busy_time: usize,
+}
+
+impl Metrics {
+    /// Returns the total number of context switches.
+    pub fn context_switches(&self) -> usize {
+        self.processes
+            .iter()
+            .map(|process| process.context_switches)
+            .sum()
+    }
+
+    /// Returns the fraction of the CPUs' time spent running processes.
+    pub fn utilization(&self) -> f64 {
+        if self.duration == 0 {
+            return 0.0;
+        }
+        self.busy_time as f64 / (self.cpus * self.duration) as f64
+    }
+
+    /// Returns the number of processes that have finished per unit of time.
+    pub fn throughput(&self) -> f64 {
+        if self.duration == 0 {
+            return 0.0;
+        }
+        let finished = self
+            .processes
+            .iter()
+            .filter(|process| process.completion.is_some())
+            .count();
+        finished as f64 / self.duration as f64
+    }
+
+    /// Returns the average turnaround time of the processes that have finished.
+    pub fn average_turnaround_time(&self) -> Option<f64> {
+        average(
+            self.processes
+                .iter()
+                .filter_map(ProcessMetrics::turnaround_time),
+        )
+    }
+
+    /// Returns the average waiting time of the processes.
+    pub fn average_waiting_time(&self) -> Option<f64> {
+        average(self.processes.iter().map(|process| process.waiting_time))
+    }
+
+    /// Returns the average response time of the processes that have run.
+    pub fn average_response_time(&self) -> Option<f64> {
+        average(
+            self.processes
+                .iter()
+                .filter_map(ProcessMetrics::response_time),
+        )
+    }
+
+    /// Returns Jain's fairness index of the share of the CPU that each process
+    /// has received while it was alive, between `1 / n` when a single process
+    /// out of `n` has used the CPU and `1` when all of them got the same share.
+    ///
+    /// Returns [`None`] if no process has been alive for at least a unit of time.
+    pub fn fairness(&self) -> Option<f64> {
+        let shares = self
+            .processes
+            .iter()
+            .filter_map(|process| process.cpu_share(self.duration))
+            .collect::<Vec<f64>>();
+        let sum = shares.iter().sum::<f64>();
+        let squares = shares.iter().map(|share| share * share).sum::<f64>();
+        if shares.is_empty() {
+            None
+        } else if squares == 0.0 {
+            // no process has run, they have all been treated the same
+            Some(1.0)
+        } else {
+            Some(sum * sum / (shares.len() as f64 * squares))
+        }
+    }
+}
+
+impl Display for Metrics {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "PID\tARRIVAL\tRESPONSE\tWAITING\tTURNAROUND\tCPU\tSWITCHES"
+        )?;
+        for process in self.processes.iter() {
+            writeln!(
+                f,
+                "{}\t{}\t{}\t\t{}\t{}\t\t{}\t{}",
+                process.pid,
+                process.arrival,
+                optional(process.response_time()),
+                process.waiting_time,
+                optional(process.turnaround_time()),
+                process.cpu_time,
+                process.context_switches
+            )?;
+        }
+        writeln!(
+            f,
+            "Average response {}, waiting {}, turnaround {}",
+            optional_average(self.average_response_time()),
+            optional_average(self.average_waiting_time()),
+            optional_average(self.average_turnaround_time())
+        )?;
+        writeln!(
+            f,
+            "Utilization {:.3}, throughput {:.3}, context switches {}",
+            self.utilization(),
+            self.throughput(),
+            self.context_switches()
+        )?;
+        writeln!(f, "Fairness {}", optional_average(self.fairness()))
+    }
+}
+
+/// Computes the metrics of a simulation from its logs.
+///
+/// The time a process has been ready is taken from the states listed
+/// by the scheduler, which hold until the next scheduling decision.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{metrics, Program, Simulator};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Simulator::run(
+///     scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1),
+///     &Program::new().fork(0, Program::new().exec(3)).exec(3),
+/// );
+///
+/// let metrics = metrics::measure(&logs);
+/// assert_eq!(metrics.processes.len(), 2);
+/// assert_eq!(metrics.utilization(), 1.0);
+/// println!("{}", metrics);
+/// ```
+pub fn measure(logs: &[Log]) -> Metrics {
+    let cpus = logs.first().map_or(1, |log| log.cpus.len());
+    let duration = logs.iter().map(|log| log.end).max().unwrap_or(0);
+    let mut processes = HashMap::<Pid, ProcessMetrics>::new();
+    // the times at which the processes have been forked by the previous decisions
+    let mut arrivals = HashMap::<Pid, usize>::new();
+    let mut last_run = vec![None; cpus];
+    let mut busy_time = 0;
+
+    for (index, log) in logs.iter().enumerate() {
+        // the processes that have finished since the previous decision
+        for process in processes.values_mut() {
+            let finished = log
+                .processes
+                .get(&process.pid)
+                .is_none_or(|info| info.state == ProcessState::Zombie);
+            if finished && process.completion.is_none() {
+                process.completion = Some(log.start);
+            }
+        }
+
+        for pid in log.processes.keys() {
+            processes.entry(*pid).or_insert_with(|| ProcessMetrics {
+                pid: *pid,
+                arrival: arrivals.get(pid).copied().unwrap_or(log.start),
+                first_run: None,
+                completion: None,
+                cpu_time: 0,
+                waiting_time: 0,
+                context_switches: 0,
+            });
+        }
+
+        // the states hold until the next decision
+        let next = logs.get(index + 1).map_or(log.start, |next| next.start);
+        for (pid, info) in log.processes.iter() {
+            if info.state == ProcessState::Ready {
+                if let Some(process) = processes.get_mut(pid) {
+                    process.waiting_time += next - log.start;
+                }
+            }
+        }
+
+        match log.decision {
+            SchedulingDecision::Run { pid, .. } => {
+                if let Some(process) = processes.get_mut(&pid) {
+                    process.first_run.get_or_insert(log.start);
+                    process.cpu_time += log.end - log.start;
+                    if last_run[log.cpu] != Some(pid) {
+                        process.context_switches += 1;
+                    }
+                }
+                last_run[log.cpu] = Some(pid);
+                busy_time += log.end - log.start;
+            }
+            SchedulingDecision::Sleep(_) => last_run[log.cpu] = None,
+            _ => {}
+        }
+
+        // a forked process arrives when the fork returns, a process that has
+        // not been forked by another one when it is first listed
+        if let Some((StopReason::Syscall { .. }, SyscallResult::Pid(child))) = log.stop_reason {
+            arrivals.entry(child).or_insert(log.end);
+        }
+    }
+
+    let mut processes = processes.into_values().collect::<Vec<ProcessMetrics>>();
+    processes.sort_by_key(|process| process.pid);
+    Metrics {
+        processes,
+        cpus,
+        duration,
+        busy_time,
+    }
+}
+
+/// Returns the average of `values`, [`None`] if there are no values.
+fn average<I: Iterator<Item = usize>>(values: I) -> Option<f64> {
+    let (sum, count) = values.fold((0, 0), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum as f64 / count as f64)
+}
+
+/// Formats a value that may be missing.
+fn optional(value: Option<usize>) -> String {
+    value.map_or(String::from("-"), |value| value.to_string())
+}
+
+/// Formats an average that may be missing.
+fn optional_average(value: Option<f64>) -> String {
+    value.map_or(String::from("-"), |value| format!("{:.3}", value))
+}
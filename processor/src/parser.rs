@@ -0,0 +1,318 @@
+//! The text format of [`Program`]s.
+
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use scheduler::Pid;
+
+use crate::{Instruction, Program};
+
+/// An error found while parsing a [`Program`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParseError {
+    /// The line of the error, starting from 1.
+    pub line: usize,
+
+    /// The description of the error.
+    pub message: String,
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A word or a brace of the text, with its line.
+#[derive(Debug, Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    line: usize,
+}
+
+/// Splits the text into tokens, skipping the comments.
+fn tokenize(text: &str) -> Vec<Token<'_>> {
+    let mut tokens = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line_text = line.split('#').next().unwrap_or("");
+        let mut start = None;
+        for (position, character) in line_text.char_indices() {
+            let separator = character.is_whitespace() || character == '{' || character == '}';
+            if separator {
+                if let Some(start) = start.take() {
+                    tokens.push(Token {
+                        text: &line_text[start..position],
+                        line: index + 1,
+                    });
+                }
+                if !character.is_whitespace() {
+                    tokens.push(Token {
+                        text: &line_text[position..position + 1],
+                        line: index + 1,
+                    });
+                }
+            } else if start.is_none() {
+                start = Some(position);
+            }
+        }
+        if let Some(start) = start {
+            tokens.push(Token {
+                text: &line_text[start..],
+                line: index + 1,
+            });
+        }
+    }
+    tokens
+}
+
+/// Builds a program from the tokens of the text.
+struct Parser<'a> {
+    tokens: Vec<Token<'a>>,
+    next: usize,
+}
+
+impl<'a> Parser<'a> {
+    /// Returns an error for the line of the last token that was read.
+    fn error<T>(&self, message: String) -> Result<T, ParseError> {
+        let line = self
+            .tokens
+            .get(self.next.saturating_sub(1))
+            .map_or(1, |token| token.line);
+        Err(ParseError { line, message })
+    }
+
+    /// Returns the next token, without reading it.
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.next).map(|token| token.text)
+    }
+
+    /// Reads the next token.
+    fn token(&mut self, expected: &str) -> Result<&'a str, ParseError> {
+        match self.tokens.get(self.next) {
+            Some(token) => {
+                self.next += 1;
+                Ok(token.text)
+            }
+            None => self.error(format!("expected {}, found the end of the text", expected)),
+        }
+    }
+
+    /// Reads the next token, which has to be `keyword`.
+    fn keyword(&mut self, keyword: &str) -> Result<(), ParseError> {
+        let token = self.token(&format!("`{}`", keyword))?;
+        if token == keyword {
+            Ok(())
+        } else {
+            self.error(format!("expected `{}`, found `{}`", keyword, token))
+        }
+    }
+
+    /// Reads a number.
+    fn number<T: FromStr>(&mut self, expected: &str) -> Result<T, ParseError> {
+        let token = self.token(expected)?;
+        match token.parse::<T>() {
+            Ok(number) => Ok(number),
+            Err(_) => self.error(format!("expected {}, found `{}`", expected, token)),
+        }
+    }
+
+    /// Reads a number if the next token is a number.
+    fn optional_number<T: FromStr>(&mut self) -> Option<T> {
+        let number = self.peek()?.parse::<T>().ok()?;
+        self.next += 1;
+        Some(number)
+    }
+
+    /// Reads a block of instructions between braces.
+    fn block(&mut self) -> Result<Program, ParseError> {
+        self.keyword("{")?;
+        let program = self.instructions()?;
+        self.keyword("}")?;
+        Ok(program)
+    }
+
+    /// Reads instructions until the end of a block or of the text.
+    fn instructions(&mut self) -> Result<Program, ParseError> {
+        let mut program = Program::new();
+        while let Some(token) = self.peek() {
+            if token == "}" {
+                break;
+            }
+            program = self.instruction(program)?;
+        }
+        Ok(program)
+    }
+
+    /// Reads an instruction and adds it to `program`.
+    fn instruction(&mut self, program: Program) -> Result<Program, ParseError> {
+        let program = match self.token("an instruction")? {
+            "exec" => {
+                let time = self.optional_number::<usize>().unwrap_or(1);
+                program.exec(time)
+            }
+            "fork" => {
+                let priority = self.number::<i8>("a priority")?;
+                match self.peek() {
+                    Some("deadline") => {
+                        self.keyword("deadline")?;
+                        let deadline = self.number("a deadline")?;
+                        self.keyword("period")?;
+                        let period = self.number("a period")?;
+                        program.fork_deadline(priority, deadline, period, self.block()?)
+                    }
+                    Some("burst") => {
+                        self.keyword("burst")?;
+                        let burst = self.number("a burst")?;
+                        program.fork_burst(priority, burst, self.block()?)
+                    }
+                    _ => program.fork(priority, self.block()?),
+                }
+            }
+            "wait" => program.wait(self.number("an event")?),
+            "signal" => program.signal(self.number("an event")?),
+            "sleep" => program.sleep(self.number("an amount of time")?),
+            "kill" => program.kill(Pid::new(self.number("a PID")?)),
+            "join" => program.join(Pid::new(self.number("a PID")?)),
+            "lock" => program.lock(self.number("a lock")?),
+            "unlock" => program.unlock(self.number("a lock")?),
+            "sem_wait" => program.sem_wait(self.number("a semaphore")?),
+            "sem_post" => program.sem_post(self.number("a semaphore")?),
+            "repeat" => {
+                let times = self.number("a number of times")?;
+                program.repeat(times, self.block()?)
+            }
+            "exit" => {
+                let status = self.optional_number::<i32>().unwrap_or(0);
+                program.exit(status)
+            }
+            token => return self.error(format!("unknown instruction `{}`", token)),
+        };
+        Ok(program)
+    }
+}
+
+/// Parses a program from its text format.
+///
+/// A program is a list of instructions, separated by whitespace or new lines.
+/// Blocks are written between braces and comments start with `#`.
+///
+/// ```text
+/// # the process with PID 1
+/// exec 4
+/// fork 0 {
+///     exec 2
+///     wait 1
+/// }
+/// sleep 10
+/// signal 1
+/// join 2
+/// ```
+///
+/// The instructions are:
+/// * `exec` or `exec N` - executes for one or `N` units of time;
+/// * `fork PRIORITY { ... }` - forks a process that runs the block;
+/// * `fork PRIORITY deadline D period P { ... }` - forks a periodic process;
+/// * `fork PRIORITY burst B { ... }` - forks a process with a burst hint;
+/// * `wait E`, `signal E` - waits for or signals the event `E`;
+/// * `sleep N` - sleeps for `N` units of time;
+/// * `kill PID`, `join PID` - kills or joins a process;
+/// * `lock L`, `unlock L` - acquires or releases the lock `L`;
+/// * `sem_wait S`, `sem_post S` - waits for or posts the semaphore `S`;
+/// * `repeat N { ... }` - runs the block `N` times;
+/// * `exit` or `exit STATUS` - exits with the status 0 or `STATUS`.
+impl FromStr for Program {
+    type Err = ParseError;
+
+    fn from_str(text: &str) -> Result<Program, ParseError> {
+        let mut parser = Parser {
+            tokens: tokenize(text),
+            next: 0,
+        };
+        let program = parser.instructions()?;
+        match parser.peek() {
+            Some(token) => {
+                parser.next += 1;
+                parser.error(format!("unexpected `{}`", token))
+            }
+            None => Ok(program),
+        }
+    }
+}
+
+impl Program {
+    /// Writes the instructions, each on its own line, with blocks indented by `indent`.
+    fn write(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        let instructions = self.instructions();
+        let mut index = 0;
+        while index < instructions.len() {
+            write!(f, "{:indent$}", "")?;
+            match &instructions[index] {
+                Instruction::Exec => {
+                    let time = instructions[index..]
+                        .iter()
+                        .take_while(|instruction| **instruction == Instruction::Exec)
+                        .count();
+                    index += time - 1;
+                    writeln!(f, "exec {}", time)?;
+                }
+                Instruction::Fork { priority, program } => {
+                    writeln!(f, "fork {} {{", priority)?;
+                    program.write_block(f, indent)?;
+                }
+                Instruction::ForkDeadline {
+                    priority,
+                    deadline,
+                    period,
+                    program,
+                } => {
+                    writeln!(
+                        f,
+                        "fork {} deadline {} period {} {{",
+                        priority, deadline, period
+                    )?;
+                    program.write_block(f, indent)?;
+                }
+                Instruction::ForkBurst {
+                    priority,
+                    burst,
+                    program,
+                } => {
+                    writeln!(f, "fork {} burst {} {{", priority, burst)?;
+                    program.write_block(f, indent)?;
+                }
+                Instruction::Wait(event) => writeln!(f, "wait {}", event)?,
+                Instruction::Signal(event) => writeln!(f, "signal {}", event)?,
+                Instruction::Sleep(time) => writeln!(f, "sleep {}", time)?,
+                Instruction::Kill(pid) => writeln!(f, "kill {}", pid)?,
+                Instruction::Join(pid) => writeln!(f, "join {}", pid)?,
+                Instruction::Lock(lock) => writeln!(f, "lock {}", lock)?,
+                Instruction::Unlock(lock) => writeln!(f, "unlock {}", lock)?,
+                Instruction::SemWait(semaphore) => writeln!(f, "sem_wait {}", semaphore)?,
+                Instruction::SemPost(semaphore) => writeln!(f, "sem_post {}", semaphore)?,
+                Instruction::Repeat { times, program } => {
+                    writeln!(f, "repeat {} {{", times)?;
+                    program.write_block(f, indent)?;
+                }
+                Instruction::Exit(status) => writeln!(f, "exit {}", status)?,
+            }
+            index += 1;
+        }
+        Ok(())
+    }
+
+    /// Writes the instructions of a block and its closing brace.
+    fn write_block(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        self.write(f, indent + 4)?;
+        writeln!(f, "{:indent$}}}", "")
+    }
+}
+
+/// Writes the program in the format that it is parsed from, so that
+/// `program.to_string().parse()` returns the same program.
+impl Display for Program {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.write(f, 0)
+    }
+}
@@ -0,0 +1,558 @@
+//! Process bodies written as lists of instructions.
+
+use std::collections::HashMap;
+
+use scheduler::{Blocker, Pid, Scheduler, SmpScheduler, Syscall, SyscallResult};
+
+use crate::engine::{Action, Engine, Executor};
+use crate::{Log, Process};
+
+/// An instruction of a [`Program`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Instruction {
+    /// Execute one unit of time.
+    Exec,
+
+    /// Send a [`Syscall::Fork`] system call, the new process runs `program`.
+    Fork {
+        /// The priority of the new process.
+        priority: i8,
+
+        /// The instructions of the new process.
+        program: Program,
+    },
+
+    /// Send a [`Syscall::ForkDeadline`] system call, the new process runs `program`.
+    ForkDeadline {
+        /// The priority of the new process.
+        priority: i8,
+
+        /// The amount of time in which each job of the new process has to finish.
+        deadline: usize,
+
+        /// The amount of time between the releases of two jobs.
+        period: usize,
+
+        /// The instructions of the new process.
+        program: Program,
+    },
+
+    /// Send a [`Syscall::ForkBurst`] system call, the new process runs `program`.
+    ForkBurst {
+        /// The priority of the new process.
+        priority: i8,
+
+        /// The expected length of each CPU burst of the new process.
+        burst: usize,
+
+        /// The instructions of the new process.
+        program: Program,
+    },
+
+    /// Send a [`Syscall::Wait`] system call for an event.
+    Wait(usize),
+
+    /// Send a [`Syscall::Signal`] system call for an event.
+    Signal(usize),
+
+    /// Send a [`Syscall::Sleep`] system call for an amount of time.
+    Sleep(usize),
+
+    /// Send a [`Syscall::Kill`] system call for a process.
+    Kill(Pid),
+
+    /// Send a [`Syscall::Join`] system call for a child process.
+    Join(Pid),
+
+    /// Send a [`Syscall::Lock`] system call for a lock.
+    Lock(usize),
+
+    /// Send a [`Syscall::Unlock`] system call for a lock.
+    Unlock(usize),
+
+    /// Send a [`Syscall::SemWait`] system call for a semaphore.
+    SemWait(usize),
+
+    /// Send a [`Syscall::SemPost`] system call for a semaphore.
+    SemPost(usize),
+
+    /// Run the instructions of `program` several times.
+    Repeat {
+        /// The number of times to run the instructions.
+        times: usize,
+
+        /// The instructions to run.
+        program: Program,
+    },
+
+    /// Stop the process with an exit status, the instructions that follow are not executed.
+    Exit(i32),
+}
+
+/// The instructions of a process.
+///
+/// A program can be run by a [`Process`] of the [`crate::Processor`], or
+/// by the [`Simulator`], which runs all the processes on a single thread.
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::Program;
+///
+/// let program = Program::new()
+///     .repeat(
+///         2,
+///         Program::new().fork(0, Program::new().exec(3).wait(1)),
+///     )
+///     .sleep(10)
+///     .signal(1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Program {
+    instructions: Vec<Instruction>,
+}
+
+impl Program {
+    /// Creates a program without any instructions.
+    pub fn new() -> Program {
+        Program {
+            instructions: vec![],
+        }
+    }
+
+    /// Returns the instructions of the program.
+    pub fn instructions(&self) -> &[Instruction] {
+        &self.instructions
+    }
+
+    /// Adds an instruction at the end of the program.
+    pub fn push(mut self, instruction: Instruction) -> Program {
+        self.instructions.push(instruction);
+        self
+    }
+
+    /// Adds `time` [`Instruction::Exec`] instructions.
+    pub fn exec(mut self, time: usize) -> Program {
+        self.instructions
+            .extend(std::iter::repeat_n(Instruction::Exec, time));
+        self
+    }
+
+    /// Adds an [`Instruction::Fork`] instruction.
+    pub fn fork(self, priority: i8, program: Program) -> Program {
+        self.push(Instruction::Fork { priority, program })
+    }
+
+    /// Adds an [`Instruction::ForkDeadline`] instruction.
+    pub fn fork_deadline(
+        self,
+        priority: i8,
+        deadline: usize,
+        period: usize,
+        program: Program,
+    ) -> Program {
+        self.push(Instruction::ForkDeadline {
+            priority,
+            deadline,
+            period,
+            program,
+        })
+    }
+
+    /// Adds an [`Instruction::ForkBurst`] instruction.
+    pub fn fork_burst(self, priority: i8, burst: usize, program: Program) -> Program {
+        self.push(Instruction::ForkBurst {
+            priority,
+            burst,
+            program,
+        })
+    }
+
+    /// Adds an [`Instruction::Wait`] instruction.
+    pub fn wait(self, event: usize) -> Program {
+        self.push(Instruction::Wait(event))
+    }
+
+    /// Adds an [`Instruction::Signal`] instruction.
+    pub fn signal(self, event: usize) -> Program {
+        self.push(Instruction::Signal(event))
+    }
+
+    /// Adds an [`Instruction::Sleep`] instruction.
+    pub fn sleep(self, time: usize) -> Program {
+        self.push(Instruction::Sleep(time))
+    }
+
+    /// Adds an [`Instruction::Kill`] instruction.
+    pub fn kill(self, pid: Pid) -> Program {
+        self.push(Instruction::Kill(pid))
+    }
+
+    /// Adds an [`Instruction::Join`] instruction.
+    pub fn join(self, pid: Pid) -> Program {
+        self.push(Instruction::Join(pid))
+    }
+
+    /// Adds an [`Instruction::Lock`] instruction.
+    pub fn lock(self, lock: usize) -> Program {
+        self.push(Instruction::Lock(lock))
+    }
+
+    /// Adds an [`Instruction::Unlock`] instruction.
+    pub fn unlock(self, lock: usize) -> Program {
+        self.push(Instruction::Unlock(lock))
+    }
+
+    /// Adds an [`Instruction::SemWait`] instruction.
+    pub fn sem_wait(self, semaphore: usize) -> Program {
+        self.push(Instruction::SemWait(semaphore))
+    }
+
+    /// Adds an [`Instruction::SemPost`] instruction.
+    pub fn sem_post(self, semaphore: usize) -> Program {
+        self.push(Instruction::SemPost(semaphore))
+    }
+
+    /// Adds an [`Instruction::Repeat`] instruction.
+    pub fn repeat(self, times: usize, program: Program) -> Program {
+        self.push(Instruction::Repeat { times, program })
+    }
+
+    /// Adds an [`Instruction::Exit`] instruction.
+    pub fn exit(self, status: i32) -> Program {
+        self.push(Instruction::Exit(status))
+    }
+
+    /// Runs the program's instructions with a [`Process`] of the [`crate::Processor`].
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::{Processor, Program};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let program = Program::new().fork(0, Program::new().exec(5)).exec(10);
+    /// let logs = Processor::run(scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+    ///     program.run(process);
+    /// });
+    /// ```
+    pub fn run<S: Scheduler + 'static>(&self, process: &Process<S>) {
+        self.run_instructions(process);
+    }
+
+    /// Runs the instructions and returns `false` if the process has exited.
+    fn run_instructions<S: Scheduler + 'static>(&self, process: &Process<S>) -> bool {
+        for instruction in self.instructions.iter() {
+            match instruction {
+                Instruction::Exec => process.exec(),
+                Instruction::Fork { priority, program } => {
+                    let program = program.clone();
+                    process.fork(move |process| program.run(process), *priority);
+                }
+                Instruction::ForkDeadline {
+                    priority,
+                    deadline,
+                    period,
+                    program,
+                } => {
+                    let program = program.clone();
+                    process.fork_deadline(
+                        move |process| program.run(process),
+                        *priority,
+                        *deadline,
+                        *period,
+                    );
+                }
+                Instruction::ForkBurst {
+                    priority,
+                    burst,
+                    program,
+                } => {
+                    let program = program.clone();
+                    process.fork_burst(move |process| program.run(process), *priority, *burst);
+                }
+                Instruction::Wait(event) => process.wait(*event),
+                Instruction::Signal(event) => process.signal(*event),
+                Instruction::Sleep(time) => process.sleep(*time),
+                Instruction::Kill(pid) => {
+                    process.kill(*pid);
+                }
+                Instruction::Join(pid) => {
+                    process.join(*pid);
+                }
+                Instruction::Lock(lock) => process.lock(*lock),
+                Instruction::Unlock(lock) => {
+                    process.unlock(*lock);
+                }
+                Instruction::SemWait(semaphore) => process.sem_wait(*semaphore),
+                Instruction::SemPost(semaphore) => process.sem_post(*semaphore),
+                Instruction::Repeat { times, program } => {
+                    for _ in 0..*times {
+                        if !program.run_instructions(process) {
+                            return false;
+                        }
+                    }
+                }
+                Instruction::Exit(status) => {
+                    process.exit(*status);
+                    return false;
+                }
+            }
+        }
+        true
+    }
+}
+
+/// The position of a process in the instructions of a program.
+struct Frame<'a> {
+    /// The instructions that the process runs.
+    instructions: &'a [Instruction],
+
+    /// The next instruction to run.
+    next: usize,
+
+    /// The number of times the instructions still have to be run.
+    times: usize,
+}
+
+/// The processes that run programs, indexed by their PID.
+struct Programs<'a> {
+    /// The stack of instructions that each process runs.
+    processes: HashMap<Pid, Vec<Frame<'a>>>,
+
+    /// The program of the process that has sent a fork system call.
+    forks: HashMap<Pid, &'a Program>,
+}
+
+impl<'a> Programs<'a> {
+    fn new(pid: Pid, program: &'a Program) -> Programs<'a> {
+        let mut programs = Programs {
+            processes: HashMap::new(),
+            forks: HashMap::new(),
+        };
+        programs.start(pid, program);
+        programs
+    }
+
+    /// Starts running `program` in the process `pid`.
+    fn start(&mut self, pid: Pid, program: &'a Program) {
+        self.processes.insert(
+            pid,
+            vec![Frame {
+                instructions: program.instructions(),
+                next: 0,
+                times: 1,
+            }],
+        );
+    }
+}
+
+impl<'a> Executor for Programs<'a> {
+    fn fetch(&mut self, pid: Pid, _time: usize) -> Action {
+        // a process without instructions exits, like one that has run all of them
+        let Some(frames) = self.processes.get_mut(&pid) else {
+            return Action::Syscall(Syscall::Exit(0));
+        };
+        while let Some(frame) = frames.last_mut() {
+            let Some(instruction) = frame.instructions.get(frame.next) else {
+                frame.times -= 1;
+                frame.next = 0;
+                if frame.times == 0 {
+                    frames.pop();
+                }
+                continue;
+            };
+            frame.next += 1;
+            match instruction {
+                Instruction::Exec => return Action::Exec,
+                Instruction::Fork { priority, program } => {
+                    self.forks.insert(pid, program);
+                    return Action::Syscall(Syscall::Fork(*priority));
+                }
+                Instruction::ForkDeadline {
+                    priority,
+                    deadline,
+                    period,
+                    program,
+                } => {
+                    self.forks.insert(pid, program);
+                    return Action::Syscall(Syscall::ForkDeadline {
+                        priority: *priority,
+                        deadline: *deadline,
+                        period: *period,
+                    });
+                }
+                Instruction::ForkBurst {
+                    priority,
+                    burst,
+                    program,
+                } => {
+                    self.forks.insert(pid, program);
+                    return Action::Syscall(Syscall::ForkBurst {
+                        priority: *priority,
+                        burst: *burst,
+                    });
+                }
+                Instruction::Wait(event) => return Action::Syscall(Syscall::Wait(*event)),
+                Instruction::Signal(event) => return Action::Syscall(Syscall::Signal(*event)),
+                Instruction::Sleep(time) => return Action::Syscall(Syscall::Sleep(*time)),
+                Instruction::Kill(pid) => return Action::Syscall(Syscall::Kill(*pid)),
+                Instruction::Join(pid) => return Action::Syscall(Syscall::Join(*pid)),
+                Instruction::Lock(lock) => return Action::Syscall(Syscall::Lock(*lock)),
+                Instruction::Unlock(lock) => return Action::Syscall(Syscall::Unlock(*lock)),
+                Instruction::SemWait(semaphore) => {
+                    return Action::Syscall(Syscall::SemWait(*semaphore))
+                }
+                Instruction::SemPost(semaphore) => {
+                    return Action::Syscall(Syscall::SemPost(*semaphore))
+                }
+                Instruction::Repeat { times, program } => {
+                    if *times > 0 {
+                        frames.push(Frame {
+                            instructions: program.instructions(),
+                            next: 0,
+                            times: *times,
+                        });
+                    }
+                }
+                Instruction::Exit(status) => {
+                    frames.clear();
+                    return Action::Syscall(Syscall::Exit(*status));
+                }
+            }
+        }
+        Action::Syscall(Syscall::Exit(0))
+    }
+
+    fn complete(&mut self, pid: Pid, syscall: Syscall, result: SyscallResult) {
+        match (syscall, result) {
+            (
+                Syscall::Fork(_) | Syscall::ForkDeadline { .. } | Syscall::ForkBurst { .. },
+                SyscallResult::Pid(child),
+            ) => {
+                if let Some(program) = self.forks.remove(&pid) {
+                    self.start(child, program);
+                }
+            }
+            (Syscall::Exit(_), _) => {
+                self.finish(pid);
+            }
+            _ => {}
+        }
+    }
+
+    fn finish(&mut self, pid: Pid) {
+        self.processes.remove(&pid);
+        self.forks.remove(&pid);
+    }
+
+    fn wakes(&self, pid: Pid, blocker: Blocker) -> bool {
+        let frames = self.processes.get(&pid).map_or(&[][..], Vec::as_slice);
+        frames.iter().any(|frame| {
+            // a frame that runs again starts over from its first instruction
+            let next = if frame.times > 1 { 0 } else { frame.next };
+            wakes(&frame.instructions[next..], blocker)
+        })
+    }
+}
+
+/// Returns `true` if `instructions` signal the event or post the semaphore
+/// that `blocker` waits for. The programs of the forked processes are not
+/// searched, they start only if the process runs again.
+fn wakes(instructions: &[Instruction], blocker: Blocker) -> bool {
+    instructions
+        .iter()
+        .any(|instruction| match (instruction, blocker) {
+            (Instruction::Signal(event), Blocker::Event(blocked_on)) => *event == blocked_on,
+            (Instruction::SemPost(semaphore), Blocker::Semaphore(blocked_on)) => {
+                *semaphore == blocked_on
+            }
+            (Instruction::Repeat { times, program }, _) => {
+                *times > 0 && wakes(program.instructions(), blocker)
+            }
+            _ => false,
+        })
+}
+
+/// A processor simulator that runs all the processes on a single thread.
+///
+/// The processes run [`Program`]s instead of functions. For the same
+/// program, the logs are identical to the ones of the [`crate::Processor`].
+pub struct Simulator;
+
+impl Simulator {
+    /// Start a new processor simulation.
+    ///
+    /// * `scheduler` - the scheduler to use for the simulation.
+    /// * `program` - the instructions for the process with PID 1.
+    ///
+    /// ## Example
+    ///
+    /// ```rust
+    /// use processor::{Processor, Program, Simulator};
+    /// use std::num::NonZeroUsize;
+    ///
+    /// let program = Program::new()
+    ///     .fork(0, Program::new().exec(2).wait(1))
+    ///     .sleep(10)
+    ///     .signal(1)
+    ///     .exec(1);
+    ///
+    /// let timeslice = NonZeroUsize::new(2).unwrap();
+    /// let logs = Simulator::run(scheduler::round_robin(timeslice, 1), &program);
+    /// let threads = Processor::run(scheduler::round_robin(timeslice, 1), |process| {
+    ///     program.run(process)
+    /// });
+    /// assert_eq!(logs, threads);
+    /// ```
+    pub fn run<S: Scheduler>(scheduler: S, program: &Program) -> Vec<Log> {
+        Simulator::simulate(
+            Engine::new(
+                scheduler,
+                |scheduler, _| scheduler.next(),
+                |scheduler, _, reason| scheduler.stop(reason),
+                1,
+            ),
+            program,
+        )
+    }
+
+    /// Start a new processor simulation whose logs do not list the processes.
+    ///
+    /// The logs are the ones of [`Simulator::run`] with empty
+    /// [`Log::processes`]. The scheduler's [`Scheduler::list`] is never
+    /// called, which saves copying the table of processes at every decision
+    /// in simulations of many processes.
+    ///
+    /// * `scheduler` - the scheduler to use for the simulation.
+    /// * `program` - the instructions for the process with PID 1.
+    pub fn run_unlisted<S: Scheduler>(scheduler: S, program: &Program) -> Vec<Log> {
+        Simulator::simulate(
+            Engine::new(
+                scheduler,
+                |scheduler, _| scheduler.next(),
+                |scheduler, _, reason| scheduler.stop(reason),
+                1,
+            )
+            .unlisted(),
+            program,
+        )
+    }
+
+    /// Start a new processor simulation with several CPUs.
+    ///
+    /// * `cpus` - the number of CPUs of the processor.
+    /// * `scheduler` - the scheduler to use for the simulation.
+    /// * `program` - the instructions for the process with PID 1.
+    pub fn run_smp<S: SmpScheduler>(cpus: usize, scheduler: S, program: &Program) -> Vec<Log> {
+        Simulator::simulate(
+            Engine::new(scheduler, S::next_cpu, S::stop_cpu, cpus),
+            program,
+        )
+    }
+
+    fn simulate<S: Scheduler>(mut engine: Engine<S>, program: &Program) -> Vec<Log> {
+        let pid = engine.start();
+        engine.run(&mut Programs::new(pid, program))
+    }
+}
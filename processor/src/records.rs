@@ -0,0 +1,655 @@
+//! Machine-readable formats of the logs, JSON Lines and CSV.
+//!
+//! The JSON Lines format keeps every field of the logs and can be
+//! loaded back, while the CSV format is a flat table meant for analysis.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::num::NonZeroUsize;
+
+use scheduler::{
+    Blocker, Pid, ProcessState, SchedulingDecision, StopReason, SyncObject, Syscall, SyscallResult,
+};
+
+use crate::json::Json;
+use crate::{BlockedProcess, Diagnosis, Log, ParseError, ProcessInfo};
+
+/// A value that is written to and read from JSON.
+trait Record: Sized {
+    /// Returns the JSON value of the record.
+    fn to_json(&self) -> Json;
+
+    /// Reads the record from its JSON value.
+    fn from_json(json: &Json) -> Result<Self, String>;
+}
+
+/// Returns a JSON object whose `type` member names the variant of an enum.
+fn tagged<const N: usize>(tag: &str, members: [(&str, Json); N]) -> Json {
+    let mut object = vec![(String::from("type"), Json::String(String::from(tag)))];
+    object.extend(
+        members
+            .into_iter()
+            .map(|(name, value)| (String::from(name), value)),
+    );
+    Json::Object(object)
+}
+
+/// Returns the variant named by the `type` member of an object.
+fn tag(json: &Json) -> Result<&str, String> {
+    json.get("type")?.to_str()
+}
+
+/// Returns an error for an unknown variant of `name`.
+fn unknown<T>(name: &str, tag: &str) -> Result<T, String> {
+    Err(format!("unknown {} `{}`", name, tag))
+}
+
+impl<T: Record> Record for Option<T> {
+    fn to_json(&self) -> Json {
+        match self {
+            Some(value) => value.to_json(),
+            None => Json::Null,
+        }
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        json.to_option().map(T::from_json).transpose()
+    }
+}
+
+impl<T: Record> Record for Vec<T> {
+    fn to_json(&self) -> Json {
+        Json::Array(self.iter().map(Record::to_json).collect())
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        json.to_array()?.iter().map(T::from_json).collect()
+    }
+}
+
+impl Record for String {
+    fn to_json(&self) -> Json {
+        Json::String(self.clone())
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        json.to_str().map(String::from)
+    }
+}
+
+impl Record for Pid {
+    fn to_json(&self) -> Json {
+        Json::number(self)
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let pid = json.to_number::<NonZeroUsize>()?;
+        Ok(Pid::new(pid.get()))
+    }
+}
+
+impl Record for SchedulingDecision {
+    fn to_json(&self) -> Json {
+        match self {
+            SchedulingDecision::Run { pid, timeslice } => tagged(
+                "Run",
+                [
+                    ("pid", pid.to_json()),
+                    ("timeslice", Json::number(timeslice)),
+                ],
+            ),
+            SchedulingDecision::Sleep(time) => tagged("Sleep", [("time", Json::number(time))]),
+            SchedulingDecision::Deadlock => tagged("Deadlock", []),
+            SchedulingDecision::Panic => tagged("Panic", []),
+            SchedulingDecision::Done => tagged("Done", []),
+        }
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match tag(json)? {
+            "Run" => Ok(SchedulingDecision::Run {
+                pid: Pid::from_json(json.get("pid")?)?,
+                timeslice: json.get("timeslice")?.to_number()?,
+            }),
+            "Sleep" => Ok(SchedulingDecision::Sleep(json.get("time")?.to_number()?)),
+            "Deadlock" => Ok(SchedulingDecision::Deadlock),
+            "Panic" => Ok(SchedulingDecision::Panic),
+            "Done" => Ok(SchedulingDecision::Done),
+            tag => unknown("decision", tag),
+        }
+    }
+}
+
+impl Record for Syscall {
+    fn to_json(&self) -> Json {
+        match self {
+            Syscall::Fork(priority) => tagged("Fork", [("priority", Json::number(priority))]),
+            Syscall::ForkDeadline {
+                priority,
+                deadline,
+                period,
+            } => tagged(
+                "ForkDeadline",
+                [
+                    ("priority", Json::number(priority)),
+                    ("deadline", Json::number(deadline)),
+                    ("period", Json::number(period)),
+                ],
+            ),
+            Syscall::ForkBurst { priority, burst } => tagged(
+                "ForkBurst",
+                [
+                    ("priority", Json::number(priority)),
+                    ("burst", Json::number(burst)),
+                ],
+            ),
+            Syscall::Sleep(time) => tagged("Sleep", [("time", Json::number(time))]),
+            Syscall::Wait(event) => tagged("Wait", [("event", Json::number(event))]),
+            Syscall::Signal(event) => tagged("Signal", [("event", Json::number(event))]),
+            Syscall::Exit(status) => tagged("Exit", [("status", Json::number(status))]),
+            Syscall::Kill(pid) => tagged("Kill", [("pid", pid.to_json())]),
+            Syscall::Join(pid) => tagged("Join", [("pid", pid.to_json())]),
+            Syscall::Lock(lock) => tagged("Lock", [("lock", Json::number(lock))]),
+            Syscall::Unlock(lock) => tagged("Unlock", [("lock", Json::number(lock))]),
+            Syscall::SemWait(semaphore) => {
+                tagged("SemWait", [("semaphore", Json::number(semaphore))])
+            }
+            Syscall::SemPost(semaphore) => {
+                tagged("SemPost", [("semaphore", Json::number(semaphore))])
+            }
+        }
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let number = |name: &str| json.get(name)?.to_number::<usize>();
+        match tag(json)? {
+            "Fork" => Ok(Syscall::Fork(json.get("priority")?.to_number()?)),
+            "ForkDeadline" => Ok(Syscall::ForkDeadline {
+                priority: json.get("priority")?.to_number()?,
+                deadline: number("deadline")?,
+                period: number("period")?,
+            }),
+            "ForkBurst" => Ok(Syscall::ForkBurst {
+                priority: json.get("priority")?.to_number()?,
+                burst: number("burst")?,
+            }),
+            "Sleep" => Ok(Syscall::Sleep(number("time")?)),
+            "Wait" => Ok(Syscall::Wait(number("event")?)),
+            "Signal" => Ok(Syscall::Signal(number("event")?)),
+            "Exit" => Ok(Syscall::Exit(json.get("status")?.to_number()?)),
+            "Kill" => Ok(Syscall::Kill(Pid::from_json(json.get("pid")?)?)),
+            "Join" => Ok(Syscall::Join(Pid::from_json(json.get("pid")?)?)),
+            "Lock" => Ok(Syscall::Lock(number("lock")?)),
+            "Unlock" => Ok(Syscall::Unlock(number("lock")?)),
+            "SemWait" => Ok(Syscall::SemWait(number("semaphore")?)),
+            "SemPost" => Ok(Syscall::SemPost(number("semaphore")?)),
+            tag => unknown("system call", tag),
+        }
+    }
+}
+
+impl Record for SyscallResult {
+    fn to_json(&self) -> Json {
+        match self {
+            SyscallResult::Pid(pid) => tagged("Pid", [("pid", pid.to_json())]),
+            SyscallResult::Success => tagged("Success", []),
+            SyscallResult::NoRunningProcess => tagged("NoRunningProcess", []),
+            SyscallResult::NoSuchProcess => tagged("NoSuchProcess", []),
+            SyscallResult::ExitStatus(status) => {
+                tagged("ExitStatus", [("status", Json::number(status))])
+            }
+            SyscallResult::NotOwner => tagged("NotOwner", []),
+            SyscallResult::Pending => tagged("Pending", []),
+        }
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match tag(json)? {
+            "Pid" => Ok(SyscallResult::Pid(Pid::from_json(json.get("pid")?)?)),
+            "Success" => Ok(SyscallResult::Success),
+            "NoRunningProcess" => Ok(SyscallResult::NoRunningProcess),
+            "NoSuchProcess" => Ok(SyscallResult::NoSuchProcess),
+            "ExitStatus" => Ok(SyscallResult::ExitStatus(json.get("status")?.to_number()?)),
+            "NotOwner" => Ok(SyscallResult::NotOwner),
+            "Pending" => Ok(SyscallResult::Pending),
+            tag => unknown("system call result", tag),
+        }
+    }
+}
+
+impl Record for StopReason {
+    fn to_json(&self) -> Json {
+        match self {
+            StopReason::Syscall { syscall, remaining } => tagged(
+                "Syscall",
+                [
+                    ("syscall", syscall.to_json()),
+                    ("remaining", Json::number(remaining)),
+                ],
+            ),
+            StopReason::Expired => tagged("Expired", []),
+        }
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match tag(json)? {
+            "Syscall" => Ok(StopReason::Syscall {
+                syscall: Syscall::from_json(json.get("syscall")?)?,
+                remaining: json.get("remaining")?.to_number()?,
+            }),
+            "Expired" => Ok(StopReason::Expired),
+            tag => unknown("stop reason", tag),
+        }
+    }
+}
+
+impl Record for SyncObject {
+    fn to_json(&self) -> Json {
+        match self {
+            SyncObject::Lock(id) => tagged("Lock", [("id", Json::number(id))]),
+            SyncObject::Semaphore(id) => tagged("Semaphore", [("id", Json::number(id))]),
+            SyncObject::Child(pid) => tagged("Child", [("pid", pid.to_json())]),
+        }
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match tag(json)? {
+            "Lock" => Ok(SyncObject::Lock(json.get("id")?.to_number()?)),
+            "Semaphore" => Ok(SyncObject::Semaphore(json.get("id")?.to_number()?)),
+            "Child" => Ok(SyncObject::Child(Pid::from_json(json.get("pid")?)?)),
+            tag => unknown("synchronization object", tag),
+        }
+    }
+}
+
+impl Record for ProcessState {
+    fn to_json(&self) -> Json {
+        match self {
+            ProcessState::Ready => tagged("Ready", []),
+            ProcessState::Running => tagged("Running", []),
+            ProcessState::Waiting { event } => tagged(
+                "Waiting",
+                [("event", event.map_or(Json::Null, Json::number))],
+            ),
+            ProcessState::Blocked { object } => tagged("Blocked", [("object", object.to_json())]),
+            ProcessState::Zombie => tagged("Zombie", []),
+        }
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match tag(json)? {
+            "Ready" => Ok(ProcessState::Ready),
+            "Running" => Ok(ProcessState::Running),
+            "Waiting" => Ok(ProcessState::Waiting {
+                event: json
+                    .get("event")?
+                    .to_option()
+                    .map(Json::to_number)
+                    .transpose()?,
+            }),
+            "Blocked" => Ok(ProcessState::Blocked {
+                object: SyncObject::from_json(json.get("object")?)?,
+            }),
+            "Zombie" => Ok(ProcessState::Zombie),
+            tag => unknown("process state", tag),
+        }
+    }
+}
+
+impl Record for Blocker {
+    fn to_json(&self) -> Json {
+        match self {
+            Blocker::Event(event) => tagged("Event", [("event", Json::number(event))]),
+            Blocker::Child(pid) => tagged("Child", [("pid", pid.to_json())]),
+            Blocker::Lock { lock, owner } => tagged(
+                "Lock",
+                [("lock", Json::number(lock)), ("owner", owner.to_json())],
+            ),
+            Blocker::Semaphore(semaphore) => {
+                tagged("Semaphore", [("semaphore", Json::number(semaphore))])
+            }
+        }
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        match tag(json)? {
+            "Event" => Ok(Blocker::Event(json.get("event")?.to_number()?)),
+            "Child" => Ok(Blocker::Child(Pid::from_json(json.get("pid")?)?)),
+            "Lock" => Ok(Blocker::Lock {
+                lock: json.get("lock")?.to_number()?,
+                owner: Pid::from_json(json.get("owner")?)?,
+            }),
+            "Semaphore" => Ok(Blocker::Semaphore(json.get("semaphore")?.to_number()?)),
+            tag => unknown("blocker", tag),
+        }
+    }
+}
+
+impl Record for ProcessInfo {
+    fn to_json(&self) -> Json {
+        let (total, syscall, execute) = self.timings;
+        Json::object([
+            ("pid", self.pid.to_json()),
+            ("state", self.state.to_json()),
+            (
+                "timings",
+                Json::Array(vec![
+                    Json::number(total),
+                    Json::number(syscall),
+                    Json::number(execute),
+                ]),
+            ),
+            ("priority", Json::number(self.priority)),
+            ("extra", self.extra.to_json()),
+            (
+                "exit_status",
+                self.exit_status.map_or(Json::Null, Json::number),
+            ),
+            ("ppid", self.ppid.to_json()),
+        ])
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let timings = json
+            .get("timings")?
+            .to_array()?
+            .iter()
+            .map(Json::to_number)
+            .collect::<Result<Vec<usize>, String>>()?;
+        let [total, syscall, execute] = timings[..] else {
+            return Err(String::from("expected three timings"));
+        };
+        Ok(ProcessInfo {
+            pid: Pid::from_json(json.get("pid")?)?,
+            state: ProcessState::from_json(json.get("state")?)?,
+            timings: (total, syscall, execute),
+            priority: json.get("priority")?.to_number()?,
+            extra: String::from_json(json.get("extra")?)?,
+            exit_status: json
+                .get("exit_status")?
+                .to_option()
+                .map(Json::to_number)
+                .transpose()?,
+            ppid: Option::<Pid>::from_json(json.get("ppid")?)?,
+        })
+    }
+}
+
+impl Record for BlockedProcess {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("pid", self.pid.to_json()),
+            ("blocker", self.blocker.to_json()),
+            ("wakers", self.wakers.to_json()),
+        ])
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        Ok(BlockedProcess {
+            pid: Pid::from_json(json.get("pid")?)?,
+            blocker: Blocker::from_json(json.get("blocker")?)?,
+            wakers: Vec::from_json(json.get("wakers")?)?,
+        })
+    }
+}
+
+impl Record for Diagnosis {
+    fn to_json(&self) -> Json {
+        Json::object([
+            ("blocked", self.blocked.to_json()),
+            ("cycles", self.cycles.to_json()),
+        ])
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        Ok(Diagnosis {
+            blocked: Vec::from_json(json.get("blocked")?)?,
+            cycles: Vec::from_json(json.get("cycles")?)?,
+        })
+    }
+}
+
+impl Record for Log {
+    fn to_json(&self) -> Json {
+        let stop_reason = match self.stop_reason {
+            Some((reason, result)) => {
+                Json::object([("reason", reason.to_json()), ("result", result.to_json())])
+            }
+            None => Json::Null,
+        };
+        // the processes are sorted, so that the same logs are always written the same way
+        let mut processes = self.processes.values().collect::<Vec<&ProcessInfo>>();
+        processes.sort_by_key(|process| process.pid);
+        Json::object([
+            ("decision", self.decision.to_json()),
+            ("stop_reason", stop_reason),
+            (
+                "processes",
+                Json::Array(processes.iter().map(|process| process.to_json()).collect()),
+            ),
+            ("cpu", Json::number(self.cpu)),
+            ("cpus", self.cpus.to_json()),
+            ("events", self.events.to_json()),
+            ("deadlock", self.deadlock.to_json()),
+            ("start", Json::number(self.start)),
+            ("end", Json::number(self.end)),
+        ])
+    }
+
+    fn from_json(json: &Json) -> Result<Self, String> {
+        let decision = SchedulingDecision::from_json(json.get("decision")?)?;
+        let stop_reason = match json.get("stop_reason")?.to_option() {
+            Some(stop_reason) => Some((
+                StopReason::from_json(stop_reason.get("reason")?)?,
+                SyscallResult::from_json(stop_reason.get("result")?)?,
+            )),
+            None => None,
+        };
+        let processes = Vec::<ProcessInfo>::from_json(json.get("processes")?)?
+            .into_iter()
+            .map(|process| (process.pid, process))
+            .collect::<HashMap<Pid, ProcessInfo>>();
+        Ok(Log {
+            decision,
+            stop_reason,
+            processes,
+            cpu: json.get("cpu")?.to_number()?,
+            cpus: Vec::from_json(json.get("cpus")?)?,
+            events: Vec::from_json(json.get("events")?)?,
+            deadlock: Option::from_json(json.get("deadlock")?)?,
+            start: json.get("start")?.to_number()?,
+            end: json.get("end")?.to_number()?,
+        })
+    }
+}
+
+/// Format the [`crate::Processor`]'s logs as JSON Lines, a JSON object on
+/// each line for each iteration.
+///
+/// The objects have the fields of the [`Log`]s, the processes are an array
+/// sorted by their PIDs and the variants of the enums are objects that name
+/// the variant in a `type` field, like `{"type":"Run","pid":1,"timeslice":3}`.
+/// The logs can be loaded back with [`parse_logs_json`].
+///
+/// * `logs` - the logs returned by the [`crate::Processor`].
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{format_logs_json, parse_logs_json, Program, Simulator};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Simulator::run(
+///     scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1),
+///     &Program::new().exec(3),
+/// );
+///
+/// let json = format_logs_json(&logs);
+/// assert_eq!(parse_logs_json(&json).unwrap(), logs);
+/// ```
+pub fn format_logs_json(logs: &[Log]) -> String {
+    let mut s = String::new();
+    for log in logs.iter() {
+        writeln!(s, "{}", log.to_json()).unwrap();
+    }
+    s
+}
+
+/// Loads the logs written by [`format_logs_json`].
+///
+/// Empty lines are skipped and the errors report the line of the log.
+pub fn parse_logs_json(text: &str) -> Result<Vec<Log>, ParseError> {
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| {
+            line.parse::<Json>()
+                .and_then(|json| Log::from_json(&json))
+                .map_err(|message| ParseError {
+                    line: index + 1,
+                    message,
+                })
+        })
+        .collect()
+}
+
+/// The header of the CSV format.
+const CSV_HEADER: [&str; 20] = [
+    "iteration",
+    "start",
+    "end",
+    "cpu",
+    "decision",
+    "decision_pid",
+    "decision_time",
+    "stop_reason",
+    "syscall",
+    "remaining",
+    "result",
+    "pid",
+    "state",
+    "priority",
+    "total_time",
+    "syscall_time",
+    "execution_time",
+    "exit_status",
+    "ppid",
+    "extra",
+];
+
+/// Returns `field` as a CSV field, quoted if it has a separator, a quote or a new line.
+fn csv_field(field: String) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Format the [`crate::Processor`]'s logs as a CSV table, with a row for
+/// each process listed at each iteration.
+///
+/// The columns of the iteration are repeated on each of its rows, an iteration
+/// that does not list any process has a single row with empty process columns.
+/// The decision, the reason that the process has stopped and the system call
+/// are split into columns, like `Run`, `1`, `3` for [`SchedulingDecision::Run`].
+/// The events and the deadlock diagnosis are not included, the format written
+/// by [`format_logs_json`] has every field of the logs.
+///
+/// * `logs` - the logs returned by the [`crate::Processor`].
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{format_logs_csv, Program, Simulator};
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Simulator::run(
+///     scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1),
+///     &Program::new().exec(3),
+/// );
+///
+/// let csv = format_logs_csv(&logs);
+/// assert!(csv.starts_with("iteration,start,end,cpu,decision,"));
+/// ```
+pub fn format_logs_csv(logs: &[Log]) -> String {
+    let mut s = String::new();
+    writeln!(s, "{}", CSV_HEADER.join(",")).unwrap();
+    for (iteration, log) in logs.iter().enumerate() {
+        let (decision, decision_pid, decision_time) = match log.decision {
+            SchedulingDecision::Run { pid, timeslice } => {
+                ("Run", pid.to_string(), timeslice.to_string())
+            }
+            SchedulingDecision::Sleep(time) => ("Sleep", String::new(), time.to_string()),
+            SchedulingDecision::Deadlock => ("Deadlock", String::new(), String::new()),
+            SchedulingDecision::Panic => ("Panic", String::new(), String::new()),
+            SchedulingDecision::Done => ("Done", String::new(), String::new()),
+        };
+        let (stop_reason, syscall, remaining, result) = match log.stop_reason {
+            Some((StopReason::Syscall { syscall, remaining }, result)) => (
+                "Syscall",
+                format!("{:?}", syscall),
+                remaining.to_string(),
+                format!("{:?}", result),
+            ),
+            Some((StopReason::Expired, result)) => (
+                "Expired",
+                String::new(),
+                String::new(),
+                format!("{:?}", result),
+            ),
+            None => ("", String::new(), String::new(), String::new()),
+        };
+        let iteration_fields = [
+            iteration.to_string(),
+            log.start.to_string(),
+            log.end.to_string(),
+            log.cpu.to_string(),
+            String::from(decision),
+            decision_pid,
+            decision_time,
+            String::from(stop_reason),
+            syscall,
+            remaining,
+            result,
+        ];
+
+        let mut processes = log.processes.values().collect::<Vec<&ProcessInfo>>();
+        processes.sort_by_key(|process| process.pid);
+        let process_fields = processes
+            .iter()
+            .map(|process| {
+                vec![
+                    process.pid.to_string(),
+                    process.state.to_string(),
+                    process.priority.to_string(),
+                    process.timings.0.to_string(),
+                    process.timings.1.to_string(),
+                    process.timings.2.to_string(),
+                    process
+                        .exit_status
+                        .map_or(String::new(), |status| status.to_string()),
+                    process.ppid.map_or(String::new(), |ppid| ppid.to_string()),
+                    process.extra.clone(),
+                ]
+            })
+            .collect::<Vec<Vec<String>>>();
+        let empty = vec![String::new(); CSV_HEADER.len() - iteration_fields.len()];
+        let rows = if process_fields.is_empty() {
+            vec![empty]
+        } else {
+            process_fields
+        };
+
+        for row in rows {
+            let fields = iteration_fields
+                .iter()
+                .cloned()
+                .chain(row)
+                .map(csv_field)
+                .collect::<Vec<String>>();
+            writeln!(s, "{}", fields.join(",")).unwrap();
+        }
+    }
+    s
+}
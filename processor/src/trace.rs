@@ -0,0 +1,144 @@
+//! Export of simulations to the Chrome Trace Event format.
+
+use std::fmt::Write;
+
+use scheduler::{Pid, ProcessState, SchedulingDecision, StopReason, Syscall};
+
+use crate::json::json_string;
+use crate::Log;
+
+/// Returns the name of a system call.
+fn syscall_name(syscall: &Syscall) -> &'static str {
+    match syscall {
+        Syscall::Fork(_) => "Fork",
+        Syscall::ForkDeadline { .. } => "ForkDeadline",
+        Syscall::ForkBurst { .. } => "ForkBurst",
+        Syscall::Sleep(_) => "Sleep",
+        Syscall::Wait(_) => "Wait",
+        Syscall::Signal(_) => "Signal",
+        Syscall::Exit(_) => "Exit",
+        Syscall::Kill(_) => "Kill",
+        Syscall::Join(_) => "Join",
+        Syscall::Lock(_) => "Lock",
+        Syscall::Unlock(_) => "Unlock",
+        Syscall::SemWait(_) => "SemWait",
+        Syscall::SemPost(_) => "SemPost",
+    }
+}
+
+/// Format the [`crate::Processor`]'s logs as a trace in the Chrome Trace Event
+/// format, which can be opened with `chrome://tracing` or Perfetto.
+///
+/// A unit of time of the simulation is a microsecond of the trace. Every process
+/// is a thread of the trace, with:
+/// * a `Run` slice for each time it has run, with its CPU and the reason it stopped;
+/// * an instant event for each system call, at the unit of time in which it was sent;
+/// * a flow arrow from each `Signal` system call to the next run of every process
+///   that the signal has woken up.
+///
+/// * `logs` - the logs returned by the [`crate::Processor`].
+///
+/// ## Example
+///
+/// ```rust
+/// use processor::{format_chrome_trace, Program, Simulator};
+/// use scheduler::Pid;
+/// use std::num::NonZeroUsize;
+///
+/// let logs = Simulator::run(
+///     scheduler::round_robin(NonZeroUsize::new(2).unwrap(), 1),
+///     &Program::new()
+///         .fork(0, Program::new().wait(1))
+///         .exec(3)
+///         .signal(1)
+///         .join(Pid::new(2)),
+/// );
+///
+/// let trace = format_chrome_trace(&logs);
+/// assert!(trace.starts_with("{\"traceEvents\":["));
+/// ```
+pub fn format_chrome_trace(logs: &[Log]) -> String {
+    let mut events = Vec::<String>::new();
+
+    events.push(String::from(
+        "{\"ph\":\"M\",\"pid\":1,\"name\":\"process_name\",\"args\":{\"name\":\"Simulation\"}}",
+    ));
+    let mut pids = logs
+        .iter()
+        .flat_map(|log| log.processes.keys().copied())
+        .collect::<Vec<Pid>>();
+    pids.sort();
+    pids.dedup();
+    for pid in pids.iter() {
+        events.push(format!(
+            "{{\"ph\":\"M\",\"pid\":1,\"tid\":{pid},\"name\":\"thread_name\",\"args\":{{\"name\":\"PID {pid}\"}}}}"
+        ));
+        events.push(format!(
+            "{{\"ph\":\"M\",\"pid\":1,\"tid\":{pid},\"name\":\"thread_sort_index\",\"args\":{{\"sort_index\":{pid}}}}}"
+        ));
+    }
+
+    let mut flows = 0;
+    for log in logs.iter() {
+        let SchedulingDecision::Run { pid, timeslice } = log.decision else {
+            continue;
+        };
+        let mut args = format!("\"cpu\":{},\"timeslice\":{}", log.cpu, timeslice);
+        if let Some((reason, result)) = log.stop_reason {
+            write!(
+                args,
+                ",\"stop\":{},\"result\":{}",
+                json_string(&reason.to_string()),
+                json_string(&format!("{:?}", result))
+            )
+            .unwrap();
+        }
+        events.push(format!(
+            "{{\"ph\":\"X\",\"pid\":1,\"tid\":{pid},\"name\":\"Run\",\"ts\":{},\"dur\":{},\"args\":{{{args}}}}}",
+            log.start,
+            log.end - log.start
+        ));
+
+        let Some((StopReason::Syscall { syscall, .. }, result)) = log.stop_reason else {
+            continue;
+        };
+        // the system call is sent in the last unit of time of the run
+        let time = log.end.saturating_sub(1);
+        events.push(format!(
+            "{{\"ph\":\"i\",\"s\":\"t\",\"pid\":1,\"tid\":{pid},\"name\":{},\"ts\":{time},\"args\":{{\"syscall\":{},\"result\":{}}}}}",
+            json_string(syscall_name(&syscall)),
+            json_string(&format!("{:?}", syscall)),
+            json_string(&format!("{:?}", result))
+        ));
+
+        if let Syscall::Signal(event) = syscall {
+            // the processes that were waiting for the event when the signal was sent
+            let mut woken = log
+                .processes
+                .values()
+                .filter(|info| info.state == ProcessState::Waiting { event: Some(event) })
+                .map(|info| info.pid)
+                .collect::<Vec<Pid>>();
+            woken.sort();
+            for woken in woken {
+                let next_run = logs.iter().find(|next| {
+                    next.start >= log.end
+                        && matches!(next.decision, SchedulingDecision::Run { pid, .. } if pid == woken)
+                });
+                let Some(next_run) = next_run else {
+                    continue;
+                };
+                flows += 1;
+                events.push(format!(
+                    "{{\"ph\":\"s\",\"pid\":1,\"tid\":{pid},\"name\":\"Signal {event}\",\"cat\":\"signal\",\"id\":{flows},\"ts\":{time}}}"
+                ));
+                events.push(format!(
+                    "{{\"ph\":\"f\",\"bp\":\"e\",\"pid\":1,\"tid\":{woken},\"name\":\"Signal {event}\",\"cat\":\"signal\",\"id\":{flows},\"ts\":{}}}",
+                    next_run.start
+                ));
+            }
+        }
+    }
+
+    format!("{{\"traceEvents\":[\n{}\n]}}\n", events.join(",\n"))
+}
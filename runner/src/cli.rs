@@ -0,0 +1,263 @@
+//! The command line of the runner, which selects the scheduler at runtime.
+
+use std::fs;
+use std::num::NonZeroUsize;
+use std::path::PathBuf;
+
+use processor::{
+    format_chrome_trace, format_gantt, format_gantt_svg, format_logs, format_logs_csv,
+    format_logs_json, format_logs_tree, Log, Program,
+};
+
+use scheduler::{
+    cfs, edf, lottery, mlfq, priority_queue, rate_monotonic, round_robin, sjf, srtf, stride,
+    weighted_cfs, MlfqLevel, Scheduler,
+};
+
+/// The usage message of the runner.
+pub const USAGE: &str = "\
+Usage: runner [OPTIONS] [compare]
+
+Options:
+    --scheduler <NAME>    rr, pq, cfs, weighted-cfs, edf, rm, mlfq, lottery,
+                          stride, sjf or srtf (default rr)
+    --timeslice <N>       the timeslice of the scheduler (default 3)
+    --remaining <N>       the minimum remaining timeslice (default 1)
+    --cpu-slices <N>      the CPU time of CFS, the boost period of MLFQ, the seed
+                          of lottery or the initial burst of SJF/SRTF (default 10)
+    --workload <FILE>     the workload that the process with PID 1 runs
+    --format <FORMAT>     logs, tree, gantt, svg, trace, json or csv (default logs)
+    --help                prints this message
+
+The `compare` command runs the workload with several schedulers.";
+
+/// The ways the logs of a simulation can be printed.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Format {
+    /// A table for each iteration, like [`processor::format_logs`].
+    Logs,
+
+    /// The processes drawn as a tree, like [`processor::format_logs_tree`].
+    Tree,
+
+    /// A Gantt chart, like [`processor::format_gantt`].
+    Gantt,
+
+    /// The SVG image of a Gantt chart, like [`processor::format_gantt_svg`].
+    Svg,
+
+    /// A Chrome trace, like [`processor::format_chrome_trace`].
+    Trace,
+
+    /// A JSON object on each line, like [`processor::format_logs_json`].
+    Json,
+
+    /// A CSV table, like [`processor::format_logs_csv`].
+    Csv,
+}
+
+impl Format {
+    /// Parses the name of a format.
+    fn parse(name: &str) -> Result<Format, String> {
+        match name {
+            "logs" => Ok(Format::Logs),
+            "tree" => Ok(Format::Tree),
+            "gantt" => Ok(Format::Gantt),
+            "svg" => Ok(Format::Svg),
+            "trace" => Ok(Format::Trace),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("Unknown format {}", name)),
+        }
+    }
+
+    /// Formats the logs.
+    pub fn format(&self, logs: &[Log]) -> String {
+        match self {
+            Format::Logs => format_logs(logs),
+            Format::Tree => format_logs_tree(logs),
+            Format::Gantt => format_gantt(logs),
+            Format::Svg => format_gantt_svg(logs),
+            Format::Trace => format_chrome_trace(logs),
+            Format::Json => format_logs_json(logs),
+            Format::Csv => format_logs_csv(logs),
+        }
+    }
+}
+
+/// The options given on the command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Options {
+    /// The name of the scheduler.
+    pub scheduler: String,
+
+    /// The timeslice of the scheduler.
+    pub timeslice: usize,
+
+    /// The minimum remaining timeslice of the scheduler.
+    pub remaining: usize,
+
+    /// The scheduler specific parameter, like the CPU time of CFS.
+    pub cpu_slices: usize,
+
+    /// The file with the workload, [`None`] for the built-in workload.
+    pub workload: Option<PathBuf>,
+
+    /// The format of the logs.
+    pub format: Format,
+
+    /// `true` if the workload runs with several schedulers.
+    pub compare: bool,
+
+    /// `true` if the usage message was asked for.
+    pub help: bool,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            scheduler: String::from("rr"),
+            timeslice: 3,
+            remaining: 1,
+            cpu_slices: 10,
+            workload: None,
+            format: Format::Logs,
+            compare: false,
+            help: false,
+        }
+    }
+}
+
+impl Options {
+    /// Parses the command line arguments, without the name of the program.
+    pub fn parse<I: IntoIterator<Item = String>>(args: I) -> Result<Options, String> {
+        let mut options = Options::default();
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for {}", arg))
+            };
+            match arg.as_str() {
+                "--scheduler" => options.scheduler = value()?,
+                "--timeslice" => options.timeslice = number(&arg, value()?)?,
+                "--remaining" => options.remaining = number(&arg, value()?)?,
+                "--cpu-slices" => options.cpu_slices = number(&arg, value()?)?,
+                "--workload" => options.workload = Some(PathBuf::from(value()?)),
+                "--format" => options.format = Format::parse(&value()?)?,
+                "--help" | "-h" => options.help = true,
+                "compare" => options.compare = true,
+                _ => return Err(format!("Unknown argument {}", arg)),
+            }
+        }
+        // check the parameters before running anything
+        scheduler(
+            &options.scheduler,
+            options.timeslice,
+            options.remaining,
+            options.cpu_slices,
+        )?;
+        Ok(options)
+    }
+
+    /// Reads the program of the workload file, [`None`] if there is no workload file.
+    pub fn program(&self) -> Result<Option<Program>, String> {
+        let Some(path) = &self.workload else {
+            return Ok(None);
+        };
+        let text = fs::read_to_string(path)
+            .map_err(|error| format!("Cannot read {}: {}", path.display(), error))?;
+        let program = text
+            .parse::<Program>()
+            .map_err(|error| format!("Cannot parse {}, {}", path.display(), error))?;
+        Ok(Some(program))
+    }
+
+    /// Returns the scheduler selected by the options.
+    pub fn scheduler(&self) -> Box<dyn Scheduler> {
+        scheduler(
+            &self.scheduler,
+            self.timeslice,
+            self.remaining,
+            self.cpu_slices,
+        )
+        .unwrap()
+    }
+}
+
+/// Parses the value of the option `name`.
+fn number(name: &str, value: String) -> Result<usize, String> {
+    value
+        .parse::<usize>()
+        .map_err(|_| format!("{} has to be a number, not {}", name, value))
+}
+
+/// Returns the scheduler called `name`, using the same parameters as the
+/// configurations of the `Makefile`.
+///
+/// * `timeslice` - the timeslice of the scheduler, the quantum of the
+///   highest MLFQ level;
+/// * `remaining` - the minimum remaining timeslice;
+/// * `cpu_slices` - the CPU time of CFS, the boost period of MLFQ in
+///   timeslices, the seed of lottery or the initial burst of SJF and SRTF.
+pub fn scheduler(
+    name: &str,
+    timeslice: usize,
+    remaining: usize,
+    cpu_slices: usize,
+) -> Result<Box<dyn Scheduler>, String> {
+    let non_zero = |name: &str, value: usize| {
+        NonZeroUsize::new(value).ok_or_else(|| format!("{} cannot be 0", name))
+    };
+    let scheduler: Box<dyn Scheduler> = match name {
+        "rr" | "round-robin" => {
+            Box::new(round_robin(non_zero("--timeslice", timeslice)?, remaining))
+        }
+        "pq" | "priority-queue" => Box::new(priority_queue(
+            non_zero("--timeslice", timeslice)?,
+            remaining,
+        )),
+        "cfs" => Box::new(cfs(
+            non_zero("--cpu-slices", cpu_slices)?,
+            non_zero("--remaining", remaining)?.get(),
+        )),
+        "weighted-cfs" => Box::new(weighted_cfs(
+            non_zero("--cpu-slices", cpu_slices)?,
+            non_zero("--remaining", remaining)?.get(),
+        )),
+        "edf" => Box::new(edf(non_zero("--timeslice", timeslice)?, remaining)),
+        "rm" | "rate-monotonic" => Box::new(rate_monotonic(
+            non_zero("--timeslice", timeslice)?,
+            remaining,
+        )),
+        "mlfq" => {
+            // the quanta and the allotments are multiples of the timeslice
+            let times = |name: &str, factor: usize, value: usize| {
+                factor
+                    .checked_mul(value)
+                    .ok_or_else(|| format!("{} is too large", name))
+            };
+            let level = |quantum: usize, allotment: usize| {
+                Ok::<MlfqLevel, String>(MlfqLevel::new(
+                    non_zero("--timeslice", times("--timeslice", quantum, timeslice)?)?,
+                    non_zero("--timeslice", times("--timeslice", allotment, timeslice)?)?,
+                ))
+            };
+            Box::new(mlfq(
+                &[level(1, 2)?, level(2, 4)?, level(4, 4)?],
+                NonZeroUsize::new(times("--cpu-slices", cpu_slices, timeslice)?),
+                remaining,
+            ))
+        }
+        "lottery" => Box::new(lottery(
+            non_zero("--timeslice", timeslice)?,
+            remaining,
+            cpu_slices as u64,
+        )),
+        "stride" => Box::new(stride(non_zero("--timeslice", timeslice)?, remaining)),
+        "sjf" => Box::new(sjf(non_zero("--timeslice", timeslice)?, 0.5, cpu_slices)),
+        "srtf" => Box::new(srtf(non_zero("--timeslice", timeslice)?, 0.5, cpu_slices)),
+        _ => return Err(format!("Unknown scheduler {}", name)),
+    };
+    Ok(scheduler)
+}
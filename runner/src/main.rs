@@ -1,29 +1,91 @@
-use scheduler::round_robin;
+use scheduler::{cfs, priority_queue, round_robin, Scheduler};
+use std::env;
 use std::num::NonZeroUsize;
+use std::process;
 
-use processor::format_logs;
-use processor::Processor;
+use processor::comparison::{AnyScheduler, Comparison};
+use processor::metrics;
+use processor::{Process, Processor};
+
+mod cli;
+use cli::{Format, Options};
+
+/// The process with PID 1 of the simulation.
+fn workload<S: Scheduler + 'static>(process: &Process<S>) {
+    process.exec();
+    process.exec();
+    process.exec();
+    process.exec();
+    let child = process.fork(
+        |process| {
+            process.exec();
+            process.exec();
+            process.wait(1);
+        },
+        0,
+    );
+    process.sleep(10);
+    process.signal(1);
+    process.exec();
+    process.join(child);
+}
 
 fn main() {
-    let logs = Processor::run(round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
-        process.exec();
-        process.exec();
-        process.exec();
-        process.exec();
-        process.fork(
-            |process| {
-                process.exec();
-                process.exec();
-                process.wait(1);
-            },
-            0,
-        );
-        process.sleep(10);
-        process.signal(1);
-        process.exec();
-    });
-
-    println!("{}", format_logs(&logs));
+    let options = match Options::parse(env::args().skip(1)) {
+        Ok(options) => options,
+        Err(error) => {
+            eprintln!("{}\n\n{}", error, cli::USAGE);
+            process::exit(2);
+        }
+    };
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+    let program = match options.program() {
+        Ok(program) => program,
+        Err(error) => {
+            eprintln!("{}", error);
+            process::exit(2);
+        }
+    };
+    let workload = |process: &Process<AnyScheduler>| match &program {
+        Some(program) => program.run(process),
+        None => workload(process),
+    };
+
+    // `cargo run -- compare` runs the workload with several schedulers
+    if options.compare {
+        let table = Comparison::new()
+            .scheduler(
+                "Round Robin 3",
+                round_robin(NonZeroUsize::new(3).unwrap(), 1),
+            )
+            .scheduler(
+                "Round Robin 5",
+                round_robin(NonZeroUsize::new(5).unwrap(), 1),
+            )
+            .scheduler(
+                "Priority Queue 3",
+                priority_queue(NonZeroUsize::new(3).unwrap(), 1),
+            )
+            .scheduler("CFS 10", cfs(NonZeroUsize::new(10).unwrap(), 1))
+            .scheduler("CFS 18", cfs(NonZeroUsize::new(18).unwrap(), 1))
+            .run(workload);
+        println!("{}", table);
+        return;
+    }
+
+    let logs = Processor::run(options.scheduler(), workload);
+
+    println!("{}", options.format.format(&logs));
+    // images, traces and records are the only output, to be saved to a file
+    if !matches!(
+        options.format,
+        Format::Svg | Format::Trace | Format::Json | Format::Csv
+    ) {
+        println!("{}", metrics::measure(&logs));
+    }
 }
 
 // Do not delete this line
@@ -0,0 +1,380 @@
+use processor::{Program, Simulator};
+use scheduler::{
+    Checked, Pid, Process, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall,
+    SyscallResult, Violation,
+};
+use std::fs;
+use std::num::NonZeroUsize;
+
+use crate::cli;
+
+/// The schedulers that the runner can build.
+const SCHEDULERS: [&str; 11] = [
+    "rr",
+    "pq",
+    "cfs",
+    "weighted-cfs",
+    "edf",
+    "rm",
+    "mlfq",
+    "lottery",
+    "stride",
+    "sjf",
+    "srtf",
+];
+
+/// A process listed by [`Scripted`].
+struct Fake {
+    pid: Pid,
+    state: ProcessState,
+    timings: (usize, usize, usize),
+}
+
+impl Process for Fake {
+    fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    fn state(&self) -> ProcessState {
+        self.state
+    }
+
+    fn timings(&self) -> (usize, usize, usize) {
+        self.timings
+    }
+
+    fn priority(&self) -> i8 {
+        0
+    }
+
+    fn extra(&self) -> String {
+        String::new()
+    }
+
+    fn exit_status(&self) -> Option<i32> {
+        None
+    }
+
+    fn ppid(&self) -> Option<Pid> {
+        None
+    }
+}
+
+/// Returns a fake process with no timings.
+fn fake(pid: usize, state: ProcessState) -> Fake {
+    Fake {
+        pid: Pid::new(pid),
+        state,
+        timings: (0, 0, 0),
+    }
+}
+
+/// A scheduler that returns scripted decisions and PIDs.
+struct Scripted {
+    /// The decisions, each with the processes listed after it.
+    steps: Vec<(SchedulingDecision, Vec<Fake>)>,
+
+    /// The PIDs returned by the forks.
+    pids: Vec<usize>,
+
+    /// The processes listed now.
+    processes: Vec<Fake>,
+}
+
+impl Scheduler for Scripted {
+    fn next(&mut self) -> SchedulingDecision {
+        let (decision, processes) = self.steps.remove(0);
+        self.processes = processes;
+        decision
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        match reason {
+            StopReason::Syscall {
+                syscall: Syscall::Fork(_),
+                ..
+            } => SyscallResult::Pid(Pid::new(self.pids.remove(0))),
+            _ => SyscallResult::Success,
+        }
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        self.processes
+            .iter()
+            .map(|process| process as &dyn Process)
+            .collect()
+    }
+}
+
+/// Forks a process for each PID, then asks for every scripted decision
+/// and returns the violations.
+fn violations(pids: &[usize], steps: Vec<(SchedulingDecision, Vec<Fake>)>) -> Vec<Violation> {
+    let count = steps.len();
+    let mut scheduler = Checked::recording(Scripted {
+        steps,
+        pids: pids.to_vec(),
+        processes: vec![fake(1, ProcessState::Ready)],
+    });
+    for _ in pids {
+        scheduler.stop(StopReason::syscall(Syscall::Fork(0)));
+    }
+    for _ in 0..count {
+        scheduler.next();
+    }
+    scheduler.violations().to_vec()
+}
+
+/// Returns the message of the only violation.
+fn message(violations: &[Violation]) -> &str {
+    assert_eq!(violations.len(), 1, "{:?}", violations);
+    &violations[0].message
+}
+
+/// Returns a decision that runs `pid` for 3 slices.
+fn run(pid: usize) -> SchedulingDecision {
+    SchedulingDecision::Run {
+        pid: Pid::new(pid),
+        timeslice: NonZeroUsize::new(3).unwrap(),
+    }
+}
+
+/// The built-in schedulers follow the contract for these workloads.
+#[test]
+pub fn conformance() {
+    let mut programs = vec![
+        Program::new()
+            .fork(1, Program::new().wait(1).exec(2))
+            .fork(2, Program::new().exec(3).signal(1))
+            .exec(2)
+            .sleep(2)
+            .join(Pid::new(2))
+            .join(Pid::new(3)),
+        Program::new()
+            .fork(0, Program::new().exec(10))
+            .exec(1)
+            .kill(Pid::new(2))
+            .join(Pid::new(2)),
+        Program::new().fork(0, Program::new().exec(5)).exec(1),
+        Program::new().fork(0, Program::new().wait(2)).wait(1),
+    ];
+    let text = fs::read_to_string("workloads/demo.txt").unwrap();
+    programs.push(text.parse::<Program>().unwrap());
+
+    for name in SCHEDULERS {
+        for program in programs.iter() {
+            Simulator::run(
+                Checked::new(cli::scheduler(name, 3, 1, 10).unwrap()),
+                program,
+            );
+        }
+    }
+}
+
+#[test]
+pub fn follows_contract() {
+    let violations = violations(
+        &[1],
+        vec![
+            (run(1), vec![fake(1, ProcessState::Running)]),
+            (SchedulingDecision::Done, vec![]),
+        ],
+    );
+    assert!(violations.is_empty(), "{:?}", violations);
+}
+
+#[test]
+#[should_panic(expected = "broke the scheduler contract")]
+pub fn panics() {
+    let mut scheduler = Checked::new(Scripted {
+        steps: vec![],
+        pids: vec![2],
+        processes: vec![],
+    });
+    scheduler.stop(StopReason::syscall(Syscall::Fork(0)));
+}
+
+#[test]
+pub fn sleep_while_ready() {
+    let violations = violations(
+        &[1],
+        vec![(
+            SchedulingDecision::Sleep(NonZeroUsize::new(2).unwrap()),
+            vec![fake(1, ProcessState::Ready)],
+        )],
+    );
+    assert_eq!(
+        message(&violations),
+        "the processor sleeps while PID 1 is ready"
+    );
+    assert_eq!(
+        violations[0].to_string(),
+        "`next` broke the scheduler contract at decision 1 (Sleep for 2 slices): \
+         the processor sleeps while PID 1 is ready\nProcesses: 1 READY"
+    );
+}
+
+#[test]
+pub fn run_blocked() {
+    let violations = violations(
+        &[1, 2],
+        vec![
+            (
+                run(1),
+                vec![
+                    fake(1, ProcessState::Running),
+                    fake(2, ProcessState::Waiting { event: Some(1) }),
+                ],
+            ),
+            (
+                run(2),
+                vec![fake(1, ProcessState::Ready), fake(2, ProcessState::Running)],
+            ),
+        ],
+    );
+    assert!(message(&violations).starts_with("PID 2 cannot run, it is "));
+    assert_eq!(violations[0].decisions, 2);
+    assert_eq!(violations[0].decision, Some(run(2)));
+}
+
+/// Runs PID 1 until it sleeps for 5 slices at time 3, lets the processor
+/// sleep for `sleep` slices and runs PID 1 again.
+fn run_after_sleep(sleep: usize) -> Vec<Violation> {
+    let sleeping = ProcessState::Waiting { event: None };
+    let mut scheduler = Checked::recording(Scripted {
+        steps: vec![
+            (run(1), vec![fake(1, ProcessState::Running)]),
+            (
+                SchedulingDecision::Sleep(NonZeroUsize::new(sleep).unwrap()),
+                vec![fake(1, sleeping)],
+            ),
+            (run(1), vec![fake(1, ProcessState::Running)]),
+        ],
+        pids: vec![1],
+        processes: vec![fake(1, ProcessState::Ready)],
+    });
+    scheduler.stop(StopReason::syscall(Syscall::Fork(0)));
+    scheduler.next();
+    scheduler.stop(StopReason::syscall(Syscall::Sleep(5)));
+    scheduler.next();
+    scheduler.next();
+    scheduler.violations().to_vec()
+}
+
+#[test]
+pub fn run_sleeping() {
+    assert!(run_after_sleep(5).is_empty());
+    assert_eq!(
+        message(&run_after_sleep(2)),
+        "PID 1 cannot run at time 5, it sleeps until time 8"
+    );
+}
+
+#[test]
+pub fn run_unlisted() {
+    let violations = violations(&[1], vec![(run(3), vec![fake(1, ProcessState::Running)])]);
+    assert_eq!(violations[0].message, "PID 3 is not listed");
+}
+
+#[test]
+pub fn two_running() {
+    let violations = violations(
+        &[1, 2],
+        vec![(
+            run(1),
+            vec![
+                fake(1, ProcessState::Running),
+                fake(2, ProcessState::Running),
+            ],
+        )],
+    );
+    assert!(message(&violations).starts_with("PID 1 has to be the only running process"));
+}
+
+#[test]
+pub fn deadlock_with_sleeper() {
+    let violations = violations(
+        &[1],
+        vec![(
+            SchedulingDecision::Deadlock,
+            vec![fake(1, ProcessState::Waiting { event: None })],
+        )],
+    );
+    assert_eq!(message(&violations), "deadlock while PID 1 can still run");
+}
+
+#[test]
+pub fn panic_with_first_process() {
+    let found = violations(
+        &[1],
+        vec![(
+            SchedulingDecision::Panic,
+            vec![fake(1, ProcessState::Ready)],
+        )],
+    );
+    assert_eq!(message(&found), "panic while process 1 is still alive");
+
+    // process 1 has stopped but its child is still scheduled
+    let found = violations(
+        &[1, 2],
+        vec![
+            (
+                run(1),
+                vec![fake(1, ProcessState::Running), fake(2, ProcessState::Ready)],
+            ),
+            (
+                run(2),
+                vec![
+                    fake(1, ProcessState::Zombie),
+                    fake(2, ProcessState::Running),
+                ],
+            ),
+        ],
+    );
+    assert_eq!(
+        message(&found),
+        "process 1 has stopped, but the decision is Run 2 for 3 slices"
+    );
+}
+
+#[test]
+pub fn fork_pids() {
+    let violations = violations(&[2, 2], vec![]);
+    assert_eq!(violations.len(), 2);
+    assert_eq!(violations[0].function, "stop");
+    assert_eq!(violations[0].decision, None);
+    assert_eq!(
+        violations[0].message,
+        "the first process has PID 2 instead of 1"
+    );
+    assert_eq!(
+        violations[1].message,
+        "fork returned PID 2, which is already used"
+    );
+}
+
+#[test]
+pub fn listed_twice() {
+    let violations = violations(
+        &[1],
+        vec![(
+            run(1),
+            vec![fake(1, ProcessState::Running), fake(1, ProcessState::Ready)],
+        )],
+    );
+    assert!(violations
+        .iter()
+        .any(|violation| violation.message == "PID 1 is listed more than once"));
+}
+
+#[test]
+pub fn timings_go_back() {
+    let mut first = fake(1, ProcessState::Running);
+    first.timings = (5, 1, 4);
+    let mut second = fake(1, ProcessState::Running);
+    second.timings = (4, 1, 3);
+    let violations = violations(&[1], vec![(run(1), vec![first]), (run(1), vec![second])]);
+    assert_eq!(
+        message(&violations),
+        "the timings of PID 1 went back from (5, 1, 4) to (4, 1, 3)"
+    );
+}
@@ -0,0 +1,41 @@
+use processor::{Processor, Program, Simulator};
+use scheduler::round_robin;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+use super::scheduler;
+
+#[test]
+pub fn contiguous_timeline() {
+    let program = Program::new()
+        .fork(0, Program::new().exec(7).sleep(4).exec(2))
+        .exec(5)
+        .sleep(20)
+        .exec(3);
+    let logs = Simulator::run(scheduler(), &program);
+
+    assert_eq!(logs.first().unwrap().start, 0);
+    for logs in logs.windows(2) {
+        assert!(logs[0].start <= logs[0].end);
+        assert_eq!(logs[0].end, logs[1].start);
+    }
+}
+
+#[test]
+pub fn process_time() {
+    // the times are checked on the test thread, a failed assertion in the
+    // process would stop the simulation instead of the test
+    let times = Mutex::new(Vec::new());
+    let logs = Processor::run(round_robin(NonZeroUsize::new(2).unwrap(), 1), |process| {
+        times.lock().unwrap().push(process.now());
+        process.exec();
+        process.exec();
+        process.exec();
+        times.lock().unwrap().push(process.now());
+        process.sleep(10);
+        times.lock().unwrap().push(process.now());
+    });
+
+    assert_eq!(times.into_inner().unwrap(), [0, 3, 14]);
+    assert_eq!(logs.last().unwrap().end, 15);
+}
@@ -0,0 +1,50 @@
+use processor::comparison::Comparison;
+use processor::metrics;
+use processor::{Process, Processor};
+use scheduler::{cfs, round_robin, Scheduler, SchedulingDecision};
+use std::num::NonZeroUsize;
+
+/// A parent and a child that share the CPU.
+fn workload<S: Scheduler + 'static>(process: &Process<S>) {
+    let child = process.fork(
+        |process| {
+            for _ in 0..6 {
+                process.exec();
+            }
+        },
+        0,
+    );
+    for _ in 0..4 {
+        process.exec();
+    }
+    process.join(child);
+}
+
+#[test]
+pub fn same_as_single_runs() {
+    let table = Comparison::new()
+        .scheduler(
+            "Round Robin 3",
+            round_robin(NonZeroUsize::new(3).unwrap(), 1),
+        )
+        .scheduler("CFS 10", cfs(NonZeroUsize::new(10).unwrap(), 1))
+        .run(workload);
+
+    assert_eq!(table.rows.len(), 2);
+    assert_eq!(table.rows[0].name, "Round Robin 3");
+    assert_eq!(table.rows[1].name, "CFS 10");
+    for row in table.rows.iter() {
+        assert_eq!(row.logs.last().unwrap().decision, SchedulingDecision::Done);
+    }
+
+    let logs = Processor::run(round_robin(NonZeroUsize::new(3).unwrap(), 1), workload);
+    assert_eq!(table.rows[0].metrics, metrics::measure(&logs));
+
+    let output = table.to_string();
+    assert_eq!(output.lines().count(), 3);
+    assert!(output
+        .lines()
+        .nth(1)
+        .unwrap()
+        .starts_with("Round Robin 3\t"));
+}
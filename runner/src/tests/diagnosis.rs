@@ -0,0 +1,109 @@
+use processor::{BlockedProcess, Diagnosis, Log, Processor, Program, Simulator};
+use scheduler::{Blocker, Pid, SchedulingDecision};
+
+use super::scheduler;
+
+/// Returns the diagnosis of the deadlock that has stopped the simulation.
+fn diagnosis(logs: &[Log]) -> Diagnosis {
+    let log = logs.last().unwrap();
+    assert_eq!(log.decision, SchedulingDecision::Deadlock);
+    log.deadlock.clone().unwrap()
+}
+
+#[test]
+pub fn lock_cycle() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .lock(1)
+            .fork(0, Program::new().lock(2).sleep(5).lock(1))
+            .sleep(2)
+            .lock(2),
+    );
+
+    let diagnosis = diagnosis(&logs);
+    assert_eq!(
+        diagnosis.blocked,
+        vec![
+            BlockedProcess {
+                pid: Pid::new(1),
+                blocker: Blocker::Lock {
+                    lock: 2,
+                    owner: Pid::new(2)
+                },
+                wakers: vec![Pid::new(2)],
+            },
+            BlockedProcess {
+                pid: Pid::new(2),
+                blocker: Blocker::Lock {
+                    lock: 1,
+                    owner: Pid::new(1)
+                },
+                wakers: vec![Pid::new(1)],
+            },
+        ]
+    );
+    assert_eq!(diagnosis.cycles, vec![vec![Pid::new(1), Pid::new(2)]]);
+}
+
+#[test]
+pub fn join_and_wait() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().wait(1))
+            .join(Pid::new(2))
+            .signal(1),
+    );
+
+    let diagnosis = diagnosis(&logs);
+    assert_eq!(diagnosis.blocked[0].blocker, Blocker::Child(Pid::new(2)));
+    assert_eq!(diagnosis.blocked[1].blocker, Blocker::Event(1));
+    assert_eq!(diagnosis.cycles, vec![vec![Pid::new(1), Pid::new(2)]]);
+}
+
+#[test]
+pub fn event_without_signal() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().wait(1).signal(2))
+            .wait(2),
+    );
+
+    // only PID 2 signals event 2 after it wakes up, nothing signals event 1
+    let diagnosis = diagnosis(&logs);
+    assert_eq!(diagnosis.blocked[0].wakers, vec![Pid::new(2)]);
+    assert!(diagnosis.blocked[1].wakers.is_empty());
+    assert!(diagnosis.cycles.is_empty());
+    assert!(diagnosis
+        .to_string()
+        .contains("process 2 waits for event 1, nothing can wake it up"));
+}
+
+#[test]
+pub fn threads_wakers() {
+    let logs = Processor::run(scheduler(), |process| {
+        process.fork(|process| process.wait(2), 0);
+        process.wait(1);
+    });
+
+    // the functions of the processes cannot be searched for signals,
+    // any other blocked process may signal the event
+    let diagnosis = diagnosis(&logs);
+    assert_eq!(diagnosis.blocked[0].wakers, vec![Pid::new(2)]);
+    assert_eq!(diagnosis.blocked[1].wakers, vec![Pid::new(1)]);
+    assert_eq!(diagnosis.cycles, vec![vec![Pid::new(1), Pid::new(2)]]);
+}
+
+#[test]
+pub fn waits_for_itself() {
+    let logs = Simulator::run(scheduler(), &Program::new().lock(1).lock(1));
+
+    let diagnosis = diagnosis(&logs);
+    assert_eq!(diagnosis.blocked[0].wakers, vec![Pid::new(1)]);
+    assert_eq!(diagnosis.cycles, vec![vec![Pid::new(1)]]);
+    assert!(diagnosis
+        .to_string()
+        .contains("Deadlock: process 1 waits for itself"));
+}
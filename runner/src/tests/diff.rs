@@ -0,0 +1,182 @@
+use processor::{diff_logs, format_logs, parse_logs, Change, Log, Program, Simulator};
+use scheduler::{round_robin, smp_round_robin, Pid, ProcessState, SchedulingDecision};
+use std::fs;
+use std::num::NonZeroUsize;
+
+use super::{scheduler, scheduler_name};
+
+/// Formats the logs and parses them back.
+fn parse(logs: &[Log]) -> Vec<Log> {
+    parse_logs(&format_logs(logs)).unwrap()
+}
+
+/// A parent and a child that take turns on the processor.
+fn logs() -> Vec<Log> {
+    parse(&Simulator::run(
+        round_robin(NonZeroUsize::new(2).unwrap(), 1),
+        &Program::new()
+            .fork(0, Program::new().exec(4))
+            .exec(4)
+            .join(Pid::new(2)),
+    ))
+}
+
+#[test]
+pub fn parse_text() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(2, Program::new().lock(1).exec(2).unlock(1).sem_post(1))
+            .fork_burst(0, 4, Program::new().sem_wait(1).exit(7))
+            .fork(1, Program::new().wait(1).exec(1))
+            .exec(2)
+            .lock(1)
+            .signal(1)
+            .unlock(1)
+            .sleep(3)
+            .kill(Pid::new(4))
+            .join(Pid::new(2))
+            .join(Pid::new(3))
+            .join(Pid::new(4)),
+    );
+
+    let parsed = parse(&logs);
+    assert_eq!(format_logs(&parsed), format_logs(&logs));
+    for (parsed, log) in parsed.iter().zip(logs.iter()) {
+        assert_eq!(parsed.decision, log.decision);
+        assert_eq!(parsed.stop_reason, log.stop_reason);
+        assert_eq!(parsed.events, log.events);
+    }
+}
+
+#[test]
+pub fn parse_deadlock() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .lock(1)
+            .fork(0, Program::new().lock(2).sleep(5).lock(1))
+            .sleep(2)
+            .lock(2),
+    );
+
+    let parsed = parse(&logs);
+    assert_eq!(
+        parsed.last().unwrap().deadlock,
+        logs.last().unwrap().deadlock
+    );
+    assert!(parsed.last().unwrap().deadlock.is_some());
+}
+
+#[test]
+pub fn parse_cpus() {
+    let logs = Simulator::run_smp(
+        2,
+        smp_round_robin(NonZeroUsize::new(3).unwrap(), 1),
+        &Program::new().fork(0, Program::new().exec(4)).exec(4),
+    );
+
+    let parsed = parse(&logs);
+    for (parsed, log) in parsed.iter().zip(logs.iter()) {
+        assert_eq!(parsed.cpu, log.cpu);
+        assert_eq!(parsed.cpus, log.cpus);
+    }
+    assert_eq!(format_logs(&parsed), format_logs(&logs));
+}
+
+#[test]
+pub fn parse_errors() {
+    let error = parse_logs("Run 1 for 3 slices\n").unwrap_err();
+    assert_eq!(error.line, 1);
+
+    let text = format_logs(&logs()).replacen("RUNNING", "WALKING", 1);
+    let error = parse_logs(&text).unwrap_err();
+    assert_eq!(error.line, 4);
+    assert_eq!(error.message, "unknown process state `WALKING`");
+
+    let text = format_logs(&logs()).replacen("-> Pid(2)", "-> Pid(0)", 1);
+    assert!(parse_logs(&text).is_err());
+}
+
+/// Every golden file of the scheduler can be parsed and written back.
+#[test]
+pub fn goldens() {
+    let Ok(folders) = fs::read_dir(format!("../outputs/{}", scheduler_name())) else {
+        return;
+    };
+    for folder in folders {
+        for file in fs::read_dir(folder.unwrap().path()).unwrap() {
+            let path = file.unwrap().path();
+            let text = fs::read_to_string(&path).unwrap();
+            let logs =
+                parse_logs(&text).unwrap_or_else(|error| panic!("{}: {}", path.display(), error));
+            assert_eq!(format_logs(&logs), text, "{}", path.display());
+        }
+    }
+}
+
+#[test]
+pub fn equal() {
+    assert_eq!(diff_logs(&logs(), &logs()), None);
+}
+
+#[test]
+pub fn process_field() {
+    let expected = logs();
+    let mut actual = logs();
+    actual[3].processes.get_mut(&Pid::new(2)).unwrap().state = ProcessState::Zombie;
+
+    let diff = diff_logs(&expected, &actual).unwrap();
+    assert_eq!(diff.iteration, 3);
+    assert_eq!(diff.expected, Some(expected[3].decision));
+    assert_eq!(diff.actual, Some(expected[3].decision));
+    assert_eq!(
+        diff.changes,
+        vec![Change {
+            pid: Some(Pid::new(2)),
+            field: "state",
+            expected: expected[3].processes[&Pid::new(2)].state.to_string(),
+            actual: String::from("ZOMBIE"),
+        }]
+    );
+    assert_eq!(diff.cause, expected[2].stop_reason);
+    assert!(diff.to_string().starts_with("Iteration 4: "));
+    assert!(diff.to_string().contains("\n  PID 2 state: expected `"));
+}
+
+#[test]
+pub fn decision() {
+    let expected = logs();
+    let mut actual = logs();
+    actual[2].decision = SchedulingDecision::Run {
+        pid: Pid::new(1),
+        timeslice: NonZeroUsize::new(2).unwrap(),
+    };
+    actual[2].processes.remove(&Pid::new(2));
+
+    let diff = diff_logs(&expected, &actual).unwrap();
+    assert_eq!(diff.iteration, 2);
+    assert_eq!(diff.changes[0].field, "decision");
+    assert_eq!(diff.changes[0].actual, "Run 1 for 2 slices");
+    let listed = diff.changes.last().unwrap();
+    assert_eq!(listed.pid, Some(Pid::new(2)));
+    assert_eq!(listed.field, "process");
+    assert_eq!(
+        (listed.expected.as_str(), listed.actual.as_str()),
+        ("listed", "-")
+    );
+}
+
+#[test]
+pub fn shorter() {
+    let expected = logs();
+    let mut actual = logs();
+    actual.pop();
+
+    let diff = diff_logs(&expected, &actual).unwrap();
+    assert_eq!(diff.iteration, expected.len() - 1);
+    assert_eq!(diff.expected, Some(SchedulingDecision::Done));
+    assert_eq!(diff.actual, None);
+    assert!(diff.changes.is_empty());
+    assert!(diff.to_string().contains("found the end of the logs"));
+}
@@ -0,0 +1,81 @@
+use processor::{Log, Program, Simulator};
+use scheduler::{edf, Pid, SchedulingDecision, StopReason, Syscall};
+
+use std::num::NonZeroUsize;
+
+use super::misses;
+
+fn run(program: &Program) -> Vec<Log> {
+    Simulator::run(edf(NonZeroUsize::new(3).unwrap(), 1), program)
+}
+
+#[test]
+pub fn nearest_deadline_first() {
+    let logs = run(&Program::new()
+        .fork_deadline(0, 50, 0, Program::new().wait(1).exec(5))
+        .fork_deadline(0, 10, 0, Program::new().wait(1).exec(5))
+        .sleep(1)
+        .signal(1)
+        .sleep(20));
+
+    let exits = logs
+        .iter()
+        .filter_map(|log| match (log.decision, log.stop_reason) {
+            (
+                SchedulingDecision::Run { pid, .. },
+                Some((
+                    StopReason::Syscall {
+                        syscall: Syscall::Exit(_),
+                        ..
+                    },
+                    _,
+                )),
+            ) => Some(pid),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(exits, [Pid::new(3), Pid::new(2), Pid::new(1)]);
+}
+
+#[test]
+pub fn deadline_miss() {
+    let logs = run(&Program::new()
+        .fork_deadline(0, 4, 0, Program::new().exec(10))
+        .sleep(20));
+
+    assert_eq!(misses(&logs, 2), 1);
+}
+
+#[test]
+pub fn blocked_past_deadline() {
+    let logs = run(&Program::new()
+        .lock(1)
+        .fork_deadline(0, 5, 0, Program::new().lock(1).exec(1).unlock(1))
+        .exec(10)
+        .unlock(1)
+        .sleep(20));
+
+    // waking up from the lock does not start a new job
+    assert_eq!(misses(&logs, 2), 1);
+}
+
+#[test]
+pub fn periodic_jobs() {
+    let logs = run(&Program::new()
+        .fork_deadline(
+            0,
+            20,
+            20,
+            Program::new().repeat(5, Program::new().exec(3).sleep(6)),
+        )
+        .fork_deadline(
+            0,
+            10,
+            10,
+            Program::new().repeat(8, Program::new().exec(1).sleep(3)),
+        )
+        .sleep(50));
+
+    assert_eq!(misses(&logs, 2), 0);
+    assert_eq!(misses(&logs, 3), 0);
+}
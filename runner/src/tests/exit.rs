@@ -0,0 +1,72 @@
+use processor::{Log, Program, Simulator};
+use scheduler::{Pid, ProcessState, SchedulingDecision};
+
+use super::scheduler;
+
+/// Returns the state and the exit status of a process in every log that lists it.
+fn listings(logs: &[Log], pid: usize) -> Vec<(ProcessState, Option<i32>)> {
+    logs.iter()
+        .filter_map(|log| log.processes.get(&Pid::new(pid)))
+        .map(|process| (process.state, process.exit_status))
+        .collect()
+}
+
+#[test]
+pub fn status() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().exec(2).exit(3).exec(10))
+            .sleep(20)
+            .join(Pid::new(2)),
+    );
+
+    assert!(listings(&logs, 2).contains(&(ProcessState::Zombie, Some(3))));
+    assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Done);
+}
+
+#[test]
+pub fn zombie_until_joined() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().exec(2))
+            .sleep(20)
+            .join(Pid::new(2))
+            .exec(5),
+    );
+
+    let zombie = logs
+        .iter()
+        .position(|log| {
+            log.processes
+                .get(&Pid::new(2))
+                .is_some_and(|process| process.state == ProcessState::Zombie)
+        })
+        .unwrap();
+    // the zombie stays listed while the parent sleeps, and it is reaped by join
+    assert!(logs[zombie].start < 10);
+    assert!(logs
+        .iter()
+        .any(|log| log.start >= 20 && log.processes.contains_key(&Pid::new(2))));
+    assert!(!logs.last().unwrap().processes.contains_key(&Pid::new(2)));
+    assert!(listings(&logs, 2)
+        .iter()
+        .all(|(state, status)| (*state == ProcessState::Zombie) == status.is_some()));
+}
+
+#[test]
+pub fn orphan() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().fork(0, Program::new().sleep(10)).exit(1))
+            .sleep(30),
+    );
+
+    // pid 2 is a zombie until pid 1 exits, pid 3 is never kept since its parent has exited
+    assert!(listings(&logs, 2).contains(&(ProcessState::Zombie, Some(1))));
+    assert!(listings(&logs, 3)
+        .iter()
+        .all(|(state, _)| *state != ProcessState::Zombie));
+}
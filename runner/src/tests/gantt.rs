@@ -0,0 +1,112 @@
+use processor::{format_gantt, format_gantt_svg, Log, Program, Simulator};
+use scheduler::{round_robin, smp_round_robin, Pid};
+use std::num::NonZeroUsize;
+
+/// A child that waits for an event and a parent that sleeps.
+fn logs() -> Vec<Log> {
+    Simulator::run(
+        round_robin(NonZeroUsize::new(2).unwrap(), 1),
+        &Program::new()
+            .fork(0, Program::new().wait(1).exec(2))
+            .exec(3)
+            .signal(1)
+            .sleep(4)
+            .exec(1),
+    )
+}
+
+#[test]
+pub fn chart() {
+    assert_eq!(
+        format_gantt(&logs()),
+        "      0         10\n\
+         PID 1 ██░████zzzz██\n\
+         PID 2  ░█www░███···\n\
+         \n\
+         █ running  ░ ready  z sleeping  w waiting  b blocked  · zombie\n"
+    );
+}
+
+#[test]
+pub fn blocked() {
+    let logs = Simulator::run(
+        round_robin(NonZeroUsize::new(2).unwrap(), 1),
+        &Program::new()
+            .lock(1)
+            .fork(0, Program::new().lock(1))
+            .exec(4)
+            .unlock(1)
+            .join(Pid::new(2)),
+    );
+
+    let chart = format_gantt(&logs);
+    assert!(chart.lines().nth(2).unwrap().contains('b'));
+}
+
+#[test]
+pub fn sections() {
+    let logs = Simulator::run(
+        round_robin(NonZeroUsize::new(5).unwrap(), 1),
+        &Program::new().exec(149),
+    );
+
+    let chart = format_gantt(&logs);
+    let rows = chart
+        .lines()
+        .filter(|line| line.starts_with("PID 1"))
+        .collect::<Vec<&str>>();
+    assert_eq!(rows.len(), 2);
+    assert_eq!(rows[0].chars().count(), "PID 1 ".len() + 100);
+    assert_eq!(rows[1].chars().count(), "PID 1 ".len() + 50);
+    assert!(chart.contains("      100       110"));
+}
+
+#[test]
+pub fn cpus() {
+    let logs = Simulator::run_smp(
+        2,
+        smp_round_robin(NonZeroUsize::new(3).unwrap(), 1),
+        &Program::new().fork(0, Program::new().exec(4)).exec(4),
+    );
+
+    let chart = format_gantt(&logs);
+    assert!(chart.contains("PID 1 000000\n"));
+    assert!(chart.contains("PID 2  11111\n"));
+    assert!(chart.contains("N running on CPU N"));
+}
+
+#[test]
+pub fn many_cpus() {
+    let mut program = Program::new();
+    for _ in 0..12 {
+        program = program.fork(0, Program::new().exec(40));
+    }
+    let logs = Simulator::run_smp(
+        13,
+        smp_round_robin(NonZeroUsize::new(3).unwrap(), 1),
+        &program.exec(4),
+    );
+
+    let chart = format_gantt(&logs);
+    let rows = chart
+        .lines()
+        .filter(|line| line.starts_with("PID "))
+        .map(|line| line.split_at(7).1)
+        .collect::<String>();
+    // the CPUs above 9 are not drawn with the letters of the other states
+    assert!(rows.contains('+'));
+    assert!(!rows.contains(['b', 'w', 'z']));
+    assert!(chart.contains("+ running on CPU 10 or above"));
+}
+
+#[test]
+pub fn svg() {
+    let svg = format_gantt_svg(&logs());
+
+    assert!(svg.starts_with("<svg "));
+    assert!(svg.trim_end().ends_with("</svg>"));
+    // PID 1 runs three times, PID 2 twice
+    assert_eq!(svg.matches("<title>running").count(), 5);
+    assert!(svg.contains("<title>waiting 3-6</title>"));
+    assert!(svg.contains("<title>sleeping 7-11</title>"));
+}
@@ -0,0 +1,122 @@
+use processor::{Processor, Program, Simulator};
+use scheduler::{smp_round_robin, Pid, ProcessState, SchedulingDecision, SyncObject};
+
+use std::num::NonZeroUsize;
+
+use super::{last_decision, scheduler};
+
+#[test]
+pub fn running_child() {
+    Processor::run(scheduler(), |process| {
+        let pid = process.fork(
+            |process| {
+                for _ in 0..10 {
+                    process.exec();
+                }
+            },
+            0,
+        );
+        assert_eq!(process.join(pid), Some(0));
+        assert!(process.now() > 10);
+    });
+}
+
+#[test]
+pub fn exited_child() {
+    Processor::run(scheduler(), |process| {
+        let pid = process.fork(|process| process.exec(), 0);
+        process.sleep(10);
+        let now = process.now();
+        assert_eq!(process.join(pid), Some(0));
+        assert_eq!(process.now(), now + 1);
+    });
+}
+
+#[test]
+pub fn killed_child() {
+    Processor::run(scheduler(), |process| {
+        let sleeper = process.fork(|process| process.sleep(100), 0);
+        process.fork(
+            move |process| {
+                process.sleep(5);
+                process.kill(sleeper);
+            },
+            0,
+        );
+        assert_eq!(process.join(sleeper), Some(-9));
+        assert!(process.now() < 100);
+    });
+}
+
+#[test]
+pub fn not_a_child() {
+    Processor::run(scheduler(), |process| {
+        let pid = process.fork(
+            |process| {
+                process.fork(|process| process.sleep(5), 0);
+            },
+            0,
+        );
+        assert_eq!(process.join(pid + 1), None);
+        assert_eq!(process.join(pid + 2), None);
+        assert_eq!(process.join(process.pid), None);
+        assert_eq!(process.join(pid), Some(0));
+        assert_eq!(process.join(pid), None);
+    });
+}
+
+#[test]
+pub fn blocked_parent() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().exec(10))
+            .join(Pid::new(2)),
+    );
+
+    // the parent is blocked on its child, it is never listed as sleeping
+    let states = logs
+        .iter()
+        .filter_map(|log| log.processes.get(&Pid::new(1)))
+        .map(|process| process.state)
+        .collect::<Vec<ProcessState>>();
+    let joining = ProcessState::Blocked {
+        object: SyncObject::Child(Pid::new(2)),
+    };
+    assert!(states.contains(&joining));
+    assert!(!states.contains(&ProcessState::Waiting { event: None }));
+    assert_eq!(joining.to_string(), "JOIN 2");
+}
+
+#[test]
+pub fn deadlock() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().wait(1))
+            .join(Pid::new(2)),
+    );
+
+    assert_eq!(last_decision(&logs), SchedulingDecision::Deadlock);
+}
+
+#[test]
+pub fn smp() {
+    let logs = Simulator::run_smp(
+        2,
+        smp_round_robin(NonZeroUsize::new(5).unwrap(), 1),
+        &Program::new()
+            .fork(0, Program::new().exec(20))
+            .join(Pid::new(2))
+            .exec(1),
+    );
+
+    let run = SchedulingDecision::Run {
+        pid: Pid::new(1),
+        timeslice: NonZeroUsize::new(5).unwrap(),
+    };
+    assert!(logs
+        .iter()
+        .any(|log| log.decision == run && log.start >= 20));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
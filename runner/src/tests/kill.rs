@@ -0,0 +1,135 @@
+use processor::{Log, Processor, Program, Simulator};
+use scheduler::{smp_round_robin, Pid, SchedulingDecision};
+
+use std::num::NonZeroUsize;
+
+use super::{last_decision, scheduler};
+
+/// Returns `true` if a process is scheduled after the simulation reaches `time`.
+fn scheduled_after(logs: &[Log], pid: usize, time: usize) -> bool {
+    logs.iter().filter(|log| log.start > time).any(|log| {
+        log.cpus.iter().any(|decision| {
+            matches!(decision, Some(SchedulingDecision::Run { pid: run, .. }) if *run == Pid::new(pid))
+        })
+    })
+}
+
+/// Returns the exit status of a process, as listed while it is a zombie.
+fn exit_status(logs: &[Log], pid: usize) -> Option<i32> {
+    logs.iter()
+        .find_map(|log| log.processes.get(&Pid::new(pid))?.exit_status)
+}
+
+#[test]
+pub fn ready() {
+    let logs = Processor::run(scheduler(), |process| {
+        let pid = process.fork(
+            |process| {
+                for _ in 0..100 {
+                    process.exec();
+                }
+            },
+            0,
+        );
+        assert!(process.kill(pid));
+    });
+
+    assert!(logs.last().unwrap().start < 100);
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn waiting() {
+    let logs = Processor::run(scheduler(), |process| {
+        let pid = process.fork(|process| process.wait(1), 0);
+        process.sleep(5);
+        assert!(process.kill(pid));
+    });
+
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn sleeping() {
+    let logs = Processor::run(scheduler(), |process| {
+        let pid = process.fork(|process| process.sleep(100), 0);
+        process.sleep(5);
+        assert!(process.kill(pid));
+    });
+
+    assert!(logs.last().unwrap().start < 100);
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn no_such_process() {
+    Processor::run(scheduler(), |process| {
+        let pid = process.fork(|process| process.exec(), 0);
+        assert!(!process.kill(pid + 1));
+        process.sleep(5);
+        assert!(!process.kill(pid));
+    });
+}
+
+#[test]
+pub fn itself() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().exec(2).kill(Pid::new(2)).exec(100))
+            .sleep(50),
+    );
+
+    assert!(!scheduled_after(&logs, 2, 10));
+    assert_eq!(exit_status(&logs, 2), Some(-9));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn pid_1() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().exec(2).kill(Pid::new(1)).exec(5))
+            .wait(1),
+    );
+
+    assert_eq!(last_decision(&logs), SchedulingDecision::Panic);
+}
+
+#[test]
+pub fn running_on_another_cpu() {
+    let logs = Simulator::run_smp(
+        2,
+        smp_round_robin(NonZeroUsize::new(5).unwrap(), 1),
+        &Program::new()
+            .fork(0, Program::new().exec(100))
+            .exec(2)
+            .kill(Pid::new(2))
+            .sleep(20),
+    );
+
+    assert!(!scheduled_after(&logs, 2, 10));
+    assert_eq!(exit_status(&logs, 2), Some(-9));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn forking_on_another_cpu() {
+    let logs = Simulator::run_smp(
+        2,
+        smp_round_robin(NonZeroUsize::new(5).unwrap(), 1),
+        &Program::new()
+            .fork(0, Program::new().exec(2).fork(0, Program::new().exec(1)))
+            .exec(2)
+            .kill(Pid::new(2))
+            .sleep(20),
+    );
+
+    // the fork of PID 2, which has been killed while it ran, creates no process
+    assert!(logs
+        .iter()
+        .all(|log| !log.processes.contains_key(&Pid::new(3))));
+    assert_eq!(exit_status(&logs, 2), Some(-9));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
@@ -0,0 +1,55 @@
+use processor::{Log, Program, Simulator};
+use scheduler::{lottery, Pid};
+
+use std::num::NonZeroUsize;
+
+use super::cpu_time;
+
+fn run(seed: u64, program: &Program) -> Vec<Log> {
+    Simulator::run(lottery(NonZeroUsize::new(2).unwrap(), 1, seed), program)
+}
+
+/// Forks two processes holding 1 and 4 tickets that compete for 1000 time units.
+fn competing() -> Program {
+    Program::new()
+        .fork(0, Program::new().exec(2000))
+        .fork(3, Program::new().exec(2000))
+        .sleep(1000)
+}
+
+#[test]
+pub fn reproducible() {
+    assert_eq!(run(7, &competing()), run(7, &competing()));
+    assert_ne!(run(7, &competing()), run(8, &competing()));
+}
+
+#[test]
+pub fn proportional_share() {
+    for seed in 0..5 {
+        let logs = run(seed, &competing());
+
+        let (low, high) = (cpu_time(&logs, 2), cpu_time(&logs, 3));
+        let share = high as f64 / (low + high) as f64;
+        assert!((0.7..0.9).contains(&share), "seed {seed}: {low} {high}");
+    }
+}
+
+#[test]
+pub fn tickets_reported() {
+    let logs = run(
+        0,
+        &Program::new()
+            .fork(-1, Program::new().exec(1))
+            .fork(2, Program::new().exec(1))
+            .sleep(10),
+    );
+
+    let tickets = |pid: usize| {
+        logs.iter()
+            .find_map(|log| log.processes.get(&Pid::new(pid)))
+            .map(|process| process.extra.clone())
+            .unwrap()
+    };
+    assert_eq!(tickets(2), "tickets=1");
+    assert_eq!(tickets(3), "tickets=3");
+}
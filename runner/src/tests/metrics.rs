@@ -0,0 +1,86 @@
+use processor::metrics::{self, ProcessMetrics};
+use processor::{Program, Simulator};
+use scheduler::{round_robin, Pid};
+use std::num::NonZeroUsize;
+
+#[test]
+pub fn round_robin_sleep() {
+    let logs = Simulator::run(
+        round_robin(NonZeroUsize::new(2).unwrap(), 1),
+        &Program::new()
+            .fork(0, Program::new().exec(3))
+            .sleep(3)
+            .exec(2),
+    );
+
+    let metrics = metrics::measure(&logs);
+    assert_eq!(
+        metrics.processes,
+        vec![
+            ProcessMetrics {
+                pid: Pid::new(1),
+                arrival: 0,
+                first_run: Some(0),
+                completion: Some(9),
+                cpu_time: 5,
+                waiting_time: 0,
+                context_switches: 2,
+            },
+            ProcessMetrics {
+                pid: Pid::new(2),
+                arrival: 1,
+                first_run: Some(2),
+                completion: Some(6),
+                cpu_time: 4,
+                waiting_time: 1,
+                context_switches: 1,
+            },
+        ]
+    );
+    assert_eq!(metrics.processes[1].response_time(), Some(1));
+    assert_eq!(metrics.processes[1].turnaround_time(), Some(5));
+    assert_eq!(metrics.average_turnaround_time(), Some(7.0));
+    assert_eq!(metrics.context_switches(), 3);
+    assert_eq!(metrics.utilization(), 1.0);
+    assert_eq!(metrics.throughput(), 2.0 / 9.0);
+}
+
+#[test]
+pub fn idle() {
+    let logs = Simulator::run(
+        round_robin(NonZeroUsize::new(2).unwrap(), 1),
+        &Program::new().sleep(4).exec(1),
+    );
+
+    let metrics = metrics::measure(&logs);
+    assert_eq!(metrics.duration, 7);
+    assert_eq!(metrics.busy_time, 3);
+    assert_eq!(metrics.processes[0].context_switches, 2);
+    assert_eq!(metrics.fairness(), Some(1.0));
+}
+
+#[test]
+pub fn fairness() {
+    let logs = Simulator::run(
+        round_robin(NonZeroUsize::new(2).unwrap(), 1),
+        &Program::new().fork(0, Program::new().exec(1)).exec(20),
+    );
+
+    // the child has run as soon as it was ready, the parent has shared the CPU
+    let fairness = metrics::measure(&logs).fairness().unwrap();
+    assert!(fairness > 0.5 && fairness < 1.0);
+}
+
+#[test]
+pub fn unfinished() {
+    let logs = Simulator::run(
+        round_robin(NonZeroUsize::new(2).unwrap(), 1),
+        &Program::new().wait(1),
+    );
+
+    let metrics = metrics::measure(&logs);
+    assert_eq!(metrics.processes[0].completion, None);
+    assert_eq!(metrics.average_turnaround_time(), None);
+    assert_eq!(metrics.throughput(), 0.0);
+    assert!(metrics.to_string().contains("1\t0\t0\t\t0\t-\t\t1\t1"));
+}
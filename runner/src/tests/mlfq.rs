@@ -0,0 +1,77 @@
+use processor::{Log, Program, Simulator};
+use scheduler::{mlfq, MlfqLevel, Pid};
+
+use std::num::NonZeroUsize;
+
+use super::extra;
+
+fn level(quantum: usize, allotment: usize) -> MlfqLevel {
+    MlfqLevel::new(
+        NonZeroUsize::new(quantum).unwrap(),
+        NonZeroUsize::new(allotment).unwrap(),
+    )
+}
+
+fn run(boost_period: Option<usize>, program: &Program) -> Vec<Log> {
+    Simulator::run(
+        mlfq(
+            &[level(2, 4), level(4, 8), level(8, 8)],
+            boost_period.and_then(NonZeroUsize::new),
+            1,
+        ),
+        program,
+    )
+}
+
+#[test]
+pub fn cpu_bound_demoted() {
+    let logs = run(
+        None,
+        &Program::new().fork(0, Program::new().exec(30)).sleep(40),
+    );
+
+    assert!(extra(&logs, 2).starts_with("level=2"));
+}
+
+#[test]
+pub fn allotment_across_syscalls() {
+    // the process gives up the processor before its quantum ends,
+    // but is still moved down once it uses its allotment
+    let logs = run(
+        None,
+        &Program::new()
+            .fork(0, Program::new().repeat(4, Program::new().sleep(1)))
+            .sleep(20),
+    );
+
+    let levels = logs
+        .iter()
+        .filter_map(|log| log.processes.get(&Pid::new(2)))
+        .map(|process| process.extra.clone())
+        .collect::<Vec<String>>();
+    assert!(levels.iter().any(|extra| extra == "level=0 used=3"));
+    assert!(levels.iter().any(|extra| extra == "level=1 used=0"));
+}
+
+#[test]
+pub fn boost() {
+    let logs = run(
+        Some(20),
+        &Program::new().fork(0, Program::new().exec(60)).sleep(80),
+    );
+
+    // boosts take place at the first scheduling decision after each period
+    let boosts = logs
+        .iter()
+        .filter(|log| !log.events.is_empty())
+        .map(|log| log.start)
+        .collect::<Vec<usize>>();
+    assert_eq!(boosts, [22, 42, 62, 82]);
+    assert!(logs
+        .iter()
+        .filter(|log| !log.events.is_empty())
+        .all(|log| log
+            .processes
+            .values()
+            .all(|process| process.extra.starts_with("level=0"))));
+}
@@ -1,30 +1,51 @@
-#[cfg(feature = "cfs")]
-use scheduler::cfs;
-#[cfg(feature = "priority-queue")]
-use scheduler::priority_queue;
-#[cfg(not(any(feature = "priority-queue", feature = "cfs")))]
-use scheduler::round_robin;
-use scheduler::Scheduler;
+use scheduler::{Pid, Scheduler, SchedulingDecision};
+
+use crate::cli;
 
 use std::env;
 use std::fs;
 
-use processor::format_logs;
 use processor::Log;
-use std::num::NonZeroUsize;
+use processor::{diff_logs, format_logs, parse_logs};
 
+mod checked;
+mod clock;
+mod comparison;
 mod deadlock;
+mod diagnosis;
+mod diff;
+mod edf;
+mod exit;
+mod gantt;
+mod join;
+mod kill;
+mod lottery;
+mod metrics;
+mod mlfq;
+mod options;
 mod panic;
+mod rate_monotonic;
+mod records;
 mod simple;
+mod simulator;
+mod sjf;
+mod smp;
+mod stride;
+mod sync;
+mod trace;
+mod tree;
 mod wait_and_signal;
+mod weighted_cfs;
 mod workers;
+mod workload;
 
 fn write_logs(folder: &str, name: &str, logs: &str) {
     let (timeslice, remaining, cpu_slices) = arguments();
-    fs::create_dir_all(format!("../outputs/{SCHEDULER}/{folder}")).unwrap();
+    fs::create_dir_all(format!("../outputs/{}/{folder}", scheduler_name())).unwrap();
     fs::write(
         format!(
-            "../outputs/{SCHEDULER}/{folder}/{name}___{timeslice}_{remaining}_{cpu_slices}.log"
+            "../outputs/{}/{folder}/{name}___{timeslice}_{remaining}_{cpu_slices}.log",
+            scheduler_name()
         ),
         logs,
     )
@@ -34,19 +55,27 @@ fn write_logs(folder: &str, name: &str, logs: &str) {
 fn read_logs(folder: &str, name: &str) -> String {
     let (timeslice, remaining, cpu_slices) = arguments();
     fs::read_to_string(format!(
-        "../outputs/{SCHEDULER}/{folder}/{name}___{timeslice}_{remaining}_{cpu_slices}.log"
+        "../outputs/{}/{folder}/{name}___{timeslice}_{remaining}_{cpu_slices}.log",
+        scheduler_name()
     ))
     .unwrap()
 }
 
 fn run(folder: &str, name: &str, logs: &[Log]) {
-    let output = format_logs(&logs);
+    let output = format_logs(logs);
 
     if env::var("WRITE_OUTPUT").is_ok() {
         write_logs(folder, name, &output);
     } else {
         let reference = read_logs(folder, name);
 
+        // the first diverging iteration is easier to read than the whole diff
+        if let (Ok(expected), Ok(actual)) = (parse_logs(&reference), parse_logs(&output)) {
+            if let Some(diff) = diff_logs(&expected, &actual) {
+                panic!("\nThe output diverges from the correct output\n\n{}", diff);
+            }
+        }
+
         println!("\nleft = Correct Output\nright = Your Output\n");
         use pretty_assertions::assert_eq;
         assert_eq!(reference, output);
@@ -71,41 +100,87 @@ fn arguments() -> (usize, usize, usize) {
 
 #[cfg(feature = "round-robin")]
 static SCHEDULER: &str = "round-robin";
-#[cfg(feature = "round-robin")]
-fn scheduler() -> impl Scheduler {
-    let (timeslice, remaining, cpu_slices) = arguments();
-
-    println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
-    round_robin(NonZeroUsize::new(timeslice).unwrap(), remaining)
-}
-
 #[cfg(feature = "priority-queue")]
 static SCHEDULER: &str = "priority-queue";
-#[cfg(feature = "priority-queue")]
-fn scheduler() -> impl Scheduler {
+#[cfg(feature = "cfs")]
+static SCHEDULER: &str = "cfs";
+#[cfg(feature = "weighted-cfs")]
+static SCHEDULER: &str = "weighted-cfs";
+#[cfg(feature = "edf")]
+static SCHEDULER: &str = "edf";
+#[cfg(feature = "rate-monotonic")]
+static SCHEDULER: &str = "rate-monotonic";
+#[cfg(feature = "mlfq")]
+static SCHEDULER: &str = "mlfq";
+#[cfg(feature = "lottery")]
+static SCHEDULER: &str = "lottery";
+#[cfg(feature = "stride")]
+static SCHEDULER: &str = "stride";
+#[cfg(feature = "sjf")]
+static SCHEDULER: &str = "sjf";
+#[cfg(feature = "srtf")]
+static SCHEDULER: &str = "srtf";
+#[cfg(not(any(
+    feature = "round-robin",
+    feature = "priority-queue",
+    feature = "cfs",
+    feature = "edf",
+    feature = "rate-monotonic",
+    feature = "mlfq",
+    feature = "lottery",
+    feature = "stride",
+    feature = "weighted-cfs",
+    feature = "sjf",
+    feature = "srtf"
+)))]
+static SCHEDULER: &str = "no-scheduler";
+
+/// Returns the scheduler selected by the `SCHEDULER` environment variable
+/// or, if it is not set, by the enabled feature.
+fn scheduler() -> Box<dyn Scheduler> {
     let (timeslice, remaining, cpu_slices) = arguments();
 
     println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
+    let name = match scheduler_name().as_str() {
+        "no-scheduler" => String::from("round-robin"),
+        name => String::from(name),
+    };
+    cli::scheduler(&name, timeslice, remaining, cpu_slices).unwrap()
+}
 
-    priority_queue(NonZeroUsize::new(timeslice).unwrap(), remaining)
+/// Returns the name of the scheduler, which is also the folder of its outputs.
+fn scheduler_name() -> String {
+    env::var("SCHEDULER").unwrap_or(SCHEDULER.to_string())
 }
 
-#[cfg(feature = "cfs")]
-static SCHEDULER: &str = "cfs";
-#[cfg(feature = "cfs")]
-fn scheduler() -> impl Scheduler {
-    let (timeslice, remaining, cpu_slices) = arguments();
+/// Returns the decision that has stopped the simulation.
+fn last_decision(logs: &[Log]) -> SchedulingDecision {
+    logs.last().unwrap().decision
+}
 
-    println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
-    cfs(NonZeroUsize::new(cpu_slices).unwrap(), remaining)
+/// Returns the extra details of a process the last time it was listed.
+fn extra(logs: &[Log], pid: usize) -> String {
+    logs.iter()
+        .rev()
+        .find_map(|log| log.processes.get(&Pid::new(pid)))
+        .map(|process| process.extra.clone())
+        .unwrap()
 }
 
-#[cfg(not(any(feature = "round-robin", feature = "priority-queue", feature = "cfs")))]
-static SCHEDULER: &str = "no-scheduler";
-#[cfg(not(any(feature = "round-robin", feature = "priority-queue", feature = "cfs")))]
-fn scheduler() -> impl Scheduler {
-    let (timeslice, remaining, cpu_slices) = arguments();
+/// Returns the amount of time that a process has spent running.
+fn cpu_time(logs: &[Log], pid: usize) -> usize {
+    logs.iter()
+        .filter(|log| {
+            matches!(log.decision, SchedulingDecision::Run { pid: run, .. } if run == Pid::new(pid))
+        })
+        .map(|log| log.end - log.start)
+        .sum()
+}
 
-    println!("Timeslice {timeslice}\nRemaining {remaining}\nCPU slices: {cpu_slices}");
-    round_robin(NonZeroUsize::new(timeslice).unwrap(), remaining)
+/// Returns the number of deadlines missed by a real-time process.
+fn misses(logs: &[Log], pid: usize) -> usize {
+    extra(logs, pid)
+        .split_once("misses=")
+        .and_then(|(_, misses)| misses.parse().ok())
+        .unwrap()
 }
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use crate::cli::{self, Format, Options};
+
+/// Parses a command line given as a single string.
+fn parse(line: &str) -> Result<Options, String> {
+    Options::parse(line.split_whitespace().map(String::from))
+}
+
+#[test]
+pub fn defaults() {
+    assert_eq!(parse(""), Ok(Options::default()));
+}
+
+#[test]
+pub fn options() {
+    let options =
+        parse("--scheduler cfs --timeslice 5 --remaining 2 --cpu-slices 18 --workload a.txt")
+            .unwrap();
+    assert_eq!(options.scheduler, "cfs");
+    assert_eq!(options.timeslice, 5);
+    assert_eq!(options.remaining, 2);
+    assert_eq!(options.cpu_slices, 18);
+    assert_eq!(options.workload, Some(PathBuf::from("a.txt")));
+    assert!(!options.compare);
+    assert!(parse("compare").unwrap().compare);
+    assert_eq!(parse("--format gantt").unwrap().format, Format::Gantt);
+    assert_eq!(parse("--format trace").unwrap().format, Format::Trace);
+    assert_eq!(parse("--format json").unwrap().format, Format::Json);
+    assert_eq!(parse("--format csv").unwrap().format, Format::Csv);
+}
+
+#[test]
+pub fn errors() {
+    assert!(parse("--scheduler fifo").is_err());
+    assert!(parse("--timeslice").is_err());
+    assert!(parse("--timeslice three").is_err());
+    assert!(parse("--timeslice 0").is_err());
+    assert!(parse("--cpu-slices 0 --scheduler cfs").is_err());
+    assert_eq!(
+        parse("--scheduler cfs --remaining 0"),
+        Err(String::from("--remaining cannot be 0"))
+    );
+    assert!(parse("--scheduler weighted-cfs --remaining 0").is_err());
+    assert_eq!(
+        parse("--scheduler mlfq --timeslice 18446744073709551615"),
+        Err(String::from("--timeslice is too large"))
+    );
+    assert_eq!(
+        parse("--scheduler mlfq --timeslice 1000 --cpu-slices 18446744073709551615"),
+        Err(String::from("--cpu-slices is too large"))
+    );
+    assert!(parse("--verbose").is_err());
+    assert!(parse("--format html").is_err());
+}
+
+#[test]
+pub fn every_scheduler() {
+    for name in [
+        "rr",
+        "pq",
+        "cfs",
+        "weighted-cfs",
+        "edf",
+        "rm",
+        "mlfq",
+        "lottery",
+        "stride",
+        "sjf",
+        "srtf",
+    ] {
+        assert!(cli::scheduler(name, 3, 1, 10).is_ok(), "{}", name);
+    }
+}
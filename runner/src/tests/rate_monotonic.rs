@@ -0,0 +1,97 @@
+use processor::{Processor, Program, Simulator};
+use scheduler::analysis::{self, Task};
+use scheduler::{rate_monotonic, Pid, SchedulingDecision, StopReason, Syscall};
+
+use std::num::NonZeroUsize;
+
+use super::misses;
+
+fn timeslice() -> NonZeroUsize {
+    NonZeroUsize::new(3).unwrap()
+}
+
+/// Runs `jobs` jobs of every task and returns the deadlines missed by each task.
+fn simulate(tasks: &[Task], jobs: usize) -> Vec<usize> {
+    let duration = jobs * tasks.iter().map(|task| task.period).max().unwrap();
+    let logs = Processor::run(rate_monotonic(timeslice(), 1), |process| {
+        for task in tasks.iter().copied() {
+            // the first job is released when the fork system call ends
+            let mut release = process.now() + 1;
+            process.fork_deadline(
+                move |process| {
+                    for _ in 0..jobs {
+                        for _ in 1..task.execution {
+                            process.exec();
+                        }
+                        release += task.period;
+                        let now = process.now();
+                        process.sleep(release.saturating_sub(now + 1).max(1));
+                    }
+                },
+                0,
+                task.deadline,
+                task.period,
+            );
+        }
+        process.sleep(2 * duration);
+    });
+
+    (0..tasks.len())
+        .map(|task| misses(&logs, task + 2))
+        .collect()
+}
+
+#[test]
+pub fn shortest_period_first() {
+    let logs = Simulator::run(
+        rate_monotonic(timeslice(), 1),
+        &Program::new()
+            .fork_deadline(0, 5, 40, Program::new().wait(1).exec(5))
+            .fork_deadline(0, 50, 20, Program::new().wait(1).exec(5))
+            .sleep(1)
+            .signal(1)
+            .sleep(20),
+    );
+
+    // the process with the shorter period runs first after the signal
+    let signal = logs
+        .iter()
+        .position(|log| {
+            matches!(
+                log.stop_reason,
+                Some((
+                    StopReason::Syscall {
+                        syscall: Syscall::Signal(1),
+                        ..
+                    },
+                    _
+                ))
+            )
+        })
+        .unwrap();
+    let first = logs[signal..].iter().find_map(|log| match log.decision {
+        SchedulingDecision::Run { pid, .. } if pid != 1 => Some(pid),
+        _ => None,
+    });
+    assert_eq!(first, Some(Pid::new(3)));
+}
+
+#[test]
+pub fn schedulable_task_set() {
+    let tasks = [Task::new(1, 8), Task::new(2, 10), Task::new(3, 20)];
+    let report = analysis::rate_monotonic(&tasks);
+
+    assert!(report.passes_bound());
+    assert!(report.is_schedulable());
+    assert!(report.is_confirmed_by(&simulate(&tasks, 5)));
+}
+
+#[test]
+pub fn overloaded_task_set() {
+    let tasks = [Task::new(2, 4), Task::new(4, 6)];
+    let report = analysis::rate_monotonic(&tasks);
+
+    assert!(!report.passes_bound());
+    assert_eq!(report.response_times, [Some(2), None]);
+    assert!(report.is_confirmed_by(&simulate(&tasks, 5)));
+}
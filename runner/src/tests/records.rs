@@ -0,0 +1,131 @@
+use processor::{
+    format_logs_csv, format_logs_json, parse_logs_json, Log, Processor, Program, Simulator,
+};
+use scheduler::{smp_round_robin, Pid, SchedulingDecision};
+use std::num::NonZeroUsize;
+
+use super::scheduler;
+
+/// Writes the logs as JSON Lines and loads them back.
+fn round_trip(logs: &[Log]) -> Vec<Log> {
+    parse_logs_json(&format_logs_json(logs)).unwrap()
+}
+
+#[test]
+pub fn processes() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(
+                2,
+                Program::new().lock(1).exec(2).unlock(1).sem_post(1).exit(3),
+            )
+            .fork_deadline(1, 10, 20, Program::new().wait(1).exec(1))
+            .fork_burst(0, 4, Program::new().sem_wait(1).exec(5))
+            .exec(2)
+            .lock(1)
+            .signal(1)
+            .unlock(1)
+            .sleep(3)
+            .kill(Pid::new(4))
+            .join(Pid::new(2))
+            .join(Pid::new(3))
+            .join(Pid::new(4)),
+    );
+
+    assert_eq!(round_trip(&logs), logs);
+}
+
+#[test]
+pub fn deadlock() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .lock(1)
+            .fork(0, Program::new().lock(2).sleep(5).lock(1))
+            .sleep(2)
+            .lock(2),
+    );
+
+    let loaded = round_trip(&logs);
+    assert_eq!(
+        loaded.last().unwrap().decision,
+        SchedulingDecision::Deadlock
+    );
+    assert!(loaded.last().unwrap().deadlock.is_some());
+    assert_eq!(loaded, logs);
+}
+
+#[test]
+pub fn cpus() {
+    let logs = Simulator::run_smp(
+        2,
+        smp_round_robin(NonZeroUsize::new(3).unwrap(), 1),
+        &Program::new().fork(0, Program::new().exec(4)).exec(4),
+    );
+
+    assert_eq!(round_trip(&logs), logs);
+}
+
+#[test]
+pub fn threads() {
+    let logs = Processor::run(scheduler(), |process| {
+        process.exec();
+        let child = process.fork(|process| process.exit(-1), 5);
+        process.join(child);
+    });
+
+    assert_eq!(round_trip(&logs), logs);
+}
+
+#[test]
+pub fn errors() {
+    let json = format_logs_json(&Simulator::run(scheduler(), &Program::new().exec(1)));
+    let first = json.lines().next().unwrap();
+
+    // empty lines are skipped
+    let text = format!("\n{}\n\n", first);
+    assert_eq!(parse_logs_json(&text).unwrap().len(), 1);
+
+    let text = format!("{}\n{{\"decision\":{{\"type\":\"Run\"}}}}\n", first);
+    let error = parse_logs_json(&text).unwrap_err();
+    assert_eq!(error.line, 2);
+    assert_eq!(error.message, "missing `pid`");
+
+    let error = parse_logs_json(&first.replace("\"pid\":1,", "\"pid\":0,")).unwrap_err();
+    assert_eq!(error.message, "0 is out of range");
+
+    let error = parse_logs_json(&first[..first.len() - 1]).unwrap_err();
+    assert_eq!(error.line, 1);
+    assert!(parse_logs_json("[1, 2]").is_err());
+}
+
+#[test]
+pub fn csv() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().exec(2))
+            .exec(3)
+            .join(Pid::new(2)),
+    );
+
+    let csv = format_logs_csv(&logs);
+    let lines = csv.lines().collect::<Vec<&str>>();
+    assert_eq!(
+        lines[0],
+        "iteration,start,end,cpu,decision,decision_pid,decision_time,stop_reason,syscall,\
+         remaining,result,pid,state,priority,total_time,syscall_time,execution_time,\
+         exit_status,ppid,extra"
+    );
+    // a row for each process of each iteration, or an empty row
+    let rows = logs
+        .iter()
+        .map(|log| log.processes.len().max(1))
+        .sum::<usize>();
+    assert_eq!(lines.len(), rows + 1);
+    assert!(lines[1].starts_with("0,0,"));
+    assert!(lines[1].contains(",Run,1,"));
+    assert!(lines.iter().any(|line| line.contains(",Syscall,Fork(0),")));
+    assert!(lines.last().unwrap().contains(",Done,,,"));
+}
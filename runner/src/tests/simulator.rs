@@ -0,0 +1,165 @@
+use processor::{format_logs, Processor, Program, Simulator};
+use scheduler::{round_robin, smp_round_robin, Pid, SchedulingDecision};
+use std::num::NonZeroUsize;
+
+use super::scheduler;
+
+/// Runs `program` with both engines and checks that the logs are identical.
+fn compare(program: Program) {
+    let threads = Processor::run(scheduler(), |process| program.run(process));
+    let simulator = Simulator::run(scheduler(), &program);
+
+    use pretty_assertions::assert_eq;
+    assert_eq!(format_logs(&threads), format_logs(&simulator));
+}
+
+#[test]
+pub fn work_sleep() {
+    compare(Program::new().repeat(3, Program::new().exec(3).sleep(10)));
+}
+
+#[test]
+pub fn worker_spawning() {
+    compare(
+        Program::new()
+            .fork(5, Program::new().exec(20).fork(5, Program::new().exec(20)))
+            .exec(50),
+    );
+}
+
+#[test]
+pub fn senders() {
+    compare(
+        Program::new()
+            .fork(0, Program::new().wait(1).signal(2))
+            .fork(0, Program::new().wait(2).signal(3))
+            .fork(0, Program::new().wait(3))
+            .fork(0, Program::new().wait(3))
+            .exec(10)
+            .signal(1)
+            .sleep(10),
+    );
+}
+
+#[test]
+pub fn deadlock() {
+    // each process signals the event of the other one after its own wait
+    compare(
+        Program::new()
+            .fork(0, Program::new().exec(5).wait(2).signal(1))
+            .sleep(10)
+            .wait(1)
+            .signal(2)
+            .sleep(10),
+    );
+}
+
+#[test]
+pub fn exit() {
+    compare(
+        Program::new()
+            .fork(0, Program::new().exec(2).exit(3).exec(10))
+            .repeat(2, Program::new().exec(3).sleep(2))
+            .exit(0)
+            .exec(10),
+    );
+}
+
+#[test]
+pub fn kill() {
+    compare(
+        Program::new()
+            .fork(0, Program::new().exec(20))
+            .fork(0, Program::new().wait(1))
+            .exec(2)
+            .kill(Pid::new(2))
+            .kill(Pid::new(3))
+            .kill(Pid::new(3))
+            .sleep(5),
+    );
+}
+
+#[test]
+pub fn kill_on_other_cpu() {
+    let program = Program::new()
+        .fork(0, Program::new().exec(20).sleep(5))
+        .exec(2)
+        .kill(Pid::new(2))
+        .sleep(10);
+    let scheduler = || smp_round_robin(NonZeroUsize::new(10).unwrap(), 1);
+    let threads = Processor::run_smp(2, scheduler(), |process| program.run(process));
+    let simulator = Simulator::run_smp(2, scheduler(), &program);
+
+    use pretty_assertions::assert_eq;
+    assert_eq!(format_logs(&threads), format_logs(&simulator));
+}
+
+#[test]
+pub fn join() {
+    compare(
+        Program::new()
+            .fork(0, Program::new().exec(10))
+            .fork(0, Program::new().exec(2).sleep(5))
+            .join(Pid::new(3))
+            .join(Pid::new(2))
+            .exec(2),
+    );
+}
+
+#[test]
+pub fn many_workers() {
+    compare(
+        Program::new()
+            .repeat(
+                200,
+                Program::new().fork(3, Program::new().repeat(3, Program::new().exec(2).sleep(1))),
+            )
+            .exec(100),
+    );
+}
+
+#[test]
+pub fn unlisted() {
+    let program = Program::new()
+        .fork(0, Program::new().exec(2).sleep(3))
+        .exec(5);
+    let listed = Simulator::run(scheduler(), &program);
+    let unlisted = Simulator::run_unlisted(scheduler(), &program);
+
+    assert_eq!(listed.len(), unlisted.len());
+    for (listed, unlisted) in listed.iter().zip(unlisted.iter()) {
+        assert!(unlisted.processes.is_empty());
+        assert_eq!(
+            (
+                listed.decision,
+                listed.stop_reason,
+                listed.start,
+                listed.end
+            ),
+            (
+                unlisted.decision,
+                unlisted.stop_reason,
+                unlisted.start,
+                unlisted.end
+            )
+        );
+    }
+}
+
+#[test]
+pub fn ten_thousand_processes() {
+    // a fast policy, so that the time of the engine is measured
+    let logs = Simulator::run_unlisted(
+        round_robin(NonZeroUsize::new(2).unwrap(), 1),
+        &Program::new()
+            .repeat(10_000, Program::new().fork(0, Program::new().exec(1)))
+            .sleep(100_000),
+    );
+
+    let runs = logs
+        .iter()
+        .filter(|log| matches!(log.decision, SchedulingDecision::Run { .. }))
+        .count();
+    assert!(runs > 10_000);
+    assert_eq!(logs.last().unwrap().decision, SchedulingDecision::Done);
+}
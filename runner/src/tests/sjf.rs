@@ -0,0 +1,92 @@
+use processor::{Log, Program, Simulator};
+use scheduler::{sjf, srtf, Pid, SchedulingDecision};
+
+use std::num::NonZeroUsize;
+
+/// Returns the time at which a process has last stopped running.
+fn finish_time(logs: &[Log], pid: usize) -> usize {
+    logs.iter()
+        .filter(|log| {
+            matches!(log.decision, SchedulingDecision::Run { pid: run, .. } if run == Pid::new(pid))
+        })
+        .map(|log| log.end)
+        .max()
+        .unwrap()
+}
+
+/// Returns the extra details of a process in every log, without repetitions.
+fn extras(logs: &[Log], pid: usize) -> Vec<String> {
+    let mut extras = logs
+        .iter()
+        .filter_map(|log| log.processes.get(&Pid::new(pid)))
+        .map(|process| process.extra.clone())
+        .collect::<Vec<String>>();
+    extras.dedup();
+    extras
+}
+
+/// A long job is running when a short job arrives.
+fn short_job_arrives() -> Program {
+    Program::new()
+        .fork_burst(0, 50, Program::new().exec(50))
+        .sleep(5)
+        .fork_burst(0, 2, Program::new().exec(2))
+        .sleep(100)
+}
+
+#[test]
+pub fn shortest_hint_first() {
+    let logs = Simulator::run(
+        sjf(NonZeroUsize::new(3).unwrap(), 0.5, 10),
+        &Program::new()
+            .fork_burst(0, 30, Program::new().exec(30))
+            .fork_burst(0, 10, Program::new().exec(10))
+            .fork_burst(0, 20, Program::new().exec(20))
+            .sleep(100),
+    );
+
+    assert!(finish_time(&logs, 3) < finish_time(&logs, 4));
+    assert!(finish_time(&logs, 4) < finish_time(&logs, 2));
+    assert_eq!(extras(&logs, 3), ["hint=10"]);
+}
+
+#[test]
+pub fn exponential_average() {
+    let logs = Simulator::run(
+        sjf(NonZeroUsize::new(3).unwrap(), 0.5, 10),
+        &Program::new()
+            .fork(0, Program::new().repeat(3, Program::new().exec(6).sleep(1)))
+            .sleep(50),
+    );
+
+    // bursts of 6 time units, starting from a prediction of 10
+    assert_eq!(
+        extras(&logs, 2),
+        [
+            "predicted=10.00 alpha=0.5",
+            "predicted=8.00 alpha=0.5",
+            "predicted=7.00 alpha=0.5",
+            "predicted=6.50 alpha=0.5"
+        ]
+    );
+}
+
+#[test]
+pub fn non_preemptive() {
+    let logs = Simulator::run(
+        sjf(NonZeroUsize::new(3).unwrap(), 0.5, 10),
+        &short_job_arrives(),
+    );
+
+    assert!(finish_time(&logs, 2) < finish_time(&logs, 3));
+}
+
+#[test]
+pub fn preemptive() {
+    let logs = Simulator::run(
+        srtf(NonZeroUsize::new(3).unwrap(), 0.5, 10),
+        &short_job_arrives(),
+    );
+
+    assert!(finish_time(&logs, 3) < finish_time(&logs, 2));
+}
@@ -0,0 +1,84 @@
+use processor::{Log, Program, Simulator};
+use scheduler::{smp_round_robin, Pid, SchedulingDecision};
+use std::num::NonZeroUsize;
+
+/// Runs the program on `cpus` CPUs with a shared round robin queue.
+fn run(cpus: usize, program: &Program) -> Vec<Log> {
+    Simulator::run_smp(
+        cpus,
+        smp_round_robin(NonZeroUsize::new(3).unwrap(), 1),
+        program,
+    )
+}
+
+/// Returns the PIDs that the CPUs run after the log.
+fn running(log: &Log) -> Vec<Pid> {
+    log.cpus
+        .iter()
+        .filter_map(|decision| match decision {
+            Some(SchedulingDecision::Run { pid, .. }) => Some(*pid),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+pub fn per_cpu_decisions() {
+    let logs = run(
+        2,
+        &Program::new()
+            .fork(0, Program::new().exec(6))
+            .exec(6)
+            .join(Pid::new(2)),
+    );
+
+    for log in logs.iter() {
+        assert_eq!(log.cpus.len(), 2);
+        // the decision of the log is the action of its CPU
+        assert_eq!(log.cpus[log.cpu], Some(log.decision));
+        // a process never runs on two CPUs at the same time
+        let pids = running(log);
+        assert!(pids.len() < 2 || pids[0] != pids[1], "{:?}", log.cpus);
+    }
+    // both processes run at the same time, each CPU running one of them
+    assert!(logs.iter().any(|log| {
+        let mut pids = running(log);
+        pids.sort();
+        pids == [Pid::new(1), Pid::new(2)]
+    }));
+    for cpu in 0..2 {
+        assert!(logs
+            .iter()
+            .any(|log| log.cpu == cpu && matches!(log.decision, SchedulingDecision::Run { .. })));
+    }
+}
+
+#[test]
+pub fn idle_cpu() {
+    let logs = run(3, &Program::new().exec(5));
+
+    // the only process runs on the first CPU, the other CPUs sleep
+    for log in logs.iter() {
+        match log.decision {
+            SchedulingDecision::Run { pid, .. } => {
+                assert_eq!((log.cpu, pid), (0, Pid::new(1)));
+            }
+            SchedulingDecision::Sleep(_) => assert_ne!(log.cpu, 0),
+            SchedulingDecision::Done => {}
+            decision => panic!("unexpected decision {}", decision),
+        }
+    }
+    // the idle CPUs sleep for the whole simulation, without gaps
+    let end = logs.last().unwrap().end;
+    for cpu in 1..3 {
+        let sleeps = logs
+            .iter()
+            .filter(|log| log.cpu == cpu)
+            .collect::<Vec<&Log>>();
+        assert_eq!(sleeps.first().unwrap().start, 0);
+        assert_eq!(sleeps.last().unwrap().end, end);
+        for sleeps in sleeps.windows(2) {
+            assert_eq!(sleeps[0].end, sleeps[1].start);
+        }
+    }
+}
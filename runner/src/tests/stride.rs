@@ -0,0 +1,52 @@
+use processor::{Log, Program, Simulator};
+use scheduler::{stride, Pid};
+
+use std::num::NonZeroUsize;
+
+use super::cpu_time;
+
+fn run(program: &Program) -> Vec<Log> {
+    Simulator::run(stride(NonZeroUsize::new(2).unwrap(), 1), program)
+}
+
+/// Forks three processes holding 1, 2 and 4 tickets that compete for 280 time units.
+fn competing() -> Program {
+    Program::new()
+        .fork(0, Program::new().exec(1000))
+        .fork(1, Program::new().exec(1000))
+        .fork(3, Program::new().exec(1000))
+        .sleep(280)
+}
+
+#[test]
+pub fn proportional_share() {
+    let logs = run(&competing());
+
+    let times = [cpu_time(&logs, 2), cpu_time(&logs, 3), cpu_time(&logs, 4)];
+    let total = times.iter().sum::<usize>();
+    for (time, tickets) in times.iter().zip([1, 2, 4]) {
+        // a process can be at most one timeslice away from its share
+        assert!(time.abs_diff(total * tickets / 7) <= 2, "{times:?}");
+    }
+}
+
+#[test]
+pub fn deterministic() {
+    assert_eq!(run(&competing()), run(&competing()));
+}
+
+#[test]
+pub fn pass_reported() {
+    let logs = run(&Program::new().fork(1, Program::new().exec(10)).sleep(20));
+
+    let extras = logs
+        .iter()
+        .filter_map(|log| log.processes.get(&Pid::new(2)))
+        .map(|process| process.extra.clone())
+        .collect::<Vec<String>>();
+    assert!(extras
+        .iter()
+        .all(|extra| extra.starts_with("tickets=2 pass=")));
+    // the pass grows by 5000 for each time unit the process runs
+    assert!(extras.iter().any(|extra| extra == "tickets=2 pass=10000"));
+}
@@ -0,0 +1,220 @@
+use processor::{Log, Processor, Program, Simulator};
+use scheduler::{
+    priority_queue, priority_queue_inheritance, smp_round_robin, Pid, ProcessState,
+    SchedulingDecision, SyncObject,
+};
+
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+use super::{last_decision, scheduler};
+
+/// Returns `true` if a process is listed as blocked on `object`.
+fn blocked_on(logs: &[Log], pid: usize, object: SyncObject) -> bool {
+    logs.iter().any(|log| {
+        log.processes
+            .get(&Pid::new(pid))
+            .is_some_and(|process| process.state == ProcessState::Blocked { object })
+    })
+}
+
+/// Returns the index of the last log that lists a process.
+fn last_listed(logs: &[Log], pid: usize) -> usize {
+    logs.iter()
+        .rposition(|log| log.processes.contains_key(&Pid::new(pid)))
+        .unwrap()
+}
+
+/// Returns `true` if any priority inversion has been reported.
+fn inversions(logs: &[Log]) -> bool {
+    logs.iter().any(|log| {
+        log.events
+            .iter()
+            .any(|event| event.starts_with("Priority inversion"))
+    })
+}
+
+/// A process with priority `1` that holds a lock, while a process with
+/// priority `5` waits for the lock and a process with priority `3` runs.
+fn pathfinder() -> Program {
+    Program::new()
+        .fork(
+            1,
+            Program::new()
+                .lock(1)
+                .fork(5, Program::new().lock(1).exec(2).unlock(1))
+                .fork(3, Program::new().exec(30))
+                .exec(10)
+                .unlock(1),
+        )
+        .sleep(200)
+}
+
+#[test]
+pub fn mutual_exclusion() {
+    let inside = Arc::new(AtomicBool::new(false));
+    let logs = Processor::run(scheduler(), move |process| {
+        let mut pids = Vec::new();
+        for _ in 0..3 {
+            let inside = inside.clone();
+            let pid = process.fork(
+                move |process| {
+                    process.lock(1);
+                    assert!(!inside.swap(true, Ordering::SeqCst));
+                    process.exec();
+                    process.sleep(2);
+                    process.exec();
+                    inside.store(false, Ordering::SeqCst);
+                    assert!(process.unlock(1));
+                },
+                0,
+            );
+            pids.push(pid);
+        }
+        for pid in pids {
+            process.join(pid);
+        }
+    });
+
+    assert!((2..=4).any(|pid| blocked_on(&logs, pid, SyncObject::Lock(1))));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn not_owner() {
+    Processor::run(scheduler(), |process| {
+        assert!(!process.unlock(1));
+        process.fork(|process| process.lock(1), 0);
+        process.sleep(5);
+        assert!(!process.unlock(1));
+    });
+}
+
+#[test]
+pub fn semaphore() {
+    let logs = Processor::run(scheduler(), |process| {
+        let pid = process.fork(
+            |process| {
+                for _ in 0..10 {
+                    process.exec();
+                }
+                process.sem_post(1);
+                process.sem_post(1);
+            },
+            0,
+        );
+        process.sem_wait(1);
+        assert!(process.now() > 10);
+        process.join(pid);
+        // the second post has incremented the semaphore
+        let now = process.now();
+        process.sem_wait(1);
+        assert_eq!(process.now(), now + 1);
+    });
+
+    assert!(blocked_on(&logs, 1, SyncObject::Semaphore(1)));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn deadlock() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .lock(1)
+            .fork(0, Program::new().lock(2).sleep(5).lock(1))
+            .sleep(2)
+            .lock(2),
+    );
+
+    assert_eq!(last_decision(&logs), SchedulingDecision::Deadlock);
+}
+
+#[test]
+pub fn released_on_exit() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().lock(1).sleep(5))
+            .sleep(2)
+            .lock(1)
+            .exec(1),
+    );
+
+    // PID 2 exits holding the lock, which is handed to PID 1
+    assert!(blocked_on(&logs, 1, SyncObject::Lock(1)));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn released_on_kill() {
+    // PID 2 holds the lock until it is killed, PID 3 asks for it meanwhile
+    let program = Program::new()
+        .fork(0, Program::new().lock(1).exec(5).wait(1))
+        .fork(0, Program::new().sleep(10).lock(1).exec(1))
+        .sleep(20)
+        .kill(Pid::new(2))
+        .join(Pid::new(3));
+    let single = Simulator::run(scheduler(), &program);
+    // on two CPUs, PID 2 is killed while it runs
+    let smp = Simulator::run_smp(
+        2,
+        smp_round_robin(NonZeroUsize::new(5).unwrap(), 1),
+        &Program::new()
+            .fork(0, Program::new().lock(1).exec(50))
+            .fork(0, Program::new().sleep(5).lock(1).exec(1))
+            .exec(10)
+            .kill(Pid::new(2))
+            .join(Pid::new(3)),
+    );
+
+    for logs in [single, smp] {
+        assert!(blocked_on(&logs, 3, SyncObject::Lock(1)));
+        assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+    }
+}
+
+#[test]
+pub fn smp() {
+    let logs = Simulator::run_smp(
+        2,
+        smp_round_robin(NonZeroUsize::new(5).unwrap(), 1),
+        &Program::new()
+            .fork(0, Program::new().lock(1).exec(20).unlock(1))
+            .exec(2)
+            .lock(1)
+            .exec(1),
+    );
+
+    assert!(blocked_on(&logs, 1, SyncObject::Lock(1)));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn priority_inversion() {
+    let logs = Simulator::run(
+        priority_queue(NonZeroUsize::new(3).unwrap(), 1),
+        &pathfinder(),
+    );
+
+    assert!(inversions(&logs));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
+
+#[test]
+pub fn priority_inheritance() {
+    let logs = Simulator::run(
+        priority_queue_inheritance(NonZeroUsize::new(3).unwrap(), 1),
+        &pathfinder(),
+    );
+
+    assert!(!inversions(&logs));
+    assert!(logs.iter().any(|log| {
+        log.processes
+            .get(&Pid::new(2))
+            .is_some_and(|process| process.priority == 5 && process.extra.starts_with("base="))
+    }));
+    assert!(last_listed(&logs, 3) < last_listed(&logs, 4));
+    assert_eq!(last_decision(&logs), SchedulingDecision::Done);
+}
@@ -0,0 +1,57 @@
+use processor::{format_chrome_trace, Log, Program, Simulator};
+use scheduler::{round_robin, Pid, SchedulingDecision};
+use std::num::NonZeroUsize;
+
+/// A child that waits for an event signaled by its parent.
+fn logs() -> Vec<Log> {
+    Simulator::run(
+        round_robin(NonZeroUsize::new(2).unwrap(), 1),
+        &Program::new()
+            .fork(0, Program::new().wait(1).exec(2))
+            .exec(3)
+            .signal(1)
+            .join(Pid::new(2)),
+    )
+}
+
+#[test]
+pub fn threads() {
+    let trace = format_chrome_trace(&logs());
+
+    assert!(trace.starts_with("{\"traceEvents\":[\n"));
+    assert!(trace.ends_with("\n]}\n"));
+    assert!(trace.contains("\"tid\":1,\"name\":\"thread_name\",\"args\":{\"name\":\"PID 1\"}"));
+    assert!(trace.contains("\"tid\":2,\"name\":\"thread_name\",\"args\":{\"name\":\"PID 2\"}"));
+}
+
+#[test]
+pub fn slices() {
+    let trace = format_chrome_trace(&logs());
+
+    // every run is a slice
+    let runs = logs()
+        .iter()
+        .filter(|log| matches!(log.decision, SchedulingDecision::Run { .. }))
+        .count();
+    assert_eq!(trace.matches("\"ph\":\"X\"").count(), runs);
+    assert!(trace.contains("\"name\":\"Fork\""));
+    assert!(trace.contains("\"name\":\"Signal\""));
+    assert!(trace.contains("\"syscall\":\"Wait(1)\""));
+}
+
+#[test]
+pub fn flows() {
+    let trace = format_chrome_trace(&logs());
+
+    let start = trace
+        .lines()
+        .find(|line| line.contains("\"ph\":\"s\""))
+        .unwrap();
+    let end = trace
+        .lines()
+        .find(|line| line.contains("\"ph\":\"f\""))
+        .unwrap();
+    assert!(start.contains("\"tid\":1,") && start.contains("\"id\":1,"));
+    assert!(end.contains("\"tid\":2,") && end.contains("\"id\":1,"));
+    assert_eq!(trace.matches("\"ph\":\"s\"").count(), 1);
+}
@@ -0,0 +1,37 @@
+use processor::{format_logs_tree, Program, Simulator};
+use scheduler::Pid;
+
+use super::scheduler;
+
+#[test]
+pub fn worker_spawning() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(5, Program::new().exec(20))
+            .fork(5, Program::new().fork(5, Program::new().exec(20)).exec(20))
+            .join(Pid::new(3))
+            .join(Pid::new(2)),
+    );
+    let output = format_logs_tree(&logs);
+
+    assert!(output.contains("\n1\t"));
+    assert!(output.contains("\n\u{251c}\u{2500}2\t"));
+    assert!(output.contains("\n\u{2514}\u{2500}3\t"));
+    assert!(output.contains("\n  \u{2514}\u{2500}4\t"));
+}
+
+#[test]
+pub fn orphans_are_roots() {
+    let logs = Simulator::run(
+        scheduler(),
+        &Program::new()
+            .fork(0, Program::new().fork(0, Program::new().exec(20)))
+            .join(Pid::new(2))
+            .exec(50),
+    );
+    let output = format_logs_tree(&logs);
+
+    // once pid 2 has been joined, pid 3 has no listed parent
+    assert!(output.contains("\n3\t"));
+}
@@ -0,0 +1,79 @@
+use processor::{Log, Program, Simulator};
+use scheduler::{weighted_cfs, Pid, ProcessState, SchedulingDecision};
+
+use std::num::NonZeroUsize;
+
+use super::cpu_time;
+
+fn run(program: &Program) -> Vec<Log> {
+    Simulator::run(weighted_cfs(NonZeroUsize::new(12).unwrap(), 1), program)
+}
+
+#[test]
+pub fn nice_levels_share() {
+    // nice 0 and nice -5 have the weights 1024 and 3121
+    let logs = run(&Program::new()
+        .fork(0, Program::new().exec(1000))
+        .fork(5, Program::new().exec(1000))
+        .sleep(400));
+
+    let (low, high) = (cpu_time(&logs, 2), cpu_time(&logs, 3));
+    let share = high as f64 / (low + high) as f64;
+    assert!((share - 3121.0 / 4145.0).abs() < 0.02, "{low} {high}");
+}
+
+#[test]
+pub fn equal_nice_levels() {
+    let logs = run(&Program::new()
+        .fork(-3, Program::new().exec(1000))
+        .fork(-3, Program::new().exec(1000))
+        .sleep(400));
+
+    assert!(cpu_time(&logs, 2).abs_diff(cpu_time(&logs, 3)) <= 12);
+}
+
+#[test]
+pub fn weighted_timeslice() {
+    let logs = run(&Program::new()
+        .fork(0, Program::new().exec(100))
+        .fork(5, Program::new().exec(100))
+        .sleep(200));
+
+    let timeslice = |pid: usize| {
+        logs.iter()
+            .find_map(|log| match log.decision {
+                SchedulingDecision::Run {
+                    pid: run,
+                    timeslice,
+                } if run == Pid::new(pid) => Some(timeslice.get()),
+                _ => None,
+            })
+            .unwrap()
+    };
+    // the timeslices are the weight shares of the cpu time, while pid 1 sleeps
+    assert_eq!(timeslice(2), 12 * 1024 / (1024 + 3121 + 1024));
+    assert_eq!(timeslice(3), 12 * 3121 / (1024 + 3121));
+}
+
+#[test]
+pub fn vruntime_reported() {
+    let logs = run(&Program::new().fork(10, Program::new().exec(10)).sleep(20));
+
+    let extra = logs
+        .iter()
+        .rev()
+        .filter_map(|log| log.processes.get(&Pid::new(2)))
+        .find(|process| process.state != ProcessState::Zombie)
+        .map(|process| process.extra.clone())
+        .unwrap();
+    assert!(extra.ends_with(" nice=-10 weight=9548"));
+    // each time unit advances the vruntime by 1024 * 1024 / 9548, instead of 1024
+    let vruntime = extra
+        .trim_start_matches("vruntime=")
+        .split(' ')
+        .next()
+        .unwrap()
+        .parse::<usize>()
+        .unwrap();
+    assert!(vruntime > 0 && vruntime <= 10 * 1024 * 1024 / 9548);
+}
@@ -0,0 +1,137 @@
+use std::fs;
+
+use processor::{Processor, Program};
+use scheduler::Pid;
+
+use super::{run, scheduler};
+
+/// Runs the workload `text` and checks its logs against the output of
+/// the test `name` from `folder`, which runs the same workload as a closure.
+fn same_as(folder: &str, name: &str, text: &str) {
+    let program = text.parse::<Program>().unwrap();
+    let logs = Processor::run(scheduler(), |process| program.run(process));
+
+    run(folder, name, &logs);
+}
+
+#[test]
+pub fn send_receive() {
+    same_as(
+        "wait_and_signal",
+        "send_receive",
+        "
+        fork 0 {
+            wait 1
+            exec 5
+        }
+        exec 5
+        signal 1
+        sleep 10
+        ",
+    );
+}
+
+#[test]
+pub fn senders() {
+    same_as(
+        "wait_and_signal",
+        "senders",
+        "
+        fork 0 { wait 1 signal 2 }
+        fork 0 { wait 2 signal 3 }
+        repeat 2 {
+            fork 0 { wait 3 }
+        }
+        exec 10
+        signal 1
+        sleep 10
+        ",
+    );
+}
+
+#[test]
+pub fn worker_spawning() {
+    same_as(
+        "workers",
+        "worker_spawning",
+        "
+        # the worker forks another worker when it is done
+        fork 5 {
+            exec 20
+            fork 5 {
+                exec 20
+            }
+        }
+        exec 50
+        ",
+    );
+}
+
+#[test]
+pub fn every_instruction() {
+    let text = "
+        exec
+        exec 2
+        fork -1 { exit }
+        fork 0 deadline 5 period 10 { exec 1 }
+        fork 0 burst 4 { exec 4 }
+        wait 1 signal 2 sleep 3
+        kill 2 join 3
+        lock 1 unlock 1
+        sem_wait 2 sem_post 2
+        repeat 2 { exec 1 }
+        exit 3
+    ";
+    let program = Program::new()
+        .exec(3)
+        .fork(-1, Program::new().exit(0))
+        .fork_deadline(0, 5, 10, Program::new().exec(1))
+        .fork_burst(0, 4, Program::new().exec(4))
+        .wait(1)
+        .signal(2)
+        .sleep(3)
+        .kill(Pid::new(2))
+        .join(Pid::new(3))
+        .lock(1)
+        .unlock(1)
+        .sem_wait(2)
+        .sem_post(2)
+        .repeat(2, Program::new().exec(1))
+        .exit(3);
+
+    assert_eq!(text.parse::<Program>(), Ok(program.clone()));
+    assert_eq!(program.to_string().parse::<Program>(), Ok(program));
+}
+
+#[test]
+pub fn errors() {
+    let error = |text: &str| text.parse::<Program>().unwrap_err().to_string();
+
+    assert_eq!(error("exec\nrun 2"), "line 2: unknown instruction `run`");
+    assert_eq!(
+        error("fork 0 {\n exec"),
+        "line 2: expected `}`, found the end of the text"
+    );
+    assert_eq!(
+        error("sleep a"),
+        "line 1: expected an amount of time, found `a`"
+    );
+    assert_eq!(
+        error("fork 200 { }"),
+        "line 1: expected a priority, found `200`"
+    );
+    assert_eq!(error("exec }"), "line 1: unexpected `}`");
+}
+
+#[test]
+pub fn files() {
+    for entry in fs::read_dir("workloads").unwrap() {
+        let path = entry.unwrap().path();
+        let text = fs::read_to_string(&path).unwrap();
+        assert!(
+            text.parse::<Program>().is_ok(),
+            "{} is not a valid workload",
+            path.display()
+        );
+    }
+}
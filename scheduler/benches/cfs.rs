@@ -0,0 +1,82 @@
+//! Measures the cost of a scheduling decision of the CFS scheduler as the number
+//! of ready processes grows.
+//!
+//! Run with `cargo bench -p scheduler --bench cfs`. Every time the number of
+//! processes doubles, the cost of a decision should grow by much less than
+//! a factor of two. Listing the processes, as the processor does after each
+//! decision, is measured separately: it returns all the processes, so its cost
+//! per listed process should stay about the same.
+
+use std::num::NonZeroUsize;
+use std::time::Instant;
+
+use scheduler::{cfs, Scheduler, SchedulingDecision, StopReason, Syscall};
+
+/// The number of scheduling decisions measured for each number of processes.
+const DECISIONS: usize = 20_000;
+
+/// Forks `processes` processes and returns the scheduler.
+fn setup(processes: usize) -> impl Scheduler {
+    let mut scheduler = cfs(NonZeroUsize::new(1 << 40).unwrap(), 1);
+    scheduler.stop(StopReason::syscall(Syscall::Fork(0)));
+    for _ in 1..processes {
+        match scheduler.next() {
+            SchedulingDecision::Run { timeslice, .. } => {
+                scheduler.stop(StopReason::Syscall {
+                    syscall: Syscall::Fork(0),
+                    remaining: timeslice.get() - 1,
+                });
+            }
+            decision => panic!("Unexpected decision {decision:?}"),
+        }
+    }
+    scheduler
+}
+
+/// The number of times the processes are listed for each number of processes.
+const LISTS: usize = 100;
+
+/// Returns the average time of a scheduling decision, in nanoseconds.
+fn measure(processes: usize) -> f64 {
+    let mut scheduler = setup(processes);
+    let start = Instant::now();
+    for _ in 0..DECISIONS {
+        scheduler.next();
+        scheduler.stop(StopReason::Expired);
+    }
+    start.elapsed().as_nanos() as f64 / DECISIONS as f64
+}
+
+/// Returns the average time of a scheduling decision followed by the listing
+/// of the processes, in nanoseconds per listed process.
+fn measure_list(processes: usize) -> f64 {
+    let mut scheduler = setup(processes);
+    let start = Instant::now();
+    for _ in 0..LISTS {
+        scheduler.next();
+        scheduler.list();
+        scheduler.stop(StopReason::Expired);
+    }
+    start.elapsed().as_nanos() as f64 / (LISTS * processes) as f64
+}
+
+/// Prints the cost returned by `measure` for each number of processes and
+/// its growth since the previous one.
+fn report(title: &str, measure: fn(usize) -> f64) {
+    println!("PROCESSES\t{title}\tGROWTH");
+    let mut previous: Option<f64> = None;
+    for processes in [1_000, 2_000, 4_000, 8_000, 16_000, 32_000] {
+        let cost = measure(processes);
+        match previous {
+            Some(previous) => println!("{processes}\t\t{cost:.1}\t\t{:.2}x", cost / previous),
+            None => println!("{processes}\t\t{cost:.1}\t\t-"),
+        }
+        previous = Some(cost);
+    }
+}
+
+fn main() {
+    report("NS/DECISION", measure);
+    println!();
+    report("NS/LISTED PROCESS", measure_list);
+}
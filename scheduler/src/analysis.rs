@@ -0,0 +1,211 @@
+//! Offline schedulability analysis for periodic task sets.
+//!
+//! The analysis is done before the simulation, for the fixed priorities
+//! used by the [`crate::rate_monotonic`] scheduler. The simulation can
+//! then confirm or disprove the [`Report`].
+
+use std::fmt::Display;
+
+/// A periodic task, as it is forked with [`crate::Syscall::ForkDeadline`].
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Task {
+    /// The worst case execution time of a job.
+    pub execution: usize,
+
+    /// The amount of time, from the release of a job, in which the job has to finish.
+    pub deadline: usize,
+
+    /// The amount of time between the releases of two jobs.
+    pub period: usize,
+}
+
+impl Task {
+    /// Creates a task whose deadline is equal to its period.
+    pub fn new(execution: usize, period: usize) -> Task {
+        Task::with_deadline(execution, period, period)
+    }
+
+    /// Creates a task with a deadline different from its period.
+    ///
+    /// The analysis assumes that the deadline is not longer than the period.
+    pub fn with_deadline(execution: usize, deadline: usize, period: usize) -> Task {
+        Task {
+            execution,
+            deadline,
+            period,
+        }
+    }
+
+    /// Returns the fraction of the processor's time that the task uses.
+    pub fn utilization(&self) -> f64 {
+        self.execution as f64 / self.period as f64
+    }
+}
+
+/// The result of the analysis of a task set.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// The analysed tasks, in the order they were given.
+    pub tasks: Vec<Task>,
+
+    /// The total utilization of the tasks.
+    pub utilization: f64,
+
+    /// The Liu & Layland utilization bound for the number of tasks.
+    pub bound: f64,
+
+    /// The worst case response time of each task, [`None`] if the
+    /// task can miss its deadline.
+    pub response_times: Vec<Option<usize>>,
+}
+
+impl Report {
+    /// Returns `true` if the utilization is within the Liu & Layland bound.
+    ///
+    /// This is a sufficient test, a task set above the bound can
+    /// still be schedulable.
+    pub fn passes_bound(&self) -> bool {
+        self.utilization <= self.bound
+    }
+
+    /// Returns `true` if the response time analysis has found that
+    /// all the tasks meet their deadlines.
+    pub fn is_schedulable(&self) -> bool {
+        self.response_times.iter().all(Option::is_some)
+    }
+
+    /// Checks the report against the deadline misses observed in a
+    /// simulation, given in the order of the tasks.
+    ///
+    /// The report is confirmed if the tasks found to be schedulable have not
+    /// missed any deadline and, for a task set that is not schedulable, if
+    /// at least one of the other tasks has missed a deadline.
+    pub fn is_confirmed_by(&self, misses: &[usize]) -> bool {
+        assert_eq!(
+            misses.len(),
+            self.tasks.len(),
+            "The misses have to be given for every task"
+        );
+        let mut misses = self.response_times.iter().zip(misses);
+        let deadlines_met = misses
+            .clone()
+            .all(|(response, misses)| response.is_none() || *misses == 0);
+        let deadlines_missed = self.is_schedulable()
+            || misses.any(|(response, misses)| response.is_none() && *misses > 0);
+        deadlines_met && deadlines_missed
+    }
+}
+
+impl Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Utilization {:.3}, bound {:.3}",
+            self.utilization, self.bound
+        )?;
+        writeln!(f, "TASK\tEXEC\tPERIOD\tDEADLINE\tRESPONSE")?;
+        for (index, (task, response)) in self.tasks.iter().zip(&self.response_times).enumerate() {
+            write!(
+                f,
+                "{}\t{}\t{}\t{}\t\t",
+                index, task.execution, task.period, task.deadline
+            )?;
+            match response {
+                Some(response) => writeln!(f, "{}", response)?,
+                None => writeln!(f, "-")?,
+            }
+        }
+        writeln!(
+            f,
+            "{}",
+            if self.is_schedulable() {
+                "Schedulable"
+            } else {
+                "Not schedulable"
+            }
+        )
+    }
+}
+
+/// Analyses a task set for the rate monotonic policy.
+///
+/// Tasks with shorter periods have higher priorities, tasks with the same period
+/// keep the order in which they are given. The report contains the result of the
+/// Liu & Layland utilization bound test and the exact response time analysis.
+///
+/// The response time analysis assumes that the deadline of every task is not
+/// longer than its period, so that a job finishes before the next one of the
+/// same task is released. For a longer deadline, the response time of the
+/// first job is not always the worst one.
+///
+/// ## Panics
+///
+/// Panics if the period of a task is 0.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::analysis::{rate_monotonic, Task};
+///
+/// let report = rate_monotonic(&[Task::new(1, 4), Task::new(2, 6), Task::new(3, 12)]);
+///
+/// // above the utilization bound, but all the deadlines are met
+/// assert!(!report.passes_bound());
+/// assert_eq!(report.response_times, [Some(1), Some(3), Some(10)]);
+/// assert!(report.is_schedulable());
+/// ```
+pub fn rate_monotonic(tasks: &[Task]) -> Report {
+    if tasks.iter().any(|task| task.period == 0) {
+        panic!("The period of a task cannot be 0");
+    }
+
+    let count = tasks.len() as f64;
+    let utilization = tasks.iter().map(Task::utilization).sum();
+    let bound = if tasks.is_empty() {
+        1.0
+    } else {
+        count * (2f64.powf(1.0 / count) - 1.0)
+    };
+
+    let mut priorities = (0..tasks.len()).collect::<Vec<usize>>();
+    priorities.sort_by_key(|index| tasks[*index].period);
+
+    let mut response_times = vec![None; tasks.len()];
+    for (priority, index) in priorities.iter().enumerate() {
+        let higher = priorities[..priority]
+            .iter()
+            .map(|index| &tasks[*index])
+            .collect::<Vec<&Task>>();
+        response_times[*index] = response_time(&tasks[*index], &higher);
+    }
+
+    Report {
+        tasks: tasks.to_vec(),
+        utilization,
+        bound,
+        response_times,
+    }
+}
+
+/// Returns the worst case response time of `task` when it is preempted by the
+/// `higher` priority tasks, or [`None`] if it is longer than the task's deadline.
+///
+/// Only the first job of the task is analysed, which is the worst one when the
+/// deadline is not longer than the period. The periods cannot be 0.
+fn response_time(task: &Task, higher: &[&Task]) -> Option<usize> {
+    let mut response = task.execution + higher.iter().map(|task| task.execution).sum::<usize>();
+    loop {
+        if response > task.deadline {
+            return None;
+        }
+        let next = task.execution
+            + higher
+                .iter()
+                .map(|higher| response.div_ceil(higher.period) * higher.execution)
+                .sum::<usize>();
+        if next == response {
+            return Some(response);
+        }
+        response = next;
+    }
+}
@@ -0,0 +1,406 @@
+//! A scheduler wrapper that checks the contract of the [`Scheduler`] trait.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Display;
+
+use crate::{
+    Blocker, Pid, Process, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall,
+    SyscallResult,
+};
+
+/// A broken rule of the contract of the [`Scheduler`] trait.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Violation {
+    /// The function of the scheduler that has broken the rule,
+    /// `next`, `stop` or `list`.
+    pub function: &'static str,
+
+    /// The number of decisions that the scheduler has returned,
+    /// including the one that has broken the rule.
+    pub decisions: usize,
+
+    /// The last decision returned by the scheduler, [`None`] before the first one.
+    pub decision: Option<SchedulingDecision>,
+
+    /// The processes listed by the scheduler when the rule was broken.
+    pub processes: Vec<(Pid, ProcessState)>,
+
+    /// The description of the broken rule.
+    pub message: String,
+}
+
+impl Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "`{}` broke the scheduler contract", self.function)?;
+        if let Some(decision) = self.decision {
+            write!(f, " at decision {} ({})", self.decisions, decision)?;
+        }
+        writeln!(f, ": {}", self.message)?;
+        let processes = self
+            .processes
+            .iter()
+            .map(|(pid, state)| format!("{} {}", pid, state))
+            .collect::<Vec<String>>();
+        write!(f, "Processes: {}", processes.join(", "))
+    }
+}
+
+/// What the checker remembers about a listed process.
+struct Listed {
+    pid: Pid,
+    state: ProcessState,
+    timings: (usize, usize, usize),
+}
+
+/// Returns what the checker remembers about the listed processes.
+fn snapshot(processes: &[&dyn Process]) -> Vec<Listed> {
+    processes
+        .iter()
+        .map(|process| Listed {
+            pid: process.pid(),
+            state: process.state(),
+            timings: process.timings(),
+        })
+        .collect()
+}
+
+/// Returns the state of the process `pid`, [`None`] if it is not listed.
+fn state(processes: &[Listed], pid: Pid) -> Option<ProcessState> {
+    processes
+        .iter()
+        .find(|process| process.pid == pid)
+        .map(|process| process.state)
+}
+
+/// The state of the checks, kept apart from the scheduler so that both
+/// can be borrowed at the same time.
+#[derive(Default)]
+struct Checker {
+    /// `true` if violations are recorded instead of panicking.
+    record: bool,
+
+    /// The recorded violations.
+    violations: Vec<Violation>,
+
+    /// The number of decisions returned by the scheduler.
+    decisions: usize,
+
+    /// The last decision returned by the scheduler.
+    decision: Option<SchedulingDecision>,
+
+    /// The PIDs returned by the forks.
+    forked: HashSet<Pid>,
+
+    /// The last timings listed for each process.
+    timings: HashMap<Pid, (usize, usize, usize)>,
+
+    /// The time of the processor, counted from the decisions and the stops.
+    time: usize,
+
+    /// The process that runs and the timeslice it has received.
+    running: Option<(Pid, usize)>,
+
+    /// The time at which each sleeping process wakes up.
+    wakeups: HashMap<Pid, usize>,
+}
+
+impl Checker {
+    /// Records or panics with a violation.
+    fn report(&mut self, function: &'static str, processes: &[Listed], message: String) {
+        let violation = Violation {
+            function,
+            decisions: self.decisions,
+            decision: self.decision,
+            processes: processes
+                .iter()
+                .map(|process| (process.pid, process.state))
+                .collect(),
+            message,
+        };
+        if self.record {
+            self.violations.push(violation);
+        } else {
+            panic!("{}", violation);
+        }
+    }
+
+    /// Checks that the PIDs are unique and that the timings do not decrease.
+    fn list(&mut self, function: &'static str, processes: &[Listed]) {
+        let mut pids = HashSet::new();
+        for process in processes.iter() {
+            if !pids.insert(process.pid) {
+                self.report(
+                    function,
+                    processes,
+                    format!("PID {} is listed more than once", process.pid),
+                );
+            }
+            let (total, syscall, execution) = process.timings;
+            if let Some(previous) = self.timings.insert(process.pid, process.timings) {
+                if total < previous.0 || syscall < previous.1 || execution < previous.2 {
+                    self.report(
+                        function,
+                        processes,
+                        format!(
+                            "the timings of PID {} went back from {:?} to {:?}",
+                            process.pid, previous, process.timings
+                        ),
+                    );
+                }
+            }
+        }
+    }
+
+    /// Checks that the PIDs returned by forks start at 1 and are unique, and
+    /// advances the time by the time the stopped process has run.
+    fn stop(&mut self, reason: StopReason, result: SyscallResult, processes: &[Listed]) {
+        if let Some((pid, timeslice)) = self.running.take() {
+            match reason {
+                StopReason::Expired => self.time += timeslice,
+                StopReason::Syscall { syscall, remaining } => {
+                    self.time += timeslice.saturating_sub(remaining);
+                    if let Syscall::Sleep(time) = syscall {
+                        self.wakeups.insert(pid, self.time + time);
+                    }
+                }
+            }
+        }
+        let fork = matches!(
+            reason,
+            StopReason::Syscall {
+                syscall: Syscall::Fork(_)
+                    | Syscall::ForkDeadline { .. }
+                    | Syscall::ForkBurst { .. },
+                ..
+            }
+        );
+        if let (true, SyscallResult::Pid(pid)) = (fork, result) {
+            if self.forked.is_empty() && pid != 1 {
+                self.report(
+                    "stop",
+                    processes,
+                    format!("the first process has PID {} instead of 1", pid),
+                );
+            }
+            if !self.forked.insert(pid) {
+                self.report(
+                    "stop",
+                    processes,
+                    format!("fork returned PID {}, which is already used", pid),
+                );
+            }
+        }
+    }
+
+    /// Checks a decision against the processes listed before and after it.
+    fn next(&mut self, decision: SchedulingDecision, before: &[Listed], after: &[Listed]) {
+        self.decisions += 1;
+        self.decision = Some(decision);
+        let ready = after
+            .iter()
+            .find(|process| process.state == ProcessState::Ready)
+            .map(|process| process.pid);
+
+        match decision {
+            SchedulingDecision::Run { pid, timeslice } => {
+                self.running = Some((pid, timeslice.get()));
+                // a sleeping process wakes up when the scheduler is asked for the next decision
+                let wakeup = self.wakeups.get(&pid).copied();
+                match state(before, pid) {
+                    Some(ProcessState::Ready | ProcessState::Running) => {}
+                    Some(ProcessState::Waiting { event: None })
+                        if wakeup.is_some_and(|wakeup| wakeup <= self.time) => {}
+                    Some(ProcessState::Waiting { event: None }) => {
+                        let until = wakeup.map_or(String::from("an unknown time"), |wakeup| {
+                            format!("time {}", wakeup)
+                        });
+                        self.report(
+                            "next",
+                            after,
+                            format!(
+                                "PID {} cannot run at time {}, it sleeps until {}",
+                                pid, self.time, until
+                            ),
+                        )
+                    }
+                    Some(state) => self.report(
+                        "next",
+                        after,
+                        format!("PID {} cannot run, it is {}", pid, state),
+                    ),
+                    None => self.report("next", after, format!("PID {} is not listed", pid)),
+                }
+                let running = after
+                    .iter()
+                    .filter(|process| process.state == ProcessState::Running)
+                    .map(|process| process.pid)
+                    .collect::<Vec<Pid>>();
+                if running != [pid] {
+                    self.report(
+                        "next",
+                        after,
+                        format!(
+                            "PID {} has to be the only running process, the running processes are {:?}",
+                            pid, running
+                        ),
+                    );
+                }
+            }
+            SchedulingDecision::Sleep(time) => {
+                self.time += time.get();
+                if let Some(ready) = ready {
+                    self.report(
+                        "next",
+                        after,
+                        format!("the processor sleeps while PID {} is ready", ready),
+                    );
+                }
+            }
+            SchedulingDecision::Deadlock => {
+                let sleeper = after
+                    .iter()
+                    .find(|process| process.state == ProcessState::Waiting { event: None })
+                    .map(|process| process.pid);
+                if let Some(pid) = ready.or(sleeper) {
+                    self.report(
+                        "next",
+                        after,
+                        format!("deadlock while PID {} can still run", pid),
+                    );
+                }
+            }
+            SchedulingDecision::Panic | SchedulingDecision::Done => {}
+        }
+
+        // before the first fork there is no process 1
+        if self.forked.is_empty() {
+            return;
+        }
+        let alive = |process: &&Listed| process.state != ProcessState::Zombie;
+        let first = after.iter().filter(alive).any(|process| process.pid == 1);
+        let others = after.iter().filter(alive).any(|process| process.pid != 1);
+        match decision {
+            SchedulingDecision::Panic if first => self.report(
+                "next",
+                after,
+                String::from("panic while process 1 is still alive"),
+            ),
+            SchedulingDecision::Panic => {}
+            decision if !first && others => self.report(
+                "next",
+                after,
+                format!("process 1 has stopped, but the decision is {}", decision),
+            ),
+            _ => {}
+        }
+    }
+}
+
+/// A scheduler that checks that the scheduler it wraps follows the contract
+/// of the [`Scheduler`] trait, to find the bugs of new schedulers before
+/// their output is compared with the expected one.
+///
+/// The checks are:
+/// * the PIDs returned by forks start at 1 and are unique, and no PID is
+///   listed twice;
+/// * the timings of a process never decrease;
+/// * a [`SchedulingDecision::Run`] names a process that is ready, running or
+///   has slept for the whole time it asked for, as sleeping processes wake up
+///   when [`Scheduler::next`] is called, and after it that process is the only
+///   running process;
+/// * a [`SchedulingDecision::Sleep`] is returned only when no process is ready;
+/// * a [`SchedulingDecision::Deadlock`] is returned only when no process is
+///   ready or sleeping;
+/// * a [`SchedulingDecision::Panic`] is returned exactly when process 1 has
+///   stopped while other processes are still alive.
+///
+/// The checks assume a single processor, with at most one running process.
+///
+/// By default, a violation panics with its description, the decision at which
+/// it was found and the listed processes. Use [`Checked::recording`] to collect
+/// the violations instead.
+///
+/// ## Example
+///
+/// ```rust
+/// use scheduler::{round_robin, Checked, Scheduler, StopReason, Syscall};
+/// use std::num::NonZeroUsize;
+///
+/// let mut scheduler = Checked::recording(round_robin(NonZeroUsize::new(3).unwrap(), 1));
+/// scheduler.stop(StopReason::syscall(Syscall::Fork(0)));
+/// scheduler.next();
+///
+/// assert!(scheduler.violations().is_empty());
+/// ```
+pub struct Checked<S: Scheduler> {
+    scheduler: S,
+    checker: Checker,
+}
+
+impl<S: Scheduler> Checked<S> {
+    /// Wraps `scheduler`, panicking at the first violation.
+    pub fn new(scheduler: S) -> Checked<S> {
+        Checked {
+            scheduler,
+            checker: Checker::default(),
+        }
+    }
+
+    /// Wraps `scheduler`, recording the violations.
+    pub fn recording(scheduler: S) -> Checked<S> {
+        Checked {
+            scheduler,
+            checker: Checker {
+                record: true,
+                ..Checker::default()
+            },
+        }
+    }
+
+    /// Returns the violations recorded so far.
+    pub fn violations(&self) -> &[Violation] {
+        &self.checker.violations
+    }
+
+    /// Returns the wrapped scheduler.
+    pub fn into_inner(self) -> S {
+        self.scheduler
+    }
+}
+
+impl<S: Scheduler> Scheduler for Checked<S> {
+    fn next(&mut self) -> SchedulingDecision {
+        let before = snapshot(&self.scheduler.list());
+        let decision = self.scheduler.next();
+        let after = snapshot(&self.scheduler.list());
+        self.checker.next(decision, &before, &after);
+        self.checker.list("next", &after);
+        decision
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        let result = self.scheduler.stop(reason);
+        let after = snapshot(&self.scheduler.list());
+        self.checker.stop(reason, result, &after);
+        self.checker.list("stop", &after);
+        result
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        let processes = self.scheduler.list();
+        self.checker.list("list", &snapshot(&processes));
+        processes
+    }
+
+    fn events(&mut self) -> Vec<String> {
+        self.scheduler.events()
+    }
+
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        self.scheduler.completions()
+    }
+
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        self.scheduler.blockers()
+    }
+}
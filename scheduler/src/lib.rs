@@ -8,15 +8,30 @@ use std::num::NonZeroUsize;
 
 mod scheduler;
 
+pub mod analysis;
+
+mod checked;
+pub use checked::{Checked, Violation};
+
 use schedulers::Cfs;
+use schedulers::EDF;
+use schedulers::LotteryScheduler;
+use schedulers::MlfqScheduler;
+use schedulers::RealTimeScheduler;
+use schedulers::RATE_MONOTONIC;
 use schedulers::RoundRobinPrioritiesScheduler;
 use schedulers::RoundRobinScheduler;
+use schedulers::SjfScheduler;
+use schedulers::SmpRoundRobinScheduler;
+use schedulers::StrideScheduler;
 
 pub use crate::scheduler::{
-    Pid, Process, ProcessState, Scheduler, SchedulingDecision, StopReason, Syscall, SyscallResult,
+    Blocker, Pid, Process, ProcessState, Scheduler, SchedulingDecision, SmpScheduler, StopReason,
+    SyncObject, Syscall, SyscallResult,
 };
 
 mod schedulers;
+pub use schedulers::MlfqLevel;
 
 // TODO import your scheduler here
 // This example imports the Empty scheduler
@@ -25,11 +40,11 @@ mod schedulers;
 ///
 /// * `timeslice` - the time quanta that a process can run before it is preempted
 /// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
-///                                 has to decode whether to schedule it again for the
-///                                 remaining time of its quanta, or to schedule a new
-///                                 process. The scheduler will schedule the process
-///                                 again of the remaining quanta is greater or equal to
-///                                 the `minimum_remaining_timeslice` value.
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value.
 #[allow(unused_variables)]
 pub fn round_robin(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
     RoundRobinScheduler::new(timeslice, minimum_remaining_timeslice)
@@ -38,11 +53,11 @@ pub fn round_robin(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize)
 /// Returns a structure that implements the `Scheduler` trait with a priority queue scheduler policy
 /// * `timeslice` - the time quanta that a process can run before it is preempted
 /// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
-///                                 has to decode whether to schedule it again for the
-///                                 remaining time of its quanta, or to schedule a new
-///                                 process. The scheduler will schedule the process
-///                                 again of the remaining quanta is greater or equal to
-///                                 the `minimum_remaining_timeslice` value.
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value.
 #[allow(unused_variables)]
 pub fn priority_queue(
     timeslice: NonZeroUsize,
@@ -51,16 +66,196 @@ pub fn priority_queue(
     RoundRobinPrioritiesScheduler::new(timeslice, minimum_remaining_timeslice)
 }
 
+/// Returns a structure that implements the `Scheduler` trait with a priority queue scheduler policy
+/// that uses the priority inheritance protocol
+///
+/// A process that holds a lock inherits the highest priority of the processes blocked on the lock,
+/// its own priority being shown in its [`Process::extra`] details. Priority inversions are reported
+/// as scheduler events by both priority queue schedulers.
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value.
+pub fn priority_queue_inheritance(
+    timeslice: NonZeroUsize,
+    minimum_remaining_timeslice: usize,
+) -> impl Scheduler {
+    RoundRobinPrioritiesScheduler::with_priority_inheritance(timeslice, minimum_remaining_timeslice)
+}
+
 /// Returns a structure that implements the `Scheduler` trait with a simplified [cfs](https://opensource.com/article/19/2/fair-scheduling-linux) scheduler policy
 /// * `cpu_time` - the total time units that the cpu has for an iteration, this is used to compute
-///                    the `timeslice` of each process.
+///   the `timeslice` of each process.
 /// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
-///                                 has to decode whether to schedule it again for the
-///                                 remaining time of its quanta, or to schedule a new
-///                                 process. The scheduler will schedule the process
-///                                 again of the remaining quanta is greater or equal to
-///                                 the `minimum_remaining_timeslice` value.
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value.
 #[allow(unused_variables)]
 pub fn cfs(cpu_time: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
     Cfs::new(cpu_time, minimum_remaining_timeslice)
 }
+
+/// Returns a structure that implements the `Scheduler` trait with a [cfs](https://opensource.com/article/19/2/fair-scheduling-linux)
+/// scheduler policy that weights the processes by their nice levels
+///
+/// The nice level of a process is the opposite of its priority, clamped between `-20` and `19`,
+/// and is mapped to a weight by the `sched_prio_to_weight` table of the Linux kernel. The
+/// vruntime of a process advances in inverse proportion to its weight and is kept in 1/1024
+/// time units. The nice level and the weight are shown in the [`Process::extra`] details.
+/// * `cpu_time` - the total time units that the cpu has for an iteration, each process
+///   getting a `timeslice` proportional to its share of the total weight.
+/// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value. It is also
+///   the minimum `timeslice` that a process gets.
+pub fn weighted_cfs(cpu_time: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
+    Cfs::weighted(cpu_time, minimum_remaining_timeslice)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with an earliest deadline first scheduler policy
+///
+/// Real-time processes are created with the [`Syscall::ForkDeadline`] system call. The number of
+/// deadlines that a process has missed is shown in its [`Process::extra`] details.
+/// * `timeslice` - the maximum time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value and no other
+///   process has a nearer deadline.
+pub fn edf(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
+    RealTimeScheduler::new(timeslice, minimum_remaining_timeslice, EDF)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with a rate monotonic scheduler policy
+///
+/// Real-time processes are created with the [`Syscall::ForkDeadline`] system call, processes with
+/// shorter periods having higher priorities. The task set can be checked before the simulation
+/// with [`analysis::rate_monotonic`].
+/// * `timeslice` - the maximum time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value and no other
+///   process has a shorter period.
+pub fn rate_monotonic(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
+    RealTimeScheduler::new(timeslice, minimum_remaining_timeslice, RATE_MONOTONIC)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with a multi-level feedback queue scheduler policy
+///
+/// New processes start on level `0`, the level with the highest priority. Priority boosts
+/// are reported as scheduler events.
+/// * `levels` - the quantum and the allotment of each level, the allotment being the total
+///   time a process can use at the level before it is moved to the level below.
+/// * `boost_period` - the amount of time after which all the processes are moved back to
+///   level `0`, [`None`] to never boost processes.
+/// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value and no other
+///   process is ready on a higher level.
+///
+/// ## Panics
+///
+/// Panics if `levels` is empty.
+pub fn mlfq(
+    levels: &[MlfqLevel],
+    boost_period: Option<NonZeroUsize>,
+    minimum_remaining_timeslice: usize,
+) -> impl Scheduler {
+    MlfqScheduler::new(levels, boost_period, minimum_remaining_timeslice)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with a lottery scheduler policy
+///
+/// A process with priority `p` holds `p + 1` tickets, the number of tickets being shown
+/// in its [`Process::extra`] details. Negative priorities are clamped, so a process with
+/// a negative priority holds a single ticket, like a process with priority `0`.
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value.
+/// * `seed` - the seed of the random number generator that draws the tickets, the
+///   same seed giving the same schedule.
+pub fn lottery(
+    timeslice: NonZeroUsize,
+    minimum_remaining_timeslice: usize,
+    seed: u64,
+) -> impl Scheduler {
+    LotteryScheduler::new(timeslice, minimum_remaining_timeslice, seed)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with a stride scheduler policy
+///
+/// A process with priority `p` holds `p + 1` tickets, the tickets and the pass being shown
+/// in its [`Process::extra`] details. Negative priorities are clamped, so a process with
+/// a negative priority holds a single ticket, like a process with priority `0`.
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value.
+pub fn stride(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> impl Scheduler {
+    StrideScheduler::new(timeslice, minimum_remaining_timeslice)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with a non-preemptive shortest job first scheduler policy
+///
+/// The length of the CPU bursts of a process is given by the hint of a [`Syscall::ForkBurst`]
+/// or predicted as the exponential average of its previous bursts. The hint, or the prediction
+/// and `alpha`, are shown in the [`Process::extra`] details.
+/// * `timeslice` - the amount of time a process runs before it is scheduled again, a process
+///   is not preempted until it sleeps, waits for an event or exits.
+/// * `alpha` - the weight of the last burst in the exponential average, between `0` and `1`.
+/// * `initial_burst` - the prediction of the first burst of the processes forked without a hint.
+pub fn sjf(timeslice: NonZeroUsize, alpha: f64, initial_burst: usize) -> impl Scheduler {
+    SjfScheduler::new(timeslice, alpha, initial_burst)
+}
+
+/// Returns a structure that implements the `Scheduler` trait with a shortest remaining time first scheduler policy
+///
+/// The preemptive version of [`sjf`], the running process being preempted when another
+/// process becomes ready with an expected burst shorter than the time left from its own.
+/// * `timeslice` - the maximum amount of time a process runs before the scheduler checks
+///   whether it should be preempted.
+/// * `alpha` - the weight of the last burst in the exponential average, between `0` and `1`.
+/// * `initial_burst` - the prediction of the first burst of the processes forked without a hint.
+pub fn srtf(timeslice: NonZeroUsize, alpha: f64, initial_burst: usize) -> impl Scheduler {
+    SjfScheduler::preemptive(timeslice, alpha, initial_burst)
+}
+
+/// Returns a structure that implements the `SmpScheduler` trait with a round robin scheduler policy
+/// that uses a single ready queue for all the CPUs
+///
+/// * `timeslice` - the time quanta that a process can run before it is preempted
+/// * `minimum_remaining_timeslice` - when a process makes a system call, the scheduler
+///   has to decode whether to schedule it again for the
+///   remaining time of its quanta, or to schedule a new
+///   process. The scheduler will schedule the process
+///   again of the remaining quanta is greater or equal to
+///   the `minimum_remaining_timeslice` value.
+pub fn smp_round_robin(
+    timeslice: NonZeroUsize,
+    minimum_remaining_timeslice: usize,
+) -> impl SmpScheduler {
+    SmpRoundRobinScheduler::new(timeslice, minimum_remaining_timeslice)
+}
@@ -94,6 +94,35 @@ pub enum Syscall {
         i8,
     ),
 
+    /// Create a new real-time process and return its PID.
+    ///
+    /// The process runs as a sequence of jobs, each job ending when the process
+    /// sleeps, waits for an event or exits. Schedulers that do not use deadlines
+    /// handle this system call as a [`Syscall::Fork`].
+    ForkDeadline {
+        /// The process's priority.
+        priority: i8,
+
+        /// The amount of time, from the release of a job, in which the job has to finish.
+        deadline: usize,
+
+        /// The amount of time between the releases of two jobs. If the period is `0`,
+        /// a new job is released every time the process becomes ready.
+        period: usize,
+    },
+
+    /// Create a new process with a hint of the length of its CPU bursts and return its PID.
+    ///
+    /// A CPU burst is the time a process executes before it sleeps or waits for an event.
+    /// Schedulers that do not predict bursts handle this system call as a [`Syscall::Fork`].
+    ForkBurst {
+        /// The process's priority.
+        priority: i8,
+
+        /// The expected length of each CPU burst of the process.
+        burst: usize,
+    },
+
     /// Ask the scheduler to suspend for an amount of time
     Sleep(
         /// The amount of time that the process should sleep. The process
@@ -119,9 +148,79 @@ pub enum Syscall {
 
     /// Ask the scheduler to finish the process.
     ///
-    /// The process will never be scheduled again and will be deleted
-    /// from the list of processes the the scheduler keeps track of.
-    Exit,
+    /// The process will never be scheduled again. It is kept in the list of
+    /// processes the the scheduler keeps track of in the [`ProcessState::Zombie`]
+    /// state, until its parent joins it with a [`Syscall::Join`] system call.
+    /// Processes whose parents have exited are deleted right away.
+    Exit(
+        /// The exit status of the process.
+        i32,
+    ),
+
+    /// Ask the scheduler to finish another process.
+    ///
+    /// The target is deleted from the list of processes as if it had sent a
+    /// [`Syscall::Exit`] system call, whether it is ready, waiting for an event
+    /// or sleeping. A process that kills itself exits. A process running on
+    /// another CPU is finished when it stops running.
+    ///
+    /// A killed process finishes with the exit status `-9`, like the processes
+    /// killed by `SIGKILL`.
+    Kill(
+        /// The PID of the process to finish.
+        Pid,
+    ),
+
+    /// Wait for a child process to exit and return its exit status.
+    ///
+    /// The process is placed in the [`ProcessState::Blocked`] state until the
+    /// child exits. If the child has already exited, its exit status is
+    /// returned immediately. Only the process that has forked the child
+    /// can join it, and only once.
+    Join(
+        /// The PID of the child process.
+        Pid,
+    ),
+
+    /// Acquire a lock.
+    ///
+    /// If the lock is held by another process, the process is placed in the
+    /// [`ProcessState::Blocked`] state until the lock is handed to it by a
+    /// [`Syscall::Unlock`] system call. Locks are not recursive, a process that
+    /// acquires a lock that it already holds blocks forever. A lock held by a
+    /// process that exits is never released.
+    Lock(
+        /// The lock number.
+        usize,
+    ),
+
+    /// Release a lock.
+    ///
+    /// The lock is handed to the process that has been blocked on it for
+    /// the longest time, if any.
+    Unlock(
+        /// The lock number.
+        usize,
+    ),
+
+    /// Decrement a semaphore.
+    ///
+    /// If the value of the semaphore is `0`, the process is placed in the
+    /// [`ProcessState::Blocked`] state until another process posts the semaphore.
+    /// Semaphores have the value `0` the first time they are used.
+    SemWait(
+        /// The semaphore number.
+        usize,
+    ),
+
+    /// Increment a semaphore.
+    ///
+    /// If processes are blocked on the semaphore, the process that has been
+    /// blocked for the longest time is woken up instead.
+    SemPost(
+        /// The semaphore number.
+        usize,
+    ),
 }
 
 /*
@@ -145,6 +244,22 @@ pub enum SyscallResult {
 
     /// The system call was issues while no process was scheduled.
     NoRunningProcess,
+
+    /// The system call was issued for a PID that does not belong to any process.
+    NoSuchProcess,
+
+    /// Returned after a [`Syscall::Join`] system call.
+    ExitStatus(
+        /// The exit status of the child process.
+        i32,
+    ),
+
+    /// Returned after a [`Syscall::Unlock`] system call for a lock that the process does not hold.
+    NotOwner,
+
+    /// The system call has blocked the process, its result is returned
+    /// by [`Scheduler::completions`] when the process is woken up.
+    Pending,
 }
 
 /// The reason that a process has stopped and the OS
@@ -208,6 +323,119 @@ pub trait Scheduler: Send {
 
     /// Returns the list of processes.
     fn list(&mut self) -> Vec<&dyn Process>;
+
+    /// Returns the events that took place in the scheduler since the
+    /// last call, like the priority boosts of a multi-level feedback queue.
+    ///
+    /// Schedulers that do not report any events can use the default
+    /// implementation.
+    fn events(&mut self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Returns the results of the system calls that have completed since the
+    /// last call, for the processes that they have blocked, like the exit status
+    /// returned to a process that has joined a child.
+    ///
+    /// Each result is given with the PID of the process and the system call.
+    /// Schedulers that return [`SyscallResult::Pending`] for a system call have
+    /// to return its result here.
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        Vec::new()
+    }
+
+    /// Returns what each process that waits, without sleeping, is blocked on.
+    ///
+    /// This is used to explain a [`SchedulingDecision::Deadlock`]. Schedulers
+    /// that do not describe their waiting processes can use the default
+    /// implementation.
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        Vec::new()
+    }
+}
+
+/// A boxed scheduler is a scheduler, which allows schedulers of different
+/// types to be used in the same place, like `Box<dyn Scheduler>`.
+impl<S: Scheduler + ?Sized> Scheduler for Box<S> {
+    fn next(&mut self) -> SchedulingDecision {
+        (**self).next()
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        (**self).stop(reason)
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        (**self).list()
+    }
+
+    fn events(&mut self) -> Vec<String> {
+        (**self).events()
+    }
+
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        (**self).completions()
+    }
+
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        (**self).blockers()
+    }
+}
+
+/// What a waiting process is blocked on.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Blocker {
+    /// The process waits for an event that any other process can signal.
+    Event(usize),
+
+    /// The process waits for a child process to exit.
+    Child(Pid),
+
+    /// The process waits to acquire a lock.
+    Lock {
+        /// The lock number.
+        lock: usize,
+
+        /// The process that holds the lock.
+        owner: Pid,
+    },
+
+    /// The process waits for any other process to post a semaphore.
+    Semaphore(usize),
+}
+
+impl Display for Blocker {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Blocker::Event(event) => write!(f, "event {}", event),
+            Blocker::Child(pid) => write!(f, "child {} to exit", pid),
+            Blocker::Lock { lock, owner } => write!(f, "lock {} held by {}", lock, owner),
+            Blocker::Semaphore(semaphore) => write!(f, "semaphore {}", semaphore),
+        }
+    }
+}
+
+/// The trait that a scheduler has to implement to schedule processes
+/// on several CPUs at the same time.
+///
+/// CPUs are numbered starting from 0 and share the same time. The processor
+/// calls the scheduler in the order of the events:
+/// * a CPU that receives a [`SchedulingDecision::Run`] decision runs the
+///   process until it is preempted or until it sends a system call;
+/// * a CPU that receives a [`SchedulingDecision::Sleep`] decision is asked
+///   for a new decision exactly after the amount of time it has slept;
+/// * events that take place at the same time are reported in the order of
+///   the CPUs, all the stops being reported before asking for new decisions.
+///
+/// [`Scheduler::next`] and [`Scheduler::stop`] are used when the scheduler
+/// runs on a single CPU.
+pub trait SmpScheduler: Scheduler {
+    /// Returns the action that the CPU `cpu` has to perform next.
+    fn next_cpu(&mut self, cpu: usize) -> SchedulingDecision;
+
+    /// The scheduler is informed about the stopping of the process
+    /// running on the CPU `cpu` and the reason.
+    fn stop_cpu(&mut self, cpu: usize, reason: StopReason) -> SyscallResult;
 }
 
 /// The state of a process.
@@ -226,6 +454,38 @@ pub enum ProcessState {
         /// If the event is [`None`], the process is sleeping.
         event: Option<usize>,
     },
+
+    /// The process is blocked on a synchronisation object.
+    Blocked {
+        /// The object that the process is blocked on.
+        object: SyncObject,
+    },
+
+    /// The process has exited and its parent has not joined it yet.
+    Zombie,
+}
+
+/// A kernel synchronisation object that processes can block on.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum SyncObject {
+    /// The lock acquired by the [`Syscall::Lock`] system call.
+    Lock(usize),
+
+    /// The semaphore decremented by the [`Syscall::SemWait`] system call.
+    Semaphore(usize),
+
+    /// The child process joined by the [`Syscall::Join`] system call.
+    Child(Pid),
+}
+
+impl Display for SyncObject {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SyncObject::Lock(id) => write!(f, "LOCK {}", id),
+            SyncObject::Semaphore(id) => write!(f, "SEM {}", id),
+            SyncObject::Child(pid) => write!(f, "JOIN {}", pid),
+        }
+    }
 }
 
 impl Display for ProcessState {
@@ -240,6 +500,8 @@ impl Display for ProcessState {
                     write!(f, "SLEEP")
                 }
             }
+            ProcessState::Blocked { object } => write!(f, "{}", object),
+            ProcessState::Zombie => write!(f, "ZOMBIE"),
         }
     }
 }
@@ -263,4 +525,10 @@ pub trait Process: Send {
 
     /// Returns details information
     fn extra(&self) -> String;
+
+    /// Returns the exit status of the process, [`None`] if the process has not exited.
+    fn exit_status(&self) -> Option<i32>;
+
+    /// Returns the PID of the process that has forked the process, [`None`] for the first process.
+    fn ppid(&self) -> Option<Pid>;
 }
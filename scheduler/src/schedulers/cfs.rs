@@ -1,6 +1,24 @@
-use std::{num::NonZeroUsize, process::exit};
-use crate::{Scheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
+use std::{collections::{BTreeMap, HashMap}, mem::take, num::NonZeroUsize, process::exit};
+use crate::{Blocker, Scheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
 use super::pcb::{Pcb, WakeupCondition};
+use super::exited::ExitedProcesses;
+use super::sync::{Outcome, Shared, SharedSyscalls, SyncObjects};
+
+/// The weight of a process with the nice level `0`.
+const NICE_0_WEIGHT: usize = 1024;
+
+/// The weights of the nice levels from `-20` to `19`, as the `sched_prio_to_weight`
+/// table of the Linux kernel. Each nice level is worth about 10% of processor time.
+const SCHED_PRIO_TO_WEIGHT: [usize; 40] = [
+    88761, 71755, 56483, 46273, 36291,
+    29154, 23254, 18705, 14949, 11916,
+    9548, 7620, 6100, 4904, 3906,
+    3121, 2501, 1991, 1586, 1277,
+    1024, 820, 655, 526, 423,
+    335, 272, 215, 172, 137,
+    110, 87, 70, 56, 45,
+    36, 29, 23, 18, 15
+];
 
 /// Data structure that implements a round robin scheduler.
 pub struct Cfs {
@@ -10,8 +28,14 @@ pub struct Cfs {
     stopped_process: Option<Pcb>,
     /// The remaining execution time for the scheduled process.
     remaining_time: usize,
-    /// The list of all processes ready to be scheduled.
-    ready_processes: Vec<Pcb>,
+    /// The processes ready to be scheduled, ordered by their vruntime and their pid.
+    timeline: BTreeMap<(usize, Pid), Pcb>,
+    /// The time at which each process in the timeline became ready. The total time of a
+    /// ready process is incremented only when it leaves the timeline, or when the processes
+    /// are listed.
+    ready_times: HashMap<Pid, usize>,
+    /// The sum of the weights of the processes in the timeline.
+    timeline_weight: usize,
     /// The list of all processes waiting for an event or sleeping.
     waiting_processes: Vec<Pcb>,
     /// The amount of time a ready process gets on the processor.
@@ -21,9 +45,21 @@ pub struct Cfs {
     minimum_remaining_timeslice: usize,
     /// The highest pid given to a process.
     highest_pid: usize,
+    /// The processes that have exited and have not been joined by their parents yet.
+    exited_processes: ExitedProcesses,
+    /// The locks and the semaphores that the processes use to synchronise.
+    sync_objects: SyncObjects,
     /// The amount of time the processor needs to sleep for a process to wake up if there are no ready processes to schedule.
     /// Is `0` if there are ready processes.
-    sleep_time: usize
+    sleep_time: usize,
+    /// Whether the processes are weighted by their nice levels.
+    weighted: bool,
+    /// The time since the first process was forked.
+    time: usize,
+    /// The minimum vruntime from all processes, updated every time a process stops.
+    min_vruntime: usize,
+    /// Whether the process with pid 1 has exited.
+    pid_1_exited: bool
 }
 
 impl Cfs {
@@ -33,15 +69,65 @@ impl Cfs {
         Self { running_process: None,
             stopped_process: None,
             remaining_time: 0,
-            ready_processes: Vec::<Pcb>::new(),
+            timeline: BTreeMap::<(usize, Pid), Pcb>::new(),
+            ready_times: HashMap::<Pid, usize>::new(),
+            timeline_weight: 0,
             waiting_processes: Vec::<Pcb>::new(),
             cpu_time,
             minimum_remaining_timeslice,
             highest_pid: 0,
-            sleep_time: 0
+            exited_processes: ExitedProcesses::new(),
+            sync_objects: SyncObjects::new(),
+            sleep_time: 0,
+            weighted: false,
+            time: 0,
+            min_vruntime: 0,
+            pid_1_exited: false
         }
     }
 
+    /// Creates a new [`Cfs`] that weights the processes by their nice levels.
+    ///
+    /// The nice level of a process is the opposite of its priority, clamped between
+    /// `-20` and `19`. The vruntime advances in inverse proportion to the weight of the
+    /// nice level and is kept in 1/1024 time units, the vruntime of a process with
+    /// the nice level `0` advancing by 1024 for each time unit it runs.
+    pub fn weighted(cpu_time: NonZeroUsize, minimum_remaining_timeslice: usize) -> Self {
+        Self { weighted: true, ..Cfs::new(cpu_time, minimum_remaining_timeslice) }
+    }
+
+    /// Returns the nice level for a priority.
+    fn nice(priority: i8) -> i8 {
+        priority.saturating_neg().clamp(-20, 19)
+    }
+
+    /// Returns the weight of a process with the given priority.
+    fn weight(&self, priority: i8) -> usize {
+        if self.weighted {
+            SCHED_PRIO_TO_WEIGHT[(Cfs::nice(priority) + 20) as usize]
+        } else {
+            NICE_0_WEIGHT
+        }
+    }
+
+    /// Returns the amount by which the vruntime of a process advances when it runs for `time`.
+    fn vruntime_delta(&self, priority: i8, time: usize) -> usize {
+        if self.weighted {
+            time * NICE_0_WEIGHT * NICE_0_WEIGHT / self.weight(priority)
+        } else {
+            time
+        }
+    }
+
+    /// Shows the vruntime of a process in its extra details, and its weight if the processes are weighted.
+    fn update_extra(&self, process: &mut Pcb) {
+        let mut extra = String::from("vruntime=") + process.vruntime().to_string().as_str();
+        if self.weighted {
+            extra += format!(" nice={} weight={}", Cfs::nice(process.priority()), self.weight(process.priority())).as_str();
+        }
+        process.set_extra(extra);
+    }
+
     /// Increments the timings for all processes.
     fn increment_timings(&mut self, _reason: &StopReason) {
         let time = match _reason {
@@ -49,9 +135,9 @@ impl Cfs {
             StopReason::Syscall { syscall: _, remaining } => self.remaining_time - *remaining
         };
 
-        if let Some(stopped_process) = &mut self.stopped_process {
-            *stopped_process += time;
-            stopped_process.set_extra(String::from("vruntime=") + stopped_process.vruntime().to_string().as_str());
+        if let Some(mut stopped_process) = self.stopped_process.take() {
+            stopped_process += self.vruntime_delta(stopped_process.priority(), time);
+            self.update_extra(&mut stopped_process);
             match _reason {
                 StopReason::Syscall { syscall: _, remaining: _ } => {
                     stopped_process.increment_timings(time, 1, time - 1);
@@ -60,11 +146,10 @@ impl Cfs {
                     stopped_process.increment_timings(time, 0, time);
                 }
             }
+            self.stopped_process = Some(stopped_process);
         }
 
-        for process in self.ready_processes.iter_mut() {
-            process.increment_timings(time, 0, 0);
-        }
+        self.time += time;
 
         for process in self.waiting_processes.iter_mut() {
             process.increment_timings(time, 0, 0);
@@ -79,24 +164,25 @@ impl Cfs {
                 }
             }
         }
+        self.update_min_vruntime();
     }
 
     /// Moves processes that have waked up into the list of ready processes.
     fn wakeup_processes(&mut self) {
         let mut still_waiting_processes = Vec::<Pcb>::new();
-        let process_iter = self.waiting_processes.iter().cloned();
-        for process in process_iter {
+        for process in take(&mut self.waiting_processes) {
             if matches!(process.state(), ProcessState::Ready) {
-                self.ready_processes.push(process);
+                self.enqueue(process);
             } else {
                 still_waiting_processes.push(process);
             }
         }
-        self.waiting_processes.clone_from(&still_waiting_processes);
+        self.waiting_processes = still_waiting_processes;
     }
 
     /// Sleeps for the amount of time needed for a process to become ready for scheduling.
     fn sleep(&mut self) {
+        self.time += self.sleep_time;
         for process in self.waiting_processes.iter_mut() {
             process.increment_timings(self.sleep_time, 0, 0);
             if let WakeupCondition::Sleep(wakeup_time) = process.wakeup() {
@@ -115,16 +201,44 @@ impl Cfs {
     }
 
     /// Forks a new process with the given priority.
-    fn new_process(&mut self, priority: i8, vruntime: usize) {
+    fn new_process(&mut self, priority: i8, vruntime: usize, parent: Option<Pid>) {
         self.highest_pid += 1;
         let mut new_process = Pcb::new(Pid::new(self.highest_pid), priority, vruntime);
-        new_process.set_extra(String::from("vruntime=") + vruntime.to_string().as_str());
-        self.ready_processes.push(new_process);
+        new_process.set_parent(parent);
+        self.update_extra(&mut new_process);
+        self.enqueue(new_process);
+    }
+
+    /// Inserts a ready process into the timeline.
+    fn enqueue(&mut self, process: Pcb) {
+        self.timeline_weight += self.weight(process.priority());
+        self.ready_times.insert(process.pid(), self.time);
+        self.timeline.insert((process.vruntime(), process.pid()), process);
+    }
+
+    /// Accounts for a process that has left the timeline, incrementing its total time
+    /// by the time it has been ready, and returns it.
+    fn dequeue(&mut self, mut process: Pcb) -> Pcb {
+        self.timeline_weight -= self.weight(process.priority());
+        if let Some(ready_time) = self.ready_times.remove(&process.pid()) {
+            process.increment_timings(self.time - ready_time, 0, 0);
+        }
+        process
+    }
+
+    /// Increments the total time of the processes in the timeline up to the current time.
+    fn update_ready_timings(&mut self) {
+        let time = self.time;
+        for process in self.timeline.values_mut() {
+            if let Some(ready_time) = self.ready_times.insert(process.pid(), time) {
+                process.increment_timings(time - ready_time, 0, 0);
+            }
+        }
     }
     
     /// Returns the total number of processes.
     fn size(&self) -> usize {
-        let mut length = self.ready_processes.len();
+        let mut length = self.timeline.len();
         if self.running_process.is_some() {
             length += 1;
         }
@@ -138,7 +252,7 @@ impl Cfs {
     fn set_ready(&mut self, mut process: Pcb) {
         process.set_state(ProcessState::Ready);
         process.set_wakeup(WakeupCondition::None);
-        self.ready_processes.push(process);
+        self.enqueue(process);
         self.remaining_time = 0;
     }
 
@@ -151,33 +265,18 @@ impl Cfs {
 
     /// Returns `true` if there are no more processes, `false` otherwise.
     fn is_done(&self) -> bool {
-        self.running_process.is_none() && self.ready_processes.is_empty() && self.waiting_processes.is_empty()
+        self.running_process.is_none() && self.timeline.is_empty() && self.waiting_processes.is_empty()
     }
 
     /// Returns `true` if the process with pid 1 exists, `false` otherwise.
     fn pid_1_exists(&self) -> bool {
-        if let Some(running_process) = &self.running_process {
-            if running_process.pid().cmp(&Pid::new(1)).is_eq() {
-                return true;
-            }
-        }
-        if self.ready_processes.iter().any(|element| element.pid().cmp(&Pid::new(1)).is_eq()) {
-            return true;
-        }
-        if self.waiting_processes.iter().any(|element| element.pid().cmp(&Pid::new(1)).is_eq()) {
-            return true;
-        }
-        false
+        self.highest_pid >= 1 && !self.pid_1_exited
     }
 
-    /// Returns the process scheduled to be run.
+    /// Removes the ready process with the lowest vruntime from the timeline and returns it.
     fn scheduled_process(&mut self) -> Option<Pcb> {
-        if !self.ready_processes.is_empty() {
-            self.ready_processes.sort();
-            Some(self.ready_processes.remove(0))
-        } else {
-            None
-        }
+        let (_, process) = self.timeline.pop_first()?;
+        Some(self.dequeue(process))
     }
 
     /// Returns the minimal amount of time the processor needs to sleep for a process to become ready for scheduling.
@@ -199,7 +298,7 @@ impl Cfs {
     /// Return an vector of refrences to all processes.
     fn get_all_processes(&self) -> Vec<&Pcb> {
         let mut processes = Vec::<&Pcb>::new();
-        processes.extend(self.ready_processes.iter());
+        processes.extend(self.timeline.values());
         processes.extend(self.waiting_processes.iter());
         if let Some(running_process) = &self.running_process {
             processes.push(running_process);
@@ -207,45 +306,70 @@ impl Cfs {
         processes
     }
 
-    /// Gets the minimum timeslice from all processes.
-    fn min_vruntime(&self) -> usize {
-        let mut processes = Vec::<&Pcb>::new();
-        processes.extend(self.ready_processes.iter());
-        processes.extend(self.waiting_processes.iter());
-        if let Some(stopped_process) = &self.stopped_process {
-            processes.push(stopped_process);
-        }
-
-        processes.iter().fold(None, |minimum: Option<usize>, process|
-            match minimum {
-                Some(minimum) => Some(minimum.min(process.vruntime())),                  
-                None => Some(process.vruntime())
-            }).unwrap_or(0)
+    /// Updates the minimum vruntime from all processes.
+    ///
+    /// The lowest vruntime of the ready processes is the first one in the timeline,
+    /// only the waiting processes have to be searched.
+    fn update_min_vruntime(&mut self) {
+        let waiting = self.waiting_processes.iter().map(|process| process.vruntime()).min();
+        let ready = self.timeline.first_key_value().map(|((vruntime, _), _)| *vruntime);
+        let stopped = self.stopped_process.as_ref().map(|process| process.vruntime());
+        self.min_vruntime = [waiting, ready, stopped].into_iter().flatten().min().unwrap_or(0);
     }
 
-    /// Computes the timeslice for the scheduled process.
-    fn compute_timeslice(&self) -> NonZeroUsize {
-        if self.cpu_time.get() / self.minimum_remaining_timeslice >= self.size() {
+    /// Computes the timeslice for the scheduled process, which has the given priority.
+    ///
+    /// If the processes are weighted, the timeslice is the share of the `cpu_time` given by
+    /// the weight of the process, but not less than the `minimum_remaining_timeslice`.
+    fn compute_timeslice(&self, priority: i8) -> NonZeroUsize {
+        if self.weighted {
+            let total_weight = self.timeline_weight
+                + self.running_process.iter().chain(self.stopped_process.iter())
+                    .map(|process| self.weight(process.priority())).sum::<usize>();
+            let timeslice = self.cpu_time.get() * self.weight(priority) / total_weight.max(1);
+            match NonZeroUsize::new(timeslice.max(self.minimum_remaining_timeslice).max(1)) {Some(value) => value, None => exit(-1)}
+        } else if self.cpu_time.get() / self.minimum_remaining_timeslice >= self.size() {
             match NonZeroUsize::new(self.cpu_time.get() / self.size()) {Some(value) => value, None => exit(-1)}
         } else {
             match NonZeroUsize::new(self.minimum_remaining_timeslice) {Some(value) => value, None => exit(-1)}
         }
     }
 
+    /// Schedules the stopped process again if it has enough time left from its quanta,
+    /// or puts it back in the ready queue otherwise.
+    fn resume(&mut self, remaining_time: usize) {
+        match self.stopped_process.take() {
+            Some(mut stopped_process) => {
+                if remaining_time >= self.minimum_remaining_timeslice {
+                    stopped_process.set_state(ProcessState::Running);
+                    let priority = stopped_process.priority();
+                    self.running_process = Some(stopped_process);
+                    self.remaining_time = self.compute_timeslice(priority).get().min(remaining_time);
+                } else {
+                    self.set_ready(stopped_process)
+                }
+            },
+            None => {
+                self.remaining_time = 0;
+            }
+        }
+    }
+
     /// Handles syscalls recievied from the running process.
     fn syscall_handler(&mut self, syscall: Syscall, remaining_time: usize) -> SyscallResult {
         match syscall {
-            Syscall::Fork(priority) => {
+            Syscall::Fork(priority) | Syscall::ForkDeadline { priority, deadline: _, period: _ }
+            | Syscall::ForkBurst { priority, burst: _ } => {
 
                 self.wakeup_processes();
-                let min_vruntime = self.min_vruntime();
-                self.new_process(priority, min_vruntime);
+                self.new_process(priority, self.min_vruntime, self.stopped_process.as_ref().map(|process| process.pid()));
                 match self.stopped_process.take() {
                     Some(mut stopped_process) => {
                         if remaining_time >= self.minimum_remaining_timeslice {
                             stopped_process.set_state(ProcessState::Running);
+                            let priority = stopped_process.priority();
                             self.running_process = Some(stopped_process);
-                            self.remaining_time = self.compute_timeslice().get().min(remaining_time);
+                            self.remaining_time = self.compute_timeslice(priority).get().min(remaining_time);
                         } else {
                             self.set_ready(stopped_process)
                         }
@@ -269,8 +393,9 @@ impl Cfs {
                     Some(mut stopped_process) => {
                         if remaining_time >= self.minimum_remaining_timeslice {
                             stopped_process.set_state(ProcessState::Running);
+                            let priority = stopped_process.priority();
                             self.running_process = Some(stopped_process);
-                            self.remaining_time = self.compute_timeslice().get().min(remaining_time);
+                            self.remaining_time = self.compute_timeslice(priority).get().min(remaining_time);
                         } else {
                             self.set_ready(stopped_process)
                         }
@@ -300,9 +425,35 @@ impl Cfs {
                     None => return SyscallResult::NoRunningProcess
                 }
             },
-            Syscall::Exit => {
-                self.stopped_process = None;
+            Syscall::Exit(status) => {
+                if let Some(stopped_process) = self.stopped_process.take() {
+                    self.pid_1_exited |= stopped_process.pid() == Pid::new(1);
+                    self.shared().exit(stopped_process, status);
+                }
             },
+            syscall => {
+                let mut stopped_process = self.stopped_process.take();
+                let outcome = self.shared_syscall(syscall, &mut stopped_process);
+                self.stopped_process = stopped_process;
+                if let (Syscall::Kill(pid), Outcome::Resume { result: SyscallResult::Success, .. }) = (syscall, &outcome) {
+                    self.pid_1_exited |= pid == Pid::new(1);
+                    self.update_min_vruntime();
+                }
+                return match outcome {
+                    Outcome::Blocked => {
+                        self.wakeup_processes();
+                        SyscallResult::Pending
+                    },
+                    Outcome::Resume { result, wakeup } => {
+                        if wakeup {
+                            self.wakeup_processes();
+                        }
+                        self.resume(remaining_time);
+                        result
+                    },
+                    Outcome::NoRunningProcess => SyscallResult::NoRunningProcess
+                };
+            }
         };
         self.wakeup_processes();
         SyscallResult::Success
@@ -311,6 +462,34 @@ impl Cfs {
 }
 
 
+impl SharedSyscalls for Cfs {
+    fn shared(&mut self) -> Shared<'_> {
+        Shared {
+            waiting_processes: &mut self.waiting_processes,
+            exited_processes: &mut self.exited_processes,
+            sync_objects: &mut self.sync_objects
+        }
+    }
+
+    fn processes(&self) -> Vec<&Pcb> {
+        self.get_all_processes()
+    }
+
+    /// Removes the process with the PID `pid` from the timeline or the waiting processes.
+    fn remove_process(&mut self, pid: Pid) -> Option<Pcb> {
+        if self.ready_times.contains_key(&pid) {
+            let key = self.timeline.keys().find(|(_, element)| *element == pid).copied();
+            if let Some(process) = key.and_then(|key| self.timeline.remove(&key)) {
+                return Some(self.dequeue(process));
+            }
+        }
+        if let Some(index) = self.waiting_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.waiting_processes.remove(index));
+        }
+        None
+    }
+}
+
 impl Scheduler for Cfs {
     fn next(&mut self) -> SchedulingDecision {
         if self.sleep_time != 0 {
@@ -330,8 +509,8 @@ impl Scheduler for Cfs {
                 match NonZeroUsize::new(self.remaining_time) {Some(time) => time, None => exit(-1)}};
         }
 
-        if !self.ready_processes.is_empty() {
-            let timeslice = self.compute_timeslice();
+        if let Some((_, process)) = self.timeline.first_key_value() {
+            let timeslice = self.compute_timeslice(process.priority());
             if let Some(scheduled_process) = self.scheduled_process() {
                 self.set_running(scheduled_process, timeslice.get());
                 return SchedulingDecision::Run { pid: match &self.running_process {Some(process) => process.pid(), None => exit(-1)},
@@ -377,10 +556,20 @@ impl Scheduler for Cfs {
     }
 
     fn list(&mut self) -> Vec<&dyn Process> {
+        self.update_ready_timings();
         let mut processes = self.get_all_processes();
+        processes.extend(self.exited_processes.zombies());
 
         processes.sort_by_key(|element|  element.pid());
 
         processes.into_iter().map(|element| element as &dyn Process).collect()
     }
+
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        self.shared_completions()
+    }
+
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        self.sync_objects.blockers(&self.waiting_processes)
+    }
 }
@@ -0,0 +1,24 @@
+use super::pcb::{Pcb, RealTime};
+use super::real_time::Policy;
+
+/// The earliest deadline first policy of a [`super::RealTimeScheduler`].
+///
+/// The ready process whose current job has the nearest absolute deadline is scheduled first.
+pub const EDF: Policy = Policy {
+    key: deadline,
+    extra,
+    preemptive: false
+};
+
+/// Returns the absolute deadline of a process, [`usize::MAX`] for processes without a deadline.
+fn deadline(process: &Pcb) -> usize {
+    match process.real_time() {
+        Some(real_time) => real_time.absolute_deadline(),
+        None => usize::MAX
+    }
+}
+
+/// Shows the deadline and the deadline misses of a process.
+fn extra(_process: &Pcb, real_time: &RealTime) -> String {
+    real_time.to_string()
+}
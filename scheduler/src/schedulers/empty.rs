@@ -1,5 +1,7 @@
 use crate::Scheduler;
 
+/// The starting point of a new scheduler, not used by the library.
+#[allow(dead_code)]
 pub struct Empty;
 
 impl Scheduler for Empty {
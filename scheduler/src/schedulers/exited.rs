@@ -0,0 +1,76 @@
+use std::{collections::HashSet, mem::take};
+use crate::{Pid, Process, ProcessState, Syscall, SyscallResult};
+use super::pcb::{Pcb, WakeupCondition};
+
+/// The exit status of a process that has been killed.
+pub const KILLED: i32 = -9;
+
+/// The processes that have exited and have not been joined by their parents yet.
+///
+/// An exited process is kept as a zombie until its parent joins it. A parent that
+/// joins a child before the child exits waits until the child exits, the exit status
+/// being returned to it as a completion.
+pub struct ExitedProcesses {
+    /// The processes that have exited before their parents have joined them.
+    zombies: Vec<Pcb>,
+    /// The PIDs of all the processes that have exited.
+    exited: HashSet<Pid>,
+    /// The results of the join system calls that have completed since the last call to
+    /// [`ExitedProcesses::completions`].
+    completions: Vec<(Pid, Syscall, SyscallResult)>
+}
+
+impl ExitedProcesses {
+
+    /// Creates a new [`ExitedProcesses`].
+    pub fn new() -> Self {
+        Self { zombies: Vec::<Pcb>::new(), exited: HashSet::new(), completions: Vec::new() }
+    }
+
+    /// Records that `process` has exited with the exit status `status`.
+    ///
+    /// If the parent of the process is one of the `waiting_processes` and waits for the
+    /// process to exit, the parent is set into the ready state. Otherwise the process
+    /// becomes a zombie, unless its parent has exited too.
+    pub fn exit(&mut self, mut process: Pcb, status: i32, waiting_processes: &mut [Pcb]) {
+        let pid = process.pid();
+        process.set_exit_status(status);
+        process.set_state(ProcessState::Zombie);
+        process.set_wakeup(WakeupCondition::None);
+        self.exited.insert(pid);
+
+        // nobody can join the children of the process anymore
+        self.zombies.retain(|element| element.ppid() != Some(pid));
+
+        match waiting_processes.iter_mut()
+            .find(|element| matches!(element.wakeup(), WakeupCondition::Join(x) if x == pid)) {
+            Some(parent) => {
+                parent.set_state(ProcessState::Ready);
+                parent.set_wakeup(WakeupCondition::None);
+                self.completions.push((parent.pid(), Syscall::Join(pid), SyscallResult::ExitStatus(status)));
+            },
+            None => {
+                if process.ppid().is_some_and(|parent| !self.exited.contains(&parent)) {
+                    self.zombies.push(process);
+                }
+            }
+        }
+    }
+
+    /// Removes the child `pid` of the process `parent` if it is a zombie and returns its exit status.
+    pub fn join(&mut self, parent: Pid, pid: Pid) -> Option<i32> {
+        let index = self.zombies.iter()
+            .position(|element| element.pid() == pid && element.ppid() == Some(parent))?;
+        self.zombies.remove(index).exit_status()
+    }
+
+    /// Returns the processes that have exited and have not been joined yet.
+    pub fn zombies(&self) -> impl Iterator<Item = &Pcb> {
+        self.zombies.iter()
+    }
+
+    /// Returns the results of the join system calls that have completed since the last call.
+    pub fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        take(&mut self.completions)
+    }
+}
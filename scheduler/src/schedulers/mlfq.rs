@@ -0,0 +1,476 @@
+use std::{num::NonZeroUsize, process::exit};
+use crate::{Blocker, Scheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
+use super::pcb::{Pcb, WakeupCondition};
+use super::exited::ExitedProcesses;
+use super::sync::{Outcome, Shared, SharedSyscalls, SyncObjects};
+
+/// The settings of a queue level of the [`crate::mlfq`] scheduler.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MlfqLevel {
+    /// The amount of time a ready process of the level gets on the processor.
+    pub quantum: NonZeroUsize,
+    /// The total amount of time a process can use at the level before it is moved
+    /// to the level below.
+    pub allotment: NonZeroUsize
+}
+
+impl MlfqLevel {
+
+    /// Creates the settings of a queue level.
+    pub fn new(quantum: NonZeroUsize, allotment: NonZeroUsize) -> Self {
+        Self { quantum, allotment }
+    }
+}
+
+/// Data structure that implements a multi-level feedback queue scheduler.
+///
+/// Level `0` is the level with the highest priority, new processes start on it.
+/// A process that has used the allotment of its level is moved to the level below,
+/// and all the processes are periodically moved back to level `0`.
+pub struct MlfqScheduler {
+    /// The process running on the processor.
+    running_process: Option<Pcb>,
+    /// Intermediate state a process is in during syscalls.
+    stopped_process: Option<Pcb>,
+    /// The remaining execution time for the scheduled process.
+    remaining_time: usize,
+    /// The list of process queues, indexed by level.
+    ready_processes: Vec<Vec<Pcb>>,
+    /// The list of all processes waiting for an event or sleeping.
+    waiting_processes: Vec<Pcb>,
+    /// The settings of each level.
+    levels: Vec<MlfqLevel>,
+    /// The amount of time between two priority boosts, [`None`] if processes are never boosted.
+    boost_period: Option<NonZeroUsize>,
+    /// The minimum required time on the processor the stopped process must have remaining
+    /// for it to be scheduled imediately after the syscall that stopped it.
+    minimum_remaining_timeslice: usize,
+    /// The highest pid given to a process.
+    highest_pid: usize,
+    /// The processes that have exited and have not been joined by their parents yet.
+    exited_processes: ExitedProcesses,
+    /// The locks and the semaphores that the processes use to synchronise.
+    sync_objects: SyncObjects,
+    /// The amount of time the processor needs to sleep for a process to wake up if there are no ready processes to schedule.
+    /// Is `0` if there are ready processes.
+    sleep_time: usize,
+    /// The current time of the processor.
+    time: usize,
+    /// The time of the last priority boost.
+    boost_time: usize,
+    /// The events that have not been reported yet.
+    events: Vec<String>
+}
+
+impl MlfqScheduler {
+
+    /// Creates a new [`MlfqScheduler`].
+    ///
+    /// Panics if `levels` is empty.
+    pub fn new(levels: &[MlfqLevel], boost_period: Option<NonZeroUsize>, minimum_remaining_timeslice: usize) -> Self {
+        if levels.is_empty() {
+            panic!("The scheduler needs at least one level");
+        }
+        Self { running_process: None,
+            stopped_process: None,
+            remaining_time: 0,
+            ready_processes: vec![Vec::<Pcb>::new(); levels.len()],
+            waiting_processes: Vec::<Pcb>::new(),
+            levels: levels.to_vec(),
+            boost_period,
+            minimum_remaining_timeslice,
+            highest_pid: 0,
+            exited_processes: ExitedProcesses::new(),
+            sync_objects: SyncObjects::new(),
+            sleep_time: 0,
+            time: 0,
+            boost_time: 0,
+            events: Vec::<String>::new()
+        }
+    }
+
+    /// Shows the level of a process in its extra details.
+    fn update_extra(process: &mut Pcb) {
+        let extra = format!("level={} used={}", process.level(), process.level_time());
+        process.set_extra(extra);
+    }
+
+    /// Moves processes that have waked up into the list of ready processes.
+    fn wakeup_processes(&mut self) {
+        let mut still_waiting_processes = Vec::<Pcb>::new();
+        for process in self.waiting_processes.drain(..) {
+            if matches!(process.state(), ProcessState::Ready) {
+                match self.ready_processes.get_mut(process.level()) {
+                    Some(process_queue) => process_queue.push(process),
+                    None => exit(-1)
+                }
+            } else {
+                still_waiting_processes.push(process);
+            }
+        }
+        self.waiting_processes = still_waiting_processes;
+    }
+
+    /// Increments the timings for all processes and charges the stopped process
+    /// for the time it has used at its level.
+    fn increment_timings(&mut self, _reason: &StopReason) {
+        let time = match _reason {
+            StopReason::Expired => self.remaining_time,
+            StopReason::Syscall { syscall: _, remaining } => self.remaining_time - *remaining
+        };
+        self.time += time;
+
+        if let Some(stopped_process) = &mut self.stopped_process {
+            match _reason {
+                StopReason::Syscall { syscall: _, remaining: _ } => stopped_process.increment_timings(time, 1, time - 1),
+                StopReason::Expired => stopped_process.increment_timings(time, 0, time)
+            }
+            stopped_process.increment_level_time(time);
+            let level = stopped_process.level();
+            if stopped_process.level_time() >= self.levels[level].allotment.get() {
+                stopped_process.set_level((level + 1).min(self.levels.len() - 1));
+            }
+            MlfqScheduler::update_extra(stopped_process);
+        }
+
+        for process in self.ready_processes.iter_mut().flatten() {
+            process.increment_timings(time, 0, 0);
+        }
+
+        for process in self.waiting_processes.iter_mut() {
+            process.increment_timings(time, 0, 0);
+            if let WakeupCondition::Sleep(sleep_time) = process.wakeup() {
+                match sleep_time.checked_sub(time).filter(|remaining_time| *remaining_time != 0) {
+                    Some(remaining_time) =>
+                        process.set_wakeup(WakeupCondition::Sleep(remaining_time)),
+                    None => {
+                        process.set_wakeup(WakeupCondition::None);
+                        process.set_state(ProcessState::Ready);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sleeps for the amount of time needed for a process to become ready for scheduling.
+    fn sleep(&mut self) {
+        self.time += self.sleep_time;
+        for process in self.waiting_processes.iter_mut() {
+            process.increment_timings(self.sleep_time, 0, 0);
+            if let WakeupCondition::Sleep(wakeup_time) = process.wakeup() {
+                match wakeup_time.checked_sub(self.sleep_time).filter(|remaining_time| *remaining_time != 0) {
+                    Some(remaining_time) =>
+                        process.set_wakeup(WakeupCondition::Sleep(remaining_time)),
+                    None => {
+                        process.set_wakeup(WakeupCondition::None);
+                        process.set_state(ProcessState::Ready);
+                    }
+                }
+            }
+        }
+        self.sleep_time = 0;
+        self.wakeup_processes();
+    }
+
+    /// Moves all the processes to level `0` if the boost period has elapsed.
+    ///
+    /// The boost takes place at the first scheduling decision after the end of the period.
+    fn boost(&mut self) {
+        let Some(boost_period) = self.boost_period else {
+            return;
+        };
+        let elapsed = self.time - self.boost_time;
+        if elapsed < boost_period.get() {
+            return;
+        }
+        self.boost_time = self.time - elapsed % boost_period.get();
+
+        let mut boosted_processes = Vec::<Pcb>::new();
+        for process_queue in self.ready_processes.iter_mut() {
+            boosted_processes.append(process_queue);
+        }
+        for process in boosted_processes.iter_mut()
+            .chain(self.waiting_processes.iter_mut())
+            .chain(self.running_process.iter_mut()) {
+            process.set_level(0);
+            MlfqScheduler::update_extra(process);
+        }
+        self.ready_processes[0] = boosted_processes;
+        self.events.push(format!("Boost at time {}, all processes moved to level 0", self.time));
+    }
+
+    /// Forks a new process, on level `0`.
+    fn new_process(&mut self, priority: i8, parent: Option<Pid>) {
+        self.highest_pid += 1;
+        let mut process = Pcb::new(Pid::new(self.highest_pid), priority, 0);
+        process.set_parent(parent);
+        MlfqScheduler::update_extra(&mut process);
+        self.ready_processes[0].push(process);
+    }
+
+    /// Returns the highest level that has ready processes.
+    fn highest_ready_level(&self) -> Option<usize> {
+        self.ready_processes.iter().position(|process_queue| !process_queue.is_empty())
+    }
+
+    /// Schedules the stopped process again if it has enough time left from its quanta
+    /// and no ready process is on a higher level, or puts it back in the ready queue otherwise.
+    fn resume(&mut self, remaining_time: usize) {
+        match self.stopped_process.take() {
+            Some(mut stopped_process) => {
+                let level = stopped_process.level();
+                if remaining_time >= self.minimum_remaining_timeslice
+                    && self.highest_ready_level().is_none_or(|ready_level| ready_level >= level) {
+                    stopped_process.set_state(ProcessState::Running);
+                    self.running_process = Some(stopped_process);
+                    self.remaining_time = remaining_time;
+                } else {
+                    self.set_ready(stopped_process)
+                }
+            },
+            None => {
+                self.remaining_time = 0;
+            }
+        }
+    }
+
+    /// Handles syscalls recievied from the running process.
+    fn syscall_handler(&mut self, syscall: Syscall, remaining_time: usize) -> SyscallResult {
+        match syscall {
+            Syscall::Fork(priority) | Syscall::ForkDeadline { priority, deadline: _, period: _ }
+            | Syscall::ForkBurst { priority, burst: _ } => {
+                self.new_process(priority, self.stopped_process.as_ref().map(|process| process.pid()));
+                self.wakeup_processes();
+                self.resume(remaining_time);
+                return SyscallResult::Pid(Pid::new(self.highest_pid));
+            }
+            Syscall::Signal(event) => {
+                for process in self.waiting_processes.iter_mut()
+                    .filter(|element| matches!(element.wakeup(), WakeupCondition::Signal(x) if x == event)) {
+                    process.set_state(ProcessState::Ready);
+                    process.set_wakeup(WakeupCondition::None);
+                }
+
+                self.wakeup_processes();
+                self.resume(remaining_time);
+            },
+            Syscall::Sleep(sleep_time) => {
+                match self.stopped_process.take() {
+                    Some(mut stopped_process) => {
+                        stopped_process.set_state(ProcessState::Waiting { event: None });
+                        stopped_process.set_wakeup(WakeupCondition::Sleep(sleep_time));
+                        self.waiting_processes.push(stopped_process);
+                    },
+                    None => return SyscallResult::NoRunningProcess
+                }
+            },
+            Syscall::Wait(event) => {
+                match self.stopped_process.take() {
+                    Some(mut stopped_process) => {
+                        stopped_process.set_state(ProcessState::Waiting { event: Some(event) });
+                        stopped_process.set_wakeup(WakeupCondition::Signal(event));
+                        self.waiting_processes.push(stopped_process);
+                    },
+                    None => return SyscallResult::NoRunningProcess
+                }
+            },
+            Syscall::Exit(status) => {
+                if let Some(stopped_process) = self.stopped_process.take() {
+                    self.shared().exit(stopped_process, status);
+                }
+                self.wakeup_processes();
+            },
+            syscall => {
+                let mut stopped_process = self.stopped_process.take();
+                let outcome = self.shared_syscall(syscall, &mut stopped_process);
+                self.stopped_process = stopped_process;
+                return match outcome {
+                    Outcome::Blocked => {
+                        self.wakeup_processes();
+                        SyscallResult::Pending
+                    },
+                    Outcome::Resume { result, wakeup } => {
+                        if wakeup {
+                            self.wakeup_processes();
+                        }
+                        self.resume(remaining_time);
+                        result
+                    },
+                    Outcome::NoRunningProcess => SyscallResult::NoRunningProcess
+                };
+            }
+        };
+
+        SyscallResult::Success
+    }
+
+    /// Returns `true` if there are no more processes, `false` otherwise.
+    fn is_done(&self) -> bool {
+        self.running_process.is_none()
+        && self.ready_processes.iter()
+            .all(|process_queue| process_queue.is_empty())
+        && self.waiting_processes.is_empty()
+    }
+
+    /// Returns `true` if the process with pid 1 exists, `false` otherwise.
+    fn pid_1_exists(&self) -> bool {
+        self.get_all_processes().iter().any(|element| element.pid() == 1)
+    }
+
+    /// Returns the first process of the highest level that has ready processes.
+    fn scheduled_process(&mut self) -> Option<Pcb> {
+        let level = self.highest_ready_level()?;
+        Some(self.ready_processes[level].remove(0))
+    }
+
+    /// Returns the minimal amount of time the processor needs to sleep for a process to become ready for scheduling.
+    fn find_sleep_time(&self) -> Option<usize> {
+        self.waiting_processes.iter().filter_map(|element|
+            match element.wakeup() {WakeupCondition::Sleep(sleep_time) => Some(sleep_time), _ => None})
+            .min()
+    }
+
+    /// Sets a process into the ready state.
+    fn set_ready(&mut self, mut process: Pcb) {
+        process.set_state(ProcessState::Ready);
+        process.set_wakeup(WakeupCondition::None);
+        match self.ready_processes.get_mut(process.level()) {
+            Some(process_queue) => process_queue.push(process),
+            None => exit(-1)
+        }
+        self.remaining_time = 0;
+    }
+
+    /// Sets a process to into the running state, for the quantum of its level
+    /// but no longer than the allotment it has left.
+    fn set_running(&mut self, mut process: Pcb) {
+        let level = self.levels[process.level()];
+        process.set_state(ProcessState::Running);
+        self.remaining_time = level.quantum.get()
+            .min(level.allotment.get().saturating_sub(process.level_time()))
+            .max(1);
+        self.running_process = Some(process);
+    }
+
+    /// Return an vector of refrences to all processes.
+    fn get_all_processes(&self) -> Vec<&Pcb> {
+        let mut processes = Vec::<&Pcb>::new();
+        processes.extend(self.ready_processes.iter().flatten());
+        processes.extend(self.waiting_processes.iter());
+        if let Some(running_process) = &self.running_process {
+            processes.push(running_process);
+        }
+        processes
+    }
+
+}
+
+
+impl SharedSyscalls for MlfqScheduler {
+    fn shared(&mut self) -> Shared<'_> {
+        Shared {
+            waiting_processes: &mut self.waiting_processes,
+            exited_processes: &mut self.exited_processes,
+            sync_objects: &mut self.sync_objects
+        }
+    }
+
+    fn processes(&self) -> Vec<&Pcb> {
+        self.get_all_processes()
+    }
+
+    fn remove_process(&mut self, pid: Pid) -> Option<Pcb> {
+        for process_queue in self.ready_processes.iter_mut() {
+            if let Some(index) = process_queue.iter().position(|element| element.pid() == pid) {
+                return Some(process_queue.remove(index));
+            }
+        }
+        if let Some(index) = self.waiting_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.waiting_processes.remove(index));
+        }
+        None
+    }
+}
+
+impl Scheduler for MlfqScheduler {
+    fn next(&mut self) -> SchedulingDecision {
+        if self.sleep_time != 0 {
+            self.sleep();
+        }
+
+        self.boost();
+
+        if self.is_done() {
+            return SchedulingDecision::Done;
+        }
+
+        if !self.pid_1_exists() {
+            return SchedulingDecision::Panic;
+        }
+
+        if let Some(scheduled_process) = &mut self.running_process {
+            return SchedulingDecision::Run { pid: scheduled_process.pid(), timeslice:
+                match NonZeroUsize::new(self.remaining_time) {Some(time) => time, None => exit(-1)}};
+        }
+
+        if let Some(scheduled_process) = self.scheduled_process() {
+            self.set_running(scheduled_process);
+            return SchedulingDecision::Run { pid: match &self.running_process {Some(process) => process.pid(), None => exit(-1)},
+            timeslice: match NonZeroUsize::new(self.remaining_time) {Some(time) => time, None => exit(-1)} };
+        }
+
+        match self.find_sleep_time() {
+            Some(sleep_time) => {
+                self.sleep_time = sleep_time;
+                SchedulingDecision::Sleep(match NonZeroUsize::new(sleep_time)
+                    {Some(sleep_time) => sleep_time, None => exit(-1)})
+            },
+            None => SchedulingDecision::Deadlock
+        }
+    }
+
+    fn stop(&mut self, _reason: StopReason) -> SyscallResult {
+        self.stopped_process = self.running_process.take();
+
+        self.increment_timings(&_reason);
+
+        match _reason {
+            StopReason::Expired => {
+                self.wakeup_processes();
+                match self.stopped_process.take() {
+                    Some(stopped_process) => {
+                        self.set_ready(stopped_process);
+                        SyscallResult::Success
+                    },
+                    None => {
+                        SyscallResult::NoRunningProcess
+                    }
+                }
+            },
+            StopReason::Syscall{ syscall, remaining } => {
+                self.syscall_handler(syscall, remaining)
+            }
+        }
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        let mut processes = self.get_all_processes();
+        processes.extend(self.exited_processes.zombies());
+
+        processes.sort_by_key(|element|  element.pid());
+
+        processes.into_iter().map(|element| element as &dyn Process).collect()
+    }
+
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        self.shared_completions()
+    }
+
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        self.sync_objects.blockers(&self.waiting_processes)
+    }
+
+    fn events(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.events)
+    }
+}
@@ -11,9 +11,10 @@
 //!
 
 mod pcb;
+mod exited;
+mod sync;
 
 mod empty;
-pub use empty::Empty;
 
 mod round_robin;
 pub use round_robin::RoundRobinScheduler;
@@ -24,4 +25,28 @@ pub use round_robin_priorities::RoundRobinPrioritiesScheduler;
 mod cfs;
 pub use cfs::Cfs;
 
+mod smp_round_robin;
+pub use smp_round_robin::SmpRoundRobinScheduler;
+
+mod real_time;
+pub use real_time::RealTimeScheduler;
+
+mod edf;
+pub use edf::EDF;
+
+mod rate_monotonic;
+pub use rate_monotonic::RATE_MONOTONIC;
+
+mod mlfq;
+pub use mlfq::{MlfqLevel, MlfqScheduler};
+
+mod lottery;
+pub use lottery::LotteryScheduler;
+
+mod stride;
+pub use stride::StrideScheduler;
+
+mod sjf;
+pub use sjf::SjfScheduler;
+
 // TODO import your schedulers here
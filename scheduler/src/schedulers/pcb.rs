@@ -1,18 +1,164 @@
+use std::fmt::Display;
 use std::ops::{AddAssign, Add};
 
 use crate::{ProcessState, Pid, Process};
 
 /// Enumerates the possible wakeup condition for [Pcb].
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, PartialEq)]
 pub enum WakeupCondition {
     /// Contains the amount of time units the process needs to sleep.
     Sleep(usize),
     /// Contains the event number of the event the process is waiting for.
     Signal(usize),
+    /// Contains the PID of the child process the process is waiting to exit.
+    Join(Pid),
+    /// Contains the number of the lock the process is waiting to acquire.
+    Lock(usize),
+    /// Contains the number of the semaphore the process is waiting to decrement.
+    Semaphore(usize),
     /// The process isn't waiting.
     None
 }
 
+/// The parameters of a process that has to finish its jobs before a deadline.
+#[derive(Clone, Copy)]
+pub struct RealTime {
+    /// The amount of time, from the release of a job, in which the job has to finish.
+    deadline: usize,
+    /// The amount of time between the releases of two jobs, `0` if a job is released
+    /// every time the process becomes ready.
+    period: usize,
+    /// The time at which the current job was released.
+    release: usize,
+    /// Whether the current job has missed its deadline.
+    missed: bool,
+    /// Whether the current job has ended, the next one being released when the process wakes up.
+    ended: bool,
+    /// The number of jobs that have missed their deadline.
+    misses: usize
+}
+
+impl RealTime {
+
+    /// Creates the parameters of a process whose first job is released at `release`.
+    pub fn new(deadline: usize, period: usize, release: usize) -> Self {
+        Self { deadline, period, release, missed: false, ended: false, misses: 0 }
+    }
+
+    /// Returns the time by which the current job has to finish.
+    pub fn absolute_deadline(&self) -> usize {
+        self.release + self.deadline
+    }
+
+    /// Returns the relative deadline of the jobs.
+    pub fn deadline(&self) -> usize {
+        self.deadline
+    }
+
+    /// Returns the period of the jobs.
+    pub fn period(&self) -> usize {
+        self.period
+    }
+
+    /// Ends the current job, when the process sleeps or waits for an event.
+    pub fn end(&mut self) {
+        self.ended = true;
+    }
+
+    /// Returns `true` if the current job has ended.
+    pub fn ended(&self) -> bool {
+        self.ended
+    }
+
+    /// Releases a new job at `time`. A periodic process releases the job at
+    /// the start of its latest period.
+    pub fn release(&mut self, time: usize) {
+        if self.period == 0 {
+            self.release = time;
+        } else {
+            self.release += self.period;
+            if time > self.release {
+                self.release += (time - self.release) / self.period * self.period;
+            }
+        }
+        self.missed = false;
+        self.ended = false;
+    }
+
+    /// Records a deadline miss if the current job has not finished by `time`.
+    pub fn check_deadline(&mut self, time: usize) {
+        if !self.missed && time > self.absolute_deadline() {
+            self.missed = true;
+            self.misses += 1;
+        }
+    }
+}
+
+impl Display for RealTime {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "deadline={} misses={}", self.absolute_deadline(), self.misses)
+    }
+}
+
+/// The prediction of the length of the CPU bursts of a process.
+///
+/// A CPU burst is the execution time of a process between two moments in which it
+/// sleeps or waits for an event.
+#[derive(Clone, Copy)]
+pub struct Burst {
+    /// The length of the bursts given when the process was forked, [`None`] if the
+    /// length is predicted from the previous bursts.
+    hint: Option<usize>,
+    /// The exponential average of the lengths of the previous bursts.
+    predicted: f64,
+    /// The execution time of the process when its current burst started.
+    start: usize
+}
+
+impl Burst {
+
+    /// Creates the prediction for a new process, that uses the `hint` if one is given
+    /// or starts the exponential average from `initial` otherwise.
+    pub fn new(hint: Option<usize>, initial: usize) -> Self {
+        Self { hint, predicted: initial as f64, start: 0 }
+    }
+
+    /// Returns the length of the bursts given when the process was forked.
+    pub fn hint(&self) -> Option<usize> {
+        self.hint
+    }
+
+    /// Returns the expected length of the current burst.
+    pub fn length(&self) -> usize {
+        self.hint.unwrap_or(self.predicted.round() as usize)
+    }
+
+    /// Returns the expected time left from the current burst of a process
+    /// that has executed for `execution` time units.
+    pub fn remaining(&self, execution: usize) -> usize {
+        self.length().saturating_sub(execution - self.start)
+    }
+
+    /// Ends the current burst of a process that has executed for `execution` time units.
+    ///
+    /// The prediction for the next burst is `alpha * t + (1 - alpha) * prediction`,
+    /// where `t` is the length of the burst that has ended.
+    pub fn end(&mut self, execution: usize, alpha: f64) {
+        let length = (execution - self.start) as f64;
+        self.predicted = alpha * length + (1.0 - alpha) * self.predicted;
+        self.start = execution;
+    }
+}
+
+impl Display for Burst {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.hint {
+            Some(hint) => write!(f, "hint={}", hint),
+            None => write!(f, "predicted={:.2}", self.predicted)
+        }
+    }
+}
+
 /// Data structure that implements a process.
 #[derive(Clone)]
 pub struct Pcb {
@@ -32,7 +178,22 @@ pub struct Pcb {
     /// The priority of the process.
     priority: i8,
     /// Extra information about the process.
-    extra: String
+    extra: String,
+    /// The deadline parameters of a real-time process, [`None`] for other processes.
+    real_time: Option<RealTime>,
+    /// The queue level of the process in a multi-level feedback queue.
+    level: usize,
+    /// The time the process has used at its current queue level.
+    level_time: usize,
+    /// The prediction of the CPU bursts of the process, [`None`] if the scheduler does not use it.
+    burst: Option<Burst>,
+    /// The PID of the process that has forked the process, [`None`] for the first process.
+    parent: Option<Pid>,
+    /// The exit status of the process, [`None`] if the process has not exited.
+    exit_status: Option<i32>,
+    /// The priority inherited from the processes blocked on the locks held by the process,
+    /// [`None`] if the process does not inherit any priority.
+    inherited_priority: Option<i8>
 }
 
 impl Pcb {
@@ -51,7 +212,14 @@ impl Pcb {
                wakeup: WakeupCondition::None,
                fork_priority: priority,
                priority,
-               extra: String::from("")
+               extra: String::from(""),
+               real_time: None,
+               level: 0,
+               level_time: 0,
+               burst: None,
+               parent: None,
+               exit_status: None,
+               inherited_priority: None
         }
     }
 
@@ -78,6 +246,82 @@ impl Pcb {
         self.extra = extra;
     }
 
+    /// Returns the deadline parameters of a [`Pcb`].
+    pub fn real_time(&self) -> Option<&RealTime> {
+        self.real_time.as_ref()
+    }
+
+    /// Returns the deadline parameters of a [`Pcb`] for modification.
+    pub fn real_time_mut(&mut self) -> Option<&mut RealTime> {
+        self.real_time.as_mut()
+    }
+
+    /// Sets the deadline parameters of a [`Pcb`].
+    pub fn set_real_time(&mut self, real_time: RealTime) {
+        self.real_time = Some(real_time);
+    }
+
+    /// Returns the queue level of a [`Pcb`].
+    pub fn level(&self) -> usize {
+        self.level
+    }
+
+    /// Returns the time a [`Pcb`] has used at its current queue level.
+    pub fn level_time(&self) -> usize {
+        self.level_time
+    }
+
+    /// Moves a [`Pcb`] to a queue level, resetting the time used at the level.
+    pub fn set_level(&mut self, level: usize) {
+        self.level = level;
+        self.level_time = 0;
+    }
+
+    /// Increments the time a [`Pcb`] has used at its current queue level.
+    pub fn increment_level_time(&mut self, time: usize) {
+        self.level_time += time;
+    }
+
+    /// Returns the prediction of the CPU bursts of a [`Pcb`].
+    pub fn burst(&self) -> Option<&Burst> {
+        self.burst.as_ref()
+    }
+
+    /// Returns a mutable reference to the prediction of the CPU bursts of a [`Pcb`].
+    pub fn burst_mut(&mut self) -> Option<&mut Burst> {
+        self.burst.as_mut()
+    }
+
+    /// Sets the prediction of the CPU bursts of a [`Pcb`].
+    pub fn set_burst(&mut self, burst: Burst) {
+        self.burst = Some(burst);
+    }
+
+    /// Sets the PID of the process that has forked a [`Pcb`].
+    pub fn set_parent(&mut self, parent: Option<Pid>) {
+        self.parent = parent;
+    }
+
+    /// Sets the exit status of a [`Pcb`].
+    pub fn set_exit_status(&mut self, status: i32) {
+        self.exit_status = Some(status);
+    }
+
+    /// Returns the priority of a [`Pcb`] without the inherited priority.
+    pub fn base_priority(&self) -> i8 {
+        self.priority
+    }
+
+    /// Returns the priority inherited by a [`Pcb`].
+    pub fn inherited_priority(&self) -> Option<i8> {
+        self.inherited_priority
+    }
+
+    /// Sets the priority inherited by a [`Pcb`], [`None`] to stop inheriting a priority.
+    pub fn set_inherited_priority(&mut self, inherited_priority: Option<i8>) {
+        self.inherited_priority = inherited_priority;
+    }
+
     /// Increments the timings of a [`Pcb`] by the specified values.
     /// ### Parameters
     /// * total_time: Increments the [`Pcb`]'s total time by this value;
@@ -119,12 +363,23 @@ impl Process for Pcb {
     }
 
     fn priority(&self) -> i8 {
-        self.priority
+        match self.inherited_priority {
+            Some(inherited_priority) => self.priority.max(inherited_priority),
+            None => self.priority
+        }
     }
 
     fn extra(&self) -> String {
         self.extra.clone()
     }
+
+    fn exit_status(&self) -> Option<i32> {
+        self.exit_status
+    }
+
+    fn ppid(&self) -> Option<Pid> {
+        self.parent
+    }
 }
 
 impl Add<usize> for Pcb {
@@ -142,7 +397,7 @@ impl AddAssign<usize> for Pcb {
 
 impl PartialEq for Pcb {
     fn eq(&self, other: &Self) -> bool {
-        self.vruntime.eq(&other.vruntime)
+        self.cmp(other).is_eq()
     }
 }
 
@@ -157,9 +412,6 @@ impl Eq for Pcb {
 
 impl Ord for Pcb {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        if self.eq(other) {
-            return self.pid.cmp(&other.pid);
-        }
-        self.vruntime.cmp(&other.vruntime)
+        self.vruntime.cmp(&other.vruntime).then(self.pid.cmp(&other.pid))
     }
 }
@@ -0,0 +1,30 @@
+use super::pcb::{Pcb, RealTime};
+use super::real_time::Policy;
+
+/// The rate monotonic policy of a [`super::RealTimeScheduler`].
+///
+/// Each real-time process has a fixed priority given by its period, the ready process
+/// with the shortest period being scheduled first. The timeslice of a process ends when
+/// a sleeping process with a shorter period wakes up, so that it can preempt the running
+/// process. Aperiodic processes use their relative deadline instead of the period.
+pub const RATE_MONOTONIC: Policy = Policy {
+    key: period,
+    extra,
+    preemptive: true
+};
+
+/// Returns the period that gives the priority of a process,
+/// [`usize::MAX`] for processes without a deadline.
+/// A process with a shorter period has a higher priority.
+fn period(process: &Pcb) -> usize {
+    match process.real_time() {
+        Some(real_time) if real_time.period() != 0 => real_time.period(),
+        Some(real_time) => real_time.deadline(),
+        None => usize::MAX
+    }
+}
+
+/// Shows the period, the deadline and the deadline misses of a process.
+fn extra(process: &Pcb, real_time: &RealTime) -> String {
+    format!("period={} {}", period(process), real_time)
+}
@@ -0,0 +1,462 @@
+use std::{num::NonZeroUsize, process::exit};
+use crate::{Blocker, Scheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
+use super::pcb::{Pcb, RealTime, WakeupCondition};
+use super::exited::ExitedProcesses;
+use super::sync::{Outcome, Shared, SharedSyscalls, SyncObjects};
+
+/// The policy of a [`RealTimeScheduler`], which gives the order of the ready processes.
+#[derive(Clone, Copy)]
+pub struct Policy {
+    /// Returns the key of a process, the ready process with the smallest key being scheduled first.
+    /// Processes without a deadline have the key [`usize::MAX`].
+    pub key: fn(&Pcb) -> usize,
+    /// Returns the deadline information that a real-time process shows in its extra details.
+    pub extra: fn(&Pcb, &RealTime) -> String,
+    /// Whether a sleeping process with a smaller key preempts the running process when it wakes up.
+    pub preemptive: bool
+}
+
+impl Policy {
+
+    /// Shows the deadline information of a process in its extra details.
+    fn update_extra(&self, process: &mut Pcb) {
+        if let Some(real_time) = process.real_time() {
+            let extra = (self.extra)(process, real_time);
+            process.set_extra(extra);
+        }
+    }
+}
+
+/// Data structure that implements a real-time scheduler with a ready queue ordered by a key.
+///
+/// Processes forked with [`Syscall::ForkDeadline`] run as a sequence of jobs, a job
+/// finishing when the process sleeps, waits for an event or exits. The ready process
+/// with the smallest key, given by the [`Policy`], is scheduled first. Processes
+/// without a deadline are scheduled in round robin order after all the real-time ones.
+pub struct RealTimeScheduler {
+    /// The process running on the processor.
+    running_process: Option<Pcb>,
+    /// Intermediate state a process is in during syscalls.
+    stopped_process: Option<Pcb>,
+    /// The remaining execution time for the scheduled process.
+    remaining_time: usize,
+    /// The list of all processes ready to be scheduled.
+    ready_processes: Vec<Pcb>,
+    /// The list of all processes waiting for an event or sleeping.
+    waiting_processes: Vec<Pcb>,
+    /// The maximum amount of time a ready process gets on the processor.
+    timeslice: NonZeroUsize,
+    /// The minimum required time on the processor the stopped process must have remaining
+    /// for it to be scheduled imediately after the syscall that stopped it.
+    minimum_remaining_timeslice: usize,
+    /// The highest pid given to a process.
+    highest_pid: usize,
+    /// The processes that have exited and have not been joined by their parents yet.
+    exited_processes: ExitedProcesses,
+    /// The locks and the semaphores that the processes use to synchronise.
+    sync_objects: SyncObjects,
+    /// The amount of time the processor needs to sleep for a process to wake up
+    /// if there are no ready processes to schedule.
+    /// Is `0` if there are ready processes.
+    sleep_time: usize,
+    /// The current time of the processor.
+    time: usize,
+    /// The policy that orders the ready processes.
+    policy: Policy
+}
+
+impl RealTimeScheduler {
+
+    /// Creates a new [`RealTimeScheduler`] with the given policy.
+    pub fn new(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize, policy: Policy) -> Self {
+        Self { running_process: None,
+            stopped_process: None,
+            remaining_time: 0,
+            ready_processes: Vec::<Pcb>::new(),
+            waiting_processes: Vec::<Pcb>::new(),
+            timeslice,
+            minimum_remaining_timeslice,
+            highest_pid: 0,
+            exited_processes: ExitedProcesses::new(),
+            sync_objects: SyncObjects::new(),
+            sleep_time: 0,
+            time: 0,
+            policy
+        }
+    }
+
+    /// Records the deadline misses of the jobs that have not finished yet.
+    fn check_deadlines(&mut self) {
+        let time = self.time;
+        let policy = self.policy;
+        for process in self.ready_processes.iter_mut().chain(self.stopped_process.iter_mut()) {
+            if let Some(real_time) = process.real_time_mut() {
+                real_time.check_deadline(time);
+            }
+            policy.update_extra(process);
+        }
+    }
+
+    /// Increments the timings for all processes.
+    fn increment_timings(&mut self, _reason: &StopReason) {
+        let time = match _reason {
+            StopReason::Expired => self.remaining_time,
+            StopReason::Syscall { syscall: _, remaining } => self.remaining_time - *remaining
+        };
+        self.time += time;
+
+        if let Some(stopped_process) = &mut self.stopped_process {
+            match _reason {
+                StopReason::Syscall { syscall: _, remaining: _ } => stopped_process.increment_timings(time, 1, time - 1),
+                StopReason::Expired => stopped_process.increment_timings(time, 0, time)
+            }
+        }
+
+        for process in self.ready_processes.iter_mut() {
+            process.increment_timings(time, 0, 0);
+        }
+
+        for process in self.waiting_processes.iter_mut() {
+            process.increment_timings(time, 0, 0);
+            if let WakeupCondition::Sleep(sleep_time) = process.wakeup() {
+                match sleep_time.checked_sub(time).filter(|remaining_time| *remaining_time != 0) {
+                    Some(remaining_time) =>
+                        process.set_wakeup(WakeupCondition::Sleep(remaining_time)),
+                    None => {
+                        process.set_wakeup(WakeupCondition::None);
+                        process.set_state(ProcessState::Ready);
+                    }
+                }
+            }
+        }
+
+        self.check_deadlines();
+    }
+
+    /// Moves processes that have waked up into the list of ready processes,
+    /// releasing a new job for the real-time ones whose job has ended.
+    fn wakeup_processes(&mut self) {
+        let mut still_waiting_processes = Vec::<Pcb>::new();
+        for mut process in self.waiting_processes.drain(..) {
+            if matches!(process.state(), ProcessState::Ready) {
+                // a process blocked on a lock, a semaphore or a child goes on with its job
+                if let Some(real_time) = process.real_time_mut().filter(|real_time| real_time.ended()) {
+                    real_time.release(self.time);
+                }
+                self.policy.update_extra(&mut process);
+                self.ready_processes.push(process);
+            } else {
+                still_waiting_processes.push(process);
+            }
+        }
+        self.waiting_processes = still_waiting_processes;
+    }
+
+    /// Sleeps for the amount of time needed for a process to become ready for scheduling.
+    fn sleep(&mut self) {
+        self.time += self.sleep_time;
+        for process in self.waiting_processes.iter_mut() {
+            process.increment_timings(self.sleep_time, 0, 0);
+            if let WakeupCondition::Sleep(wakeup_time) = process.wakeup() {
+                match wakeup_time.checked_sub(self.sleep_time).filter(|remaining_time| *remaining_time != 0) {
+                    Some(remaining_time) =>
+                        process.set_wakeup(WakeupCondition::Sleep(remaining_time)),
+                    None => {
+                        process.set_wakeup(WakeupCondition::None);
+                        process.set_state(ProcessState::Ready);
+                    }
+                }
+            }
+        }
+        self.sleep_time = 0;
+        self.wakeup_processes();
+    }
+
+    /// Forks a new process with the given priority and, for real-time processes,
+    /// the relative deadline and period of its jobs.
+    fn new_process(&mut self, priority: i8, real_time: Option<(usize, usize)>, parent: Option<Pid>) {
+        self.highest_pid += 1;
+        let mut process = Pcb::new(Pid::new(self.highest_pid), priority, 0);
+        process.set_parent(parent);
+        if let Some((deadline, period)) = real_time {
+            process.set_real_time(RealTime::new(deadline, period, self.time));
+            self.policy.update_extra(&mut process);
+        }
+        self.ready_processes.push(process);
+    }
+
+    /// Sets a process into the ready state.
+    fn set_ready(&mut self, mut process: Pcb) {
+        process.set_state(ProcessState::Ready);
+        process.set_wakeup(WakeupCondition::None);
+        self.ready_processes.push(process);
+        self.remaining_time = 0;
+    }
+
+    /// Sets a process to into the running state.
+    fn set_running(&mut self, mut process: Pcb) {
+        process.set_state(ProcessState::Running);
+        self.running_process = Some(process);
+        self.remaining_time = self.timeslice.get();
+    }
+
+    /// Returns `true` if there are no more processes, `false` otherwise.
+    fn is_done(&self) -> bool {
+        self.running_process.is_none() && self.ready_processes.is_empty() && self.waiting_processes.is_empty()
+    }
+
+    /// Returns `true` if the process with pid 1 exists, `false` otherwise.
+    fn pid_1_exists(&self) -> bool {
+        self.get_all_processes().iter().any(|element| element.pid() == 1)
+    }
+
+    /// Returns the ready process with the smallest key, processes with the same
+    /// key being scheduled in the order they became ready.
+    fn scheduled_process(&mut self) -> Option<Pcb> {
+        let key = self.policy.key;
+        let index = self.ready_processes.iter().enumerate()
+            .min_by_key(|(_, element)| key(element))
+            .map(|(index, _)| index)?;
+        Some(self.ready_processes.remove(index))
+    }
+
+    /// Returns the minimal amount of time the processor needs to sleep
+    /// for a process to become ready for scheduling.
+    fn find_sleep_time(&self) -> Option<usize> {
+        self.waiting_processes.iter().filter_map(|element|
+            match element.wakeup() {WakeupCondition::Sleep(sleep_time) => Some(sleep_time), _ => None})
+            .min()
+    }
+
+    /// Returns the amount of time until a sleeping process with a key smaller than `key`
+    /// wakes up. The running process is preempted at that time.
+    fn find_preemption_time(&self, key: usize) -> Option<usize> {
+        self.waiting_processes.iter()
+            .filter(|element| (self.policy.key)(element) < key)
+            .filter_map(|element|
+                match element.wakeup() {WakeupCondition::Sleep(sleep_time) if sleep_time != 0 => Some(sleep_time), _ => None})
+            .min()
+    }
+
+    /// Return an vector of refrences to all processes.
+    fn get_all_processes(&self) -> Vec<&Pcb> {
+        let mut processes = Vec::<&Pcb>::new();
+        processes.extend(self.ready_processes.iter());
+        processes.extend(self.waiting_processes.iter());
+        if let Some(running_process) = &self.running_process {
+            processes.push(running_process);
+        }
+        processes
+    }
+
+    /// Schedules the stopped process again if it has enough time left from its quanta
+    /// and no ready process has a smaller key, or puts it back in the ready queue otherwise.
+    fn resume(&mut self, remaining_time: usize) {
+        match self.stopped_process.take() {
+            Some(mut stopped_process) => {
+                let key = (self.policy.key)(&stopped_process);
+                if remaining_time >= self.minimum_remaining_timeslice
+                    && self.ready_processes.iter().all(|element| (self.policy.key)(element) >= key) {
+                    stopped_process.set_state(ProcessState::Running);
+                    self.running_process = Some(stopped_process);
+                    self.remaining_time = remaining_time;
+                } else {
+                    self.set_ready(stopped_process)
+                }
+            },
+            None => {
+                self.remaining_time = 0;
+            }
+        }
+    }
+
+    /// Handles syscalls recievied from the running process.
+    fn syscall_handler(&mut self, syscall: Syscall, remaining_time: usize) -> SyscallResult {
+        match syscall {
+            Syscall::Fork(priority) | Syscall::ForkBurst { priority, burst: _ } => {
+                self.new_process(priority, None, self.stopped_process.as_ref().map(|process| process.pid()));
+                self.wakeup_processes();
+                self.resume(remaining_time);
+                return SyscallResult::Pid(Pid::new(self.highest_pid));
+            }
+            Syscall::ForkDeadline { priority, deadline, period } => {
+                self.new_process(priority, Some((deadline, period)), self.stopped_process.as_ref().map(|process| process.pid()));
+                self.wakeup_processes();
+                self.resume(remaining_time);
+                return SyscallResult::Pid(Pid::new(self.highest_pid));
+            }
+            Syscall::Signal(event) => {
+                for process in self.waiting_processes.iter_mut()
+                    .filter(|element| matches!(element.wakeup(), WakeupCondition::Signal(x) if x == event)) {
+                    process.set_state(ProcessState::Ready);
+                    process.set_wakeup(WakeupCondition::None);
+                }
+
+                self.wakeup_processes();
+                self.resume(remaining_time);
+            },
+            Syscall::Sleep(sleep_time) => {
+                match self.stopped_process.take() {
+                    Some(mut stopped_process) => {
+                        if let Some(real_time) = stopped_process.real_time_mut() {
+                            real_time.end();
+                        }
+                        stopped_process.set_state(ProcessState::Waiting { event: None });
+                        stopped_process.set_wakeup(WakeupCondition::Sleep(sleep_time));
+                        self.waiting_processes.push(stopped_process);
+                    },
+                    None => return SyscallResult::NoRunningProcess
+                }
+            },
+            Syscall::Wait(event) => {
+                match self.stopped_process.take() {
+                    Some(mut stopped_process) => {
+                        if let Some(real_time) = stopped_process.real_time_mut() {
+                            real_time.end();
+                        }
+                        stopped_process.set_state(ProcessState::Waiting { event: Some(event) });
+                        stopped_process.set_wakeup(WakeupCondition::Signal(event));
+                        self.waiting_processes.push(stopped_process);
+                    },
+                    None => return SyscallResult::NoRunningProcess
+                }
+            },
+            Syscall::Exit(status) => {
+                if let Some(stopped_process) = self.stopped_process.take() {
+                    self.shared().exit(stopped_process, status);
+                }
+                self.wakeup_processes();
+            },
+            syscall => {
+                let mut stopped_process = self.stopped_process.take();
+                let outcome = self.shared_syscall(syscall, &mut stopped_process);
+                self.stopped_process = stopped_process;
+                return match outcome {
+                    Outcome::Blocked => {
+                        self.wakeup_processes();
+                        SyscallResult::Pending
+                    },
+                    Outcome::Resume { result, wakeup } => {
+                        if wakeup {
+                            self.wakeup_processes();
+                        }
+                        self.resume(remaining_time);
+                        result
+                    },
+                    Outcome::NoRunningProcess => SyscallResult::NoRunningProcess
+                };
+            }
+        };
+
+        SyscallResult::Success
+    }
+
+}
+
+
+impl SharedSyscalls for RealTimeScheduler {
+    fn shared(&mut self) -> Shared<'_> {
+        Shared {
+            waiting_processes: &mut self.waiting_processes,
+            exited_processes: &mut self.exited_processes,
+            sync_objects: &mut self.sync_objects
+        }
+    }
+
+    fn processes(&self) -> Vec<&Pcb> {
+        self.get_all_processes()
+    }
+
+    fn remove_process(&mut self, pid: Pid) -> Option<Pcb> {
+        if let Some(index) = self.ready_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.ready_processes.remove(index));
+        }
+        if let Some(index) = self.waiting_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.waiting_processes.remove(index));
+        }
+        None
+    }
+}
+
+impl Scheduler for RealTimeScheduler {
+    fn next(&mut self) -> SchedulingDecision {
+        if self.sleep_time != 0 {
+            self.sleep();
+        }
+
+        if self.is_done() {
+            return SchedulingDecision::Done;
+        }
+
+        if !self.pid_1_exists() {
+            return SchedulingDecision::Panic;
+        }
+
+        if self.running_process.is_none() {
+            if let Some(scheduled_process) = self.scheduled_process() {
+                self.set_running(scheduled_process);
+            }
+        }
+
+        if let Some(scheduled_process) = &self.running_process {
+            let pid = scheduled_process.pid();
+            if self.policy.preemptive {
+                if let Some(wakeup_time) = self.find_preemption_time((self.policy.key)(scheduled_process)) {
+                    self.remaining_time = self.remaining_time.min(wakeup_time);
+                }
+            }
+            return SchedulingDecision::Run { pid, timeslice:
+                match NonZeroUsize::new(self.remaining_time) {Some(time) => time, None => exit(-1)}};
+        }
+
+        match self.find_sleep_time() {
+            Some(sleep_time) => {
+                self.sleep_time = sleep_time;
+                SchedulingDecision::Sleep(match NonZeroUsize::new(sleep_time)
+                    {Some(sleep_time) => sleep_time, None => exit(-1)})
+            },
+            None => SchedulingDecision::Deadlock
+        }
+    }
+
+    fn stop(&mut self, _reason: StopReason) -> SyscallResult {
+        self.stopped_process = self.running_process.take();
+
+        self.increment_timings(&_reason);
+
+        match _reason {
+            StopReason::Expired => {
+                self.wakeup_processes();
+                match self.stopped_process.take() {
+                    Some(stopped_process) => {
+                        self.set_ready(stopped_process);
+                        SyscallResult::Success
+                    },
+                    None => {
+                        SyscallResult::NoRunningProcess
+                    }
+                }
+            },
+            StopReason::Syscall{ syscall, remaining } => {
+                self.syscall_handler(syscall, remaining)
+            }
+        }
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        let mut processes = self.get_all_processes();
+        processes.extend(self.exited_processes.zombies());
+
+        processes.sort_by_key(|element|  element.pid());
+
+        processes.into_iter().map(|element| element as &dyn Process).collect()
+    }
+
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        self.shared_completions()
+    }
+
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        self.sync_objects.blockers(&self.waiting_processes)
+    }
+}
@@ -1,6 +1,8 @@
 use std::{num::NonZeroUsize, process::exit};
-use crate::{Scheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
+use crate::{Blocker, Scheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
 use super::pcb::{Pcb, WakeupCondition};
+use super::exited::ExitedProcesses;
+use super::sync::{Outcome, Shared, SharedSyscalls, SyncObjects};
 
 /// Data structure that implements a round robin scheduler.
 pub struct RoundRobinScheduler {
@@ -21,6 +23,10 @@ pub struct RoundRobinScheduler {
     minimum_remaining_timeslice: usize,
     /// The highest pid given to a process.
     highest_pid: usize,
+    /// The processes that have exited and have not been joined by their parents yet.
+    exited_processes: ExitedProcesses,
+    /// The locks and the semaphores that the processes use to synchronise.
+    sync_objects: SyncObjects,
     /// The amount of time the processor needs to sleep for a process to wake up if there are no ready processes to schedule.
     /// Is `0` if there are ready processes.
     sleep_time: usize
@@ -38,6 +44,8 @@ impl RoundRobinScheduler {
             timeslice,
             minimum_remaining_timeslice,
             highest_pid: 0,
+            exited_processes: ExitedProcesses::new(),
+            sync_objects: SyncObjects::new(),
             sleep_time: 0
         }
     }
@@ -108,9 +116,11 @@ impl RoundRobinScheduler {
     }
 
     /// Forks a new process with the given priority.
-    fn new_process(&mut self, priority: i8) {
+    fn new_process(&mut self, priority: i8, parent: Option<Pid>) {
         self.highest_pid += 1;
-        self.ready_processes.push(Pcb::new(Pid::new(self.highest_pid), priority, 0));
+        let mut process = Pcb::new(Pid::new(self.highest_pid), priority, 0);
+        process.set_parent(parent);
+        self.ready_processes.push(process);
     }
     
     /// Sets a process into the ready state.
@@ -185,11 +195,31 @@ impl RoundRobinScheduler {
         processes
     }
 
+    /// Schedules the stopped process again if it has enough time left from its quanta,
+    /// or puts it back in the ready queue otherwise.
+    fn resume(&mut self, remaining_time: usize) {
+        match self.stopped_process.take() {
+            Some(mut stopped_process) => {
+                if remaining_time >= self.minimum_remaining_timeslice {
+                    stopped_process.set_state(ProcessState::Running);
+                    self.running_process = Some(stopped_process);
+                    self.remaining_time = remaining_time;
+                } else {
+                    self.set_ready(stopped_process)
+                }
+            },
+            None => {
+                self.remaining_time = 0;
+            }
+        }
+    }
+
     /// Handles syscalls recievied from the running process.
     fn syscall_handler(&mut self, syscall: Syscall, remaining_time: usize) -> SyscallResult {
         match syscall {
-            Syscall::Fork(priority) => {
-                self.new_process(priority);
+            Syscall::Fork(priority) | Syscall::ForkDeadline { priority, deadline: _, period: _ }
+            | Syscall::ForkBurst { priority, burst: _ } => {
+                self.new_process(priority, self.stopped_process.as_ref().map(|process| process.pid()));
 
                 self.wakeup_processes();
                 match self.stopped_process.take() {
@@ -252,7 +282,31 @@ impl RoundRobinScheduler {
                     None => return SyscallResult::NoRunningProcess
                 }
             },
-            Syscall::Exit => self.wakeup_processes(),
+            Syscall::Exit(status) => {
+                if let Some(stopped_process) = self.stopped_process.take() {
+                    self.shared().exit(stopped_process, status);
+                }
+                self.wakeup_processes();
+            },
+            syscall => {
+                let mut stopped_process = self.stopped_process.take();
+                let outcome = self.shared_syscall(syscall, &mut stopped_process);
+                self.stopped_process = stopped_process;
+                return match outcome {
+                    Outcome::Blocked => {
+                        self.wakeup_processes();
+                        SyscallResult::Pending
+                    },
+                    Outcome::Resume { result, wakeup } => {
+                        if wakeup {
+                            self.wakeup_processes();
+                        }
+                        self.resume(remaining_time);
+                        result
+                    },
+                    Outcome::NoRunningProcess => SyscallResult::NoRunningProcess
+                };
+            }
         };
         
         SyscallResult::Success
@@ -261,6 +315,30 @@ impl RoundRobinScheduler {
 }
 
 
+impl SharedSyscalls for RoundRobinScheduler {
+    fn shared(&mut self) -> Shared<'_> {
+        Shared {
+            waiting_processes: &mut self.waiting_processes,
+            exited_processes: &mut self.exited_processes,
+            sync_objects: &mut self.sync_objects
+        }
+    }
+
+    fn processes(&self) -> Vec<&Pcb> {
+        self.get_all_processes()
+    }
+
+    fn remove_process(&mut self, pid: Pid) -> Option<Pcb> {
+        if let Some(index) = self.ready_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.ready_processes.remove(index));
+        }
+        if let Some(index) = self.waiting_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.waiting_processes.remove(index));
+        }
+        None
+    }
+}
+
 impl Scheduler for RoundRobinScheduler {
     fn next(&mut self) -> SchedulingDecision {
         if self.sleep_time != 0 {
@@ -325,9 +403,18 @@ impl Scheduler for RoundRobinScheduler {
 
     fn list(&mut self) -> Vec<&dyn Process> {
         let mut processes = self.get_all_processes();
+        processes.extend(self.exited_processes.zombies());
 
         processes.sort_by_key(|element|  element.pid());
 
         processes.into_iter().map(|element| element as &dyn Process).collect()
     }
+
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        self.shared_completions()
+    }
+
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        self.sync_objects.blockers(&self.waiting_processes)
+    }
 }
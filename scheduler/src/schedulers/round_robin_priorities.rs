@@ -1,6 +1,8 @@
-use std::{num::NonZeroUsize, process::exit};
-use crate::{Scheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
+use std::{mem::take, num::NonZeroUsize, process::exit};
+use crate::{Blocker, Scheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
 use super::pcb::{Pcb, WakeupCondition};
+use super::exited::ExitedProcesses;
+use super::sync::{Outcome, Shared, SharedSyscalls, SyncObjects};
 
 /// A macro for turning an integer into [usize].
 macro_rules! usize_from {
@@ -31,6 +33,14 @@ pub struct RoundRobinPrioritiesScheduler {
     minimum_remaining_timeslice: usize,
     /// The highest pid given to a process.
     highest_pid: usize,
+    /// The processes that have exited and have not been joined by their parents yet.
+    exited_processes: ExitedProcesses,
+    /// The locks and the semaphores that the processes use to synchronise.
+    sync_objects: SyncObjects,
+    /// Whether the processes that hold locks inherit the priorities of the processes blocked on them.
+    priority_inheritance: bool,
+    /// The events that have not been reported yet.
+    events: Vec<String>,
     /// The amount of time the processor needs to sleep for a process to wake up if there are no ready processes to schedule.
     /// Is `0` if there are ready processes.
     sleep_time: usize
@@ -48,10 +58,23 @@ impl RoundRobinPrioritiesScheduler {
             timeslice,
             minimum_remaining_timeslice,
             highest_pid: 0,
+            exited_processes: ExitedProcesses::new(),
+            sync_objects: SyncObjects::new(),
+            priority_inheritance: false,
+            events: Vec::<String>::new(),
             sleep_time: 0
         }
     }
 
+    /// Creates a new [`RoundRobinPrioritiesScheduler`] that uses the priority inheritance protocol.
+    ///
+    /// A process that holds a lock runs with the highest priority of the processes blocked on it,
+    /// until it releases the lock. Its own priority is shown in its extra details while it inherits
+    /// a higher one.
+    pub fn with_priority_inheritance(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> Self {
+        Self { priority_inheritance: true, ..RoundRobinPrioritiesScheduler::new(timeslice, minimum_remaining_timeslice) }
+    }
+
     /// Moves processes that have waked up into the list of ready processes.
     fn wakeup_processes(&mut self) {
         let mut still_waiting_processes = Vec::<Pcb>::new();
@@ -122,19 +145,42 @@ impl RoundRobinPrioritiesScheduler {
     }
 
     /// Forks a new process with the given priority.
-    fn new_process(&mut self, priority: i8) {
+    fn new_process(&mut self, priority: i8, parent: Option<Pid>) {
         self.highest_pid += 1;
+        let mut process = Pcb::new(Pid::new(self.highest_pid), priority, 0);
+        process.set_parent(parent);
         match self.ready_processes.get_mut(usize_from!(priority)) {
-            Some(process_queue) => process_queue.push(Pcb::new(Pid::new(self.highest_pid), priority, 0)),
+            Some(process_queue) => process_queue.push(process),
             None => exit(-1)
         }
     }
 
+    /// Schedules the stopped process again if it has enough time left from its quanta,
+    /// or puts it back in the ready queue otherwise.
+    fn resume(&mut self, remaining_time: usize) {
+        match self.stopped_process.take() {
+            Some(mut stopped_process) => {
+                if remaining_time >= self.minimum_remaining_timeslice {
+                    stopped_process.set_state(ProcessState::Running);
+                    self.running_process = Some(stopped_process);
+                    self.remaining_time = remaining_time;
+                } else {
+                    stopped_process.increment_priority();
+                    self.set_ready(stopped_process)
+                }
+            },
+            None => {
+                self.remaining_time = 0;
+            }
+        }
+    }
+
     /// Handles syscalls recievied from the running process.
     fn syscall_handler(&mut self, syscall: Syscall, remaining_time: usize) -> SyscallResult {
         match syscall {
-            Syscall::Fork(priority) => {
-                self.new_process(priority);
+            Syscall::Fork(priority) | Syscall::ForkDeadline { priority, deadline: _, period: _ }
+            | Syscall::ForkBurst { priority, burst: _ } => {
+                self.new_process(priority, self.stopped_process.as_ref().map(|process| process.pid()));
 
                 self.wakeup_processes();
                 match self.stopped_process.take() {
@@ -201,12 +247,91 @@ impl RoundRobinPrioritiesScheduler {
                     None => return SyscallResult::NoRunningProcess
                 }
             },
-            Syscall::Exit => self.wakeup_processes(),
+            Syscall::Exit(status) => {
+                if let Some(stopped_process) = self.stopped_process.take() {
+                    self.shared().exit(stopped_process, status);
+                }
+                self.wakeup_processes();
+            },
+            syscall => {
+                let mut stopped_process = self.stopped_process.take();
+                let outcome = self.shared_syscall(syscall, &mut stopped_process);
+                self.stopped_process = stopped_process;
+                return match outcome {
+                    Outcome::Blocked => {
+                        self.wakeup_processes();
+                        SyscallResult::Pending
+                    },
+                    Outcome::Resume { result, wakeup } => {
+                        if wakeup {
+                            self.wakeup_processes();
+                        }
+                        self.resume(remaining_time);
+                        result
+                    },
+                    Outcome::NoRunningProcess => SyscallResult::NoRunningProcess
+                };
+            }
         };
         
         SyscallResult::Success
     }
 
+    /// Sets the priorities inherited by the processes from the processes blocked on the locks that they hold
+    /// and moves the ready processes whose priorities have changed into the queues of their new priorities.
+    fn inherit_priorities(&mut self) {
+        if !self.priority_inheritance {
+            return;
+        }
+
+        // the inherited priorities are propagated along the chains of blocked processes
+        for _ in 0..self.highest_pid {
+            let inherited_priorities = self.get_all_processes().into_iter()
+                .map(|element| self.sync_objects.inherited_priority(element.pid(), self.waiting_processes.iter()))
+                .collect::<Vec<Option<i8>>>();
+            let mut changed = false;
+            let processes = self.ready_processes.iter_mut().flatten()
+                .chain(self.waiting_processes.iter_mut())
+                .chain(self.running_process.iter_mut());
+            for (process, inherited_priority) in processes.zip(inherited_priorities) {
+                let inherited_priority = inherited_priority.filter(|priority| *priority > process.base_priority());
+                if process.inherited_priority() != inherited_priority {
+                    process.set_inherited_priority(inherited_priority);
+                    process.set_extra(match inherited_priority {
+                        Some(_) => format!("base={}", process.base_priority()),
+                        None => String::from("")
+                    });
+                    changed = true;
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+
+        let mut moved_processes = Vec::<Pcb>::new();
+        for (priority, process_queue) in self.ready_processes.iter_mut().enumerate() {
+            let (staying_processes, leaving_processes): (Vec<Pcb>, Vec<Pcb>) = take(process_queue).into_iter()
+                .partition(|element| usize_from!(element.priority()) == priority);
+            *process_queue = staying_processes;
+            moved_processes.extend(leaving_processes);
+        }
+        for process in moved_processes {
+            match self.ready_processes.get_mut(usize_from!(process.priority())) {
+                Some(process_queue) => process_queue.push(process),
+                None => exit(-1)
+            }
+        }
+    }
+
+    /// Reports the priority inversions that take place while the running process runs.
+    fn detect_inversions(&mut self) {
+        if let Some(running_process) = &self.running_process {
+            let inversions = self.sync_objects.inversions(running_process, &self.get_all_processes());
+            self.events.extend(inversions);
+        }
+    }
+
     /// Returns `true` if there are no more processes, `false` otherwise.
     fn is_done(&self) -> bool {
         self.running_process.is_none()
@@ -233,7 +358,7 @@ impl RoundRobinPrioritiesScheduler {
 
     /// Returns the process scheduled to be run.
     fn scheduled_process(&mut self) -> Option<Pcb> {
-        if let Some(process_queue) = self.ready_processes.iter_mut().filter(|queue| !queue.is_empty()).next_back() {
+        if let Some(process_queue) = self.ready_processes.iter_mut().rfind(|queue| !queue.is_empty()) {
             return Some(process_queue.remove(0));
         }
         None
@@ -287,6 +412,32 @@ impl RoundRobinPrioritiesScheduler {
 }
 
 
+impl SharedSyscalls for RoundRobinPrioritiesScheduler {
+    fn shared(&mut self) -> Shared<'_> {
+        Shared {
+            waiting_processes: &mut self.waiting_processes,
+            exited_processes: &mut self.exited_processes,
+            sync_objects: &mut self.sync_objects
+        }
+    }
+
+    fn processes(&self) -> Vec<&Pcb> {
+        self.get_all_processes()
+    }
+
+    fn remove_process(&mut self, pid: Pid) -> Option<Pcb> {
+        for process_queue in self.ready_processes.iter_mut() {
+            if let Some(index) = process_queue.iter().position(|element| element.pid() == pid) {
+                return Some(process_queue.remove(index));
+            }
+        }
+        if let Some(index) = self.waiting_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.waiting_processes.remove(index));
+        }
+        None
+    }
+}
+
 impl Scheduler for RoundRobinPrioritiesScheduler {
     fn next(&mut self) -> SchedulingDecision {
         if self.sleep_time != 0 {
@@ -301,6 +452,9 @@ impl Scheduler for RoundRobinPrioritiesScheduler {
             return SchedulingDecision::Panic;
         }
 
+        self.inherit_priorities();
+        self.detect_inversions();
+
         if let Some(scheduled_process) = &mut self.running_process {
             return SchedulingDecision::Run { pid: scheduled_process.pid(), timeslice:
                 match NonZeroUsize::new(self.remaining_time) {Some(time) => time, None => exit(-1)}};
@@ -308,6 +462,7 @@ impl Scheduler for RoundRobinPrioritiesScheduler {
 
         if let Some(scheduled_process) = self.scheduled_process() {
             self.set_running(scheduled_process);
+            self.detect_inversions();
             return SchedulingDecision::Run { pid: match &self.running_process {Some(process) => process.pid(), None => exit(-1)},
             timeslice: self.timeslice };
         }
@@ -352,9 +507,22 @@ impl Scheduler for RoundRobinPrioritiesScheduler {
 
     fn list(&mut self) -> Vec<&dyn Process> {
         let mut processes = self.get_all_processes();
+        processes.extend(self.exited_processes.zombies());
 
         processes.sort_by_key(|element|  element.pid());
 
         processes.into_iter().map(|element| element as &dyn Process).collect()
     }
+
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        self.shared_completions()
+    }
+
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        self.sync_objects.blockers(&self.waiting_processes)
+    }
+
+    fn events(&mut self) -> Vec<String> {
+        take(&mut self.events)
+    }
 }
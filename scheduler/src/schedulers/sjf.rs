@@ -0,0 +1,450 @@
+use std::{num::NonZeroUsize, process::exit};
+use crate::{Blocker, Scheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
+use super::pcb::{Burst, Pcb, WakeupCondition};
+use super::exited::ExitedProcesses;
+use super::sync::{Outcome, Shared, SharedSyscalls, SyncObjects};
+
+/// Data structure that implements a shortest job first scheduler.
+///
+/// The ready process with the shortest expected CPU burst is scheduled, processes with
+/// the same expected burst being scheduled in the order they became ready. The length of
+/// the bursts is given by the hint of a [`Syscall::ForkBurst`] or, for the other processes,
+/// predicted as the exponential average of their previous bursts.
+///
+/// Without preemption (SJF), a process runs until it sleeps, waits for an event or exits.
+/// With preemption (SRTF), the running process is preempted when another process becomes
+/// ready with an expected burst shorter than the time left from its own.
+pub struct SjfScheduler {
+    /// The process running on the processor.
+    running_process: Option<Pcb>,
+    /// Intermediate state a process is in during syscalls.
+    stopped_process: Option<Pcb>,
+    /// The remaining execution time for the scheduled process.
+    remaining_time: usize,
+    /// The list of all processes ready to be scheduled.
+    ready_processes: Vec<Pcb>,
+    /// The list of all processes waiting for an event or sleeping.
+    waiting_processes: Vec<Pcb>,
+    /// The amount of time a process runs before the scheduler checks whether it should be preempted.
+    timeslice: NonZeroUsize,
+    /// The highest pid given to a process.
+    highest_pid: usize,
+    /// The processes that have exited and have not been joined by their parents yet.
+    exited_processes: ExitedProcesses,
+    /// The locks and the semaphores that the processes use to synchronise.
+    sync_objects: SyncObjects,
+    /// The amount of time the processor needs to sleep for a process to wake up if there are no ready processes to schedule.
+    /// Is `0` if there are ready processes.
+    sleep_time: usize,
+    /// The weight of the last burst in the exponential average.
+    alpha: f64,
+    /// The prediction of the first burst of the processes forked without a hint.
+    initial_burst: usize,
+    /// Whether a process is preempted by a process with a shorter burst.
+    preemptive: bool
+}
+
+impl SjfScheduler {
+
+    /// Creates a new non-preemptive [`SjfScheduler`].
+    ///
+    /// Panics if `alpha` is not between `0` and `1`.
+    pub fn new(timeslice: NonZeroUsize, alpha: f64, initial_burst: usize) -> Self {
+        if !(0.0..=1.0).contains(&alpha) {
+            panic!("The alpha of the exponential average has to be between 0 and 1");
+        }
+        Self { running_process: None,
+            stopped_process: None,
+            remaining_time: 0,
+            ready_processes: Vec::<Pcb>::new(),
+            waiting_processes: Vec::<Pcb>::new(),
+            timeslice,
+            highest_pid: 0,
+            exited_processes: ExitedProcesses::new(),
+            sync_objects: SyncObjects::new(),
+            sleep_time: 0,
+            alpha,
+            initial_burst,
+            preemptive: false
+        }
+    }
+
+    /// Creates a new preemptive [`SjfScheduler`], that schedules the process with
+    /// the shortest remaining time first.
+    ///
+    /// Panics if `alpha` is not between `0` and `1`.
+    pub fn preemptive(timeslice: NonZeroUsize, alpha: f64, initial_burst: usize) -> Self {
+        Self { preemptive: true, ..SjfScheduler::new(timeslice, alpha, initial_burst) }
+    }
+
+    /// Returns the expected time left from the current burst of a process.
+    fn remaining_burst(process: &Pcb) -> usize {
+        match process.burst() {
+            Some(burst) => burst.remaining(process.timings().2),
+            None => 0
+        }
+    }
+
+    /// Shows the prediction of the bursts of a process in its extra details.
+    fn update_extra(&self, process: &mut Pcb) {
+        let extra = match process.burst() {
+            Some(burst) if burst.hint().is_some() => burst.to_string(),
+            Some(burst) => format!("{} alpha={}", burst, self.alpha),
+            None => String::new()
+        };
+        process.set_extra(extra);
+    }
+
+    /// Increments the timings for all processes.
+    fn increment_timings(&mut self, _reason: &StopReason) {
+        let time = match _reason {
+            StopReason::Expired => self.remaining_time,
+            StopReason::Syscall { syscall: _, remaining } => self.remaining_time - *remaining
+        };
+
+        if let Some(stopped_process) = &mut self.stopped_process {
+            match _reason {
+                StopReason::Syscall { syscall: _, remaining: _ } => stopped_process.increment_timings(time, 1, time - 1),
+                StopReason::Expired => stopped_process.increment_timings(time, 0, time)
+            }
+        }
+
+        for process in self.ready_processes.iter_mut() {
+            process.increment_timings(time, 0, 0);
+        }
+
+        for process in self.waiting_processes.iter_mut() {
+            process.increment_timings(time, 0, 0);
+            if let WakeupCondition::Sleep(sleep_time) = process.wakeup() {
+                match sleep_time.checked_sub(time).filter(|remaining_time| *remaining_time != 0) {
+                    Some(remaining_time) =>
+                        process.set_wakeup(WakeupCondition::Sleep(remaining_time)),
+                    None => {
+                        process.set_wakeup(WakeupCondition::None);
+                        process.set_state(ProcessState::Ready);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Moves processes that have waked up into the list of ready processes.
+    fn wakeup_processes(&mut self) {
+        let mut still_waiting_processes = Vec::<Pcb>::new();
+        for process in self.waiting_processes.drain(..) {
+            if matches!(process.state(), ProcessState::Ready) {
+                self.ready_processes.push(process);
+            } else {
+                still_waiting_processes.push(process);
+            }
+        }
+        self.waiting_processes = still_waiting_processes;
+    }
+
+    /// Sleeps for the amount of time needed for a process to become ready for scheduling.
+    fn sleep(&mut self) {
+        for process in self.waiting_processes.iter_mut() {
+            process.increment_timings(self.sleep_time, 0, 0);
+            if let WakeupCondition::Sleep(wakeup_time) = process.wakeup() {
+                match wakeup_time.checked_sub(self.sleep_time).filter(|remaining_time| *remaining_time != 0) {
+                    Some(remaining_time) =>
+                        process.set_wakeup(WakeupCondition::Sleep(remaining_time)),
+                    None => {
+                        process.set_wakeup(WakeupCondition::None);
+                        process.set_state(ProcessState::Ready);
+                    }
+                }
+            }
+        }
+        self.sleep_time = 0;
+        self.wakeup_processes();
+    }
+
+    /// Forks a new process with the given priority and, optionally, the hint of the length of its bursts.
+    fn new_process(&mut self, priority: i8, hint: Option<usize>, parent: Option<Pid>) {
+        self.highest_pid += 1;
+        let mut process = Pcb::new(Pid::new(self.highest_pid), priority, 0);
+        process.set_parent(parent);
+        process.set_burst(Burst::new(hint, self.initial_burst));
+        self.update_extra(&mut process);
+        self.ready_processes.push(process);
+    }
+
+    /// Ends the current burst of the stopped process, as it gives up the processor.
+    fn end_burst(&mut self) {
+        let alpha = self.alpha;
+        if let Some(mut stopped_process) = self.stopped_process.take() {
+            let execution = stopped_process.timings().2;
+            if let Some(burst) = stopped_process.burst_mut() {
+                burst.end(execution, alpha);
+            }
+            self.update_extra(&mut stopped_process);
+            self.stopped_process = Some(stopped_process);
+        }
+    }
+
+    /// Sets a process into the ready state.
+    fn set_ready(&mut self, mut process: Pcb) {
+        process.set_state(ProcessState::Ready);
+        process.set_wakeup(WakeupCondition::None);
+        self.ready_processes.push(process);
+        self.remaining_time = 0;
+    }
+
+    /// Sets a process to into the running state.
+    fn set_running(&mut self, mut process: Pcb) {
+        process.set_state(ProcessState::Running);
+        self.running_process = Some(process);
+        self.remaining_time = self.timeslice.get();
+    }
+
+    /// Returns `true` if there are no more processes, `false` otherwise.
+    fn is_done(&self) -> bool {
+        self.running_process.is_none() && self.ready_processes.is_empty() && self.waiting_processes.is_empty()
+    }
+
+    /// Returns `true` if the process with pid 1 exists, `false` otherwise.
+    fn pid_1_exists(&self) -> bool {
+        self.get_all_processes().iter().any(|element| element.pid() == 1)
+    }
+
+    /// Returns the ready process with the shortest expected burst.
+    fn scheduled_process(&mut self) -> Option<Pcb> {
+        let index = self.ready_processes.iter().enumerate()
+            .min_by_key(|(_, element)| SjfScheduler::remaining_burst(element))
+            .map(|(index, _)| index)?;
+        Some(self.ready_processes.remove(index))
+    }
+
+    /// Returns the minimal amount of time the processor needs to sleep for a process to become ready for scheduling.
+    fn find_sleep_time(&self) -> Option<usize> {
+        self.waiting_processes.iter().filter_map(|element|
+            match element.wakeup() {WakeupCondition::Sleep(sleep_time) => Some(sleep_time), _ => None})
+            .min()
+    }
+
+    /// Returns the amount of time until a sleeping process with an expected burst shorter than
+    /// `remaining` wakes up. With preemption, the running process is preempted at that time.
+    fn find_preemption_time(&self, remaining: usize) -> Option<usize> {
+        self.waiting_processes.iter()
+            .filter(|element| SjfScheduler::remaining_burst(element) < remaining)
+            .filter_map(|element|
+                match element.wakeup() {WakeupCondition::Sleep(sleep_time) if sleep_time != 0 => Some(sleep_time), _ => None})
+            .min()
+    }
+
+    /// Return an vector of refrences to all processes.
+    fn get_all_processes(&self) -> Vec<&Pcb> {
+        let mut processes = Vec::<&Pcb>::new();
+        processes.extend(self.ready_processes.iter());
+        processes.extend(self.waiting_processes.iter());
+        if let Some(running_process) = &self.running_process {
+            processes.push(running_process);
+        }
+        processes
+    }
+
+    /// Schedules the stopped process again for the time left from its timeslice, or for
+    /// a new timeslice if none is left. With preemption, the process is put back in the ready
+    /// queue if a ready process has an expected burst shorter than the time left from its own.
+    fn resume(&mut self, remaining_time: usize) {
+        match self.stopped_process.take() {
+            Some(mut stopped_process) => {
+                let remaining_burst = SjfScheduler::remaining_burst(&stopped_process);
+                if self.preemptive
+                    && self.ready_processes.iter().any(|element| SjfScheduler::remaining_burst(element) < remaining_burst) {
+                    self.set_ready(stopped_process);
+                } else {
+                    stopped_process.set_state(ProcessState::Running);
+                    self.running_process = Some(stopped_process);
+                    self.remaining_time = if remaining_time != 0 { remaining_time } else { self.timeslice.get() };
+                }
+            },
+            None => {
+                self.remaining_time = 0;
+            }
+        }
+    }
+
+    /// Handles syscalls recievied from the running process.
+    fn syscall_handler(&mut self, syscall: Syscall, remaining_time: usize) -> SyscallResult {
+        match syscall {
+            Syscall::Fork(priority) | Syscall::ForkDeadline { priority, deadline: _, period: _ } => {
+                self.new_process(priority, None, self.stopped_process.as_ref().map(|process| process.pid()));
+                self.wakeup_processes();
+                self.resume(remaining_time);
+                return SyscallResult::Pid(Pid::new(self.highest_pid));
+            }
+            Syscall::ForkBurst { priority, burst } => {
+                self.new_process(priority, Some(burst), self.stopped_process.as_ref().map(|process| process.pid()));
+                self.wakeup_processes();
+                self.resume(remaining_time);
+                return SyscallResult::Pid(Pid::new(self.highest_pid));
+            }
+            Syscall::Signal(event) => {
+                for process in self.waiting_processes.iter_mut()
+                    .filter(|element| matches!(element.wakeup(), WakeupCondition::Signal(x) if x == event)) {
+                    process.set_state(ProcessState::Ready);
+                    process.set_wakeup(WakeupCondition::None);
+                }
+
+                self.wakeup_processes();
+                self.resume(remaining_time);
+            },
+            Syscall::Sleep(sleep_time) => {
+                self.end_burst();
+                match self.stopped_process.take() {
+                    Some(mut stopped_process) => {
+                        stopped_process.set_state(ProcessState::Waiting { event: None });
+                        stopped_process.set_wakeup(WakeupCondition::Sleep(sleep_time));
+                        self.waiting_processes.push(stopped_process);
+                    },
+                    None => return SyscallResult::NoRunningProcess
+                }
+            },
+            Syscall::Wait(event) => {
+                self.end_burst();
+                match self.stopped_process.take() {
+                    Some(mut stopped_process) => {
+                        stopped_process.set_state(ProcessState::Waiting { event: Some(event) });
+                        stopped_process.set_wakeup(WakeupCondition::Signal(event));
+                        self.waiting_processes.push(stopped_process);
+                    },
+                    None => return SyscallResult::NoRunningProcess
+                }
+            },
+            Syscall::Exit(status) => {
+                if let Some(stopped_process) = self.stopped_process.take() {
+                    self.shared().exit(stopped_process, status);
+                }
+                self.wakeup_processes();
+            },
+            syscall => {
+                let mut stopped_process = self.stopped_process.take();
+                let outcome = self.shared_syscall(syscall, &mut stopped_process);
+                self.stopped_process = stopped_process;
+                return match outcome {
+                    Outcome::Blocked => {
+                        self.wakeup_processes();
+                        SyscallResult::Pending
+                    },
+                    Outcome::Resume { result, wakeup } => {
+                        if wakeup {
+                            self.wakeup_processes();
+                        }
+                        self.resume(remaining_time);
+                        result
+                    },
+                    Outcome::NoRunningProcess => SyscallResult::NoRunningProcess
+                };
+            }
+        };
+
+        SyscallResult::Success
+    }
+
+}
+
+
+impl SharedSyscalls for SjfScheduler {
+    fn shared(&mut self) -> Shared<'_> {
+        Shared {
+            waiting_processes: &mut self.waiting_processes,
+            exited_processes: &mut self.exited_processes,
+            sync_objects: &mut self.sync_objects
+        }
+    }
+
+    fn processes(&self) -> Vec<&Pcb> {
+        self.get_all_processes()
+    }
+
+    fn remove_process(&mut self, pid: Pid) -> Option<Pcb> {
+        if let Some(index) = self.ready_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.ready_processes.remove(index));
+        }
+        if let Some(index) = self.waiting_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.waiting_processes.remove(index));
+        }
+        None
+    }
+}
+
+impl Scheduler for SjfScheduler {
+    fn next(&mut self) -> SchedulingDecision {
+        if self.sleep_time != 0 {
+            self.sleep();
+        }
+
+        if self.is_done() {
+            return SchedulingDecision::Done;
+        }
+
+        if !self.pid_1_exists() {
+            return SchedulingDecision::Panic;
+        }
+
+        if self.running_process.is_none() {
+            if let Some(scheduled_process) = self.scheduled_process() {
+                self.set_running(scheduled_process);
+            }
+        }
+
+        if let Some(scheduled_process) = &self.running_process {
+            let pid = scheduled_process.pid();
+            if self.preemptive {
+                if let Some(wakeup_time) = self.find_preemption_time(SjfScheduler::remaining_burst(scheduled_process)) {
+                    self.remaining_time = self.remaining_time.min(wakeup_time);
+                }
+            }
+            return SchedulingDecision::Run { pid, timeslice:
+                match NonZeroUsize::new(self.remaining_time) {Some(time) => time, None => exit(-1)}};
+        }
+
+        match self.find_sleep_time() {
+            Some(sleep_time) => {
+                self.sleep_time = sleep_time;
+                SchedulingDecision::Sleep(match NonZeroUsize::new(sleep_time)
+                    {Some(sleep_time) => sleep_time, None => exit(-1)})
+            },
+            None => SchedulingDecision::Deadlock
+        }
+    }
+
+    fn stop(&mut self, _reason: StopReason) -> SyscallResult {
+        self.stopped_process = self.running_process.take();
+
+        self.increment_timings(&_reason);
+
+        match _reason {
+            StopReason::Expired => {
+                self.wakeup_processes();
+                if self.stopped_process.is_none() {
+                    return SyscallResult::NoRunningProcess;
+                }
+                self.resume(0);
+                SyscallResult::Success
+            },
+            StopReason::Syscall{ syscall, remaining } => {
+                self.syscall_handler(syscall, remaining)
+            }
+        }
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        let mut processes = self.get_all_processes();
+        processes.extend(self.exited_processes.zombies());
+
+        processes.sort_by_key(|element|  element.pid());
+
+        processes.into_iter().map(|element| element as &dyn Process).collect()
+    }
+
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        self.shared_completions()
+    }
+
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        self.sync_objects.blockers(&self.waiting_processes)
+    }
+}
@@ -0,0 +1,413 @@
+use std::{num::NonZeroUsize, process::exit};
+use crate::{Blocker, Scheduler, SmpScheduler, Process, Pid, ProcessState, StopReason, SchedulingDecision, Syscall, SyscallResult};
+use super::pcb::{Pcb, WakeupCondition};
+use super::exited::{ExitedProcesses, KILLED};
+use super::sync::{Outcome, Shared, SharedSyscalls, SyncObjects};
+
+/// The state of a CPU, as seen by the [`SmpRoundRobinScheduler`].
+#[derive(Clone, Default)]
+struct Cpu {
+    /// The process running on the CPU.
+    running_process: Option<Pcb>,
+    /// The remaining execution time for the scheduled process.
+    remaining_time: usize,
+    /// The time at which the CPU has received its last decision.
+    decision_time: usize,
+    /// The amount of time the CPU sleeps for, `0` if the CPU is not sleeping.
+    sleep_time: usize,
+    /// Whether the running process has been killed by a process running on another CPU.
+    /// The process is finished when it stops running.
+    killed: bool
+}
+
+/// Data structure that implements a round robin scheduler for several CPUs.
+///
+/// All the CPUs share a single queue of ready processes.
+pub struct SmpRoundRobinScheduler {
+    /// The CPUs of the processor, indexed by their number.
+    cpus: Vec<Cpu>,
+    /// The list of all processes ready to be scheduled.
+    ready_processes: Vec<Pcb>,
+    /// The list of all processes waiting for an event or sleeping.
+    waiting_processes: Vec<Pcb>,
+    /// The amount of time a ready process gets on the processor.
+    timeslice: NonZeroUsize,
+    /// The minimum required time on the processor the stopped process must have remaining
+    /// for it to be scheduled imediately after the syscall that stopped it.
+    minimum_remaining_timeslice: usize,
+    /// The highest pid given to a process.
+    highest_pid: usize,
+    /// The processes that have exited and have not been joined by their parents yet.
+    exited_processes: ExitedProcesses,
+    /// The locks and the semaphores that the processes use to synchronise.
+    sync_objects: SyncObjects,
+    /// The current time of the processor.
+    time: usize
+}
+
+impl SmpRoundRobinScheduler {
+
+    /// Creates a new [`SmpRoundRobinScheduler`].
+    pub fn new(timeslice: NonZeroUsize, minimum_remaining_timeslice: usize) -> Self {
+        Self { cpus: Vec::<Cpu>::new(),
+            ready_processes: Vec::<Pcb>::new(),
+            waiting_processes: Vec::<Pcb>::new(),
+            timeslice,
+            minimum_remaining_timeslice,
+            highest_pid: 0,
+            exited_processes: ExitedProcesses::new(),
+            sync_objects: SyncObjects::new(),
+            time: 0
+        }
+    }
+
+    /// Returns the CPU with the given number, adding it if the scheduler does not know about it yet.
+    fn cpu(&mut self, cpu: usize) -> &mut Cpu {
+        if cpu >= self.cpus.len() {
+            self.cpus.resize(cpu + 1, Cpu::default());
+        }
+        &mut self.cpus[cpu]
+    }
+
+    /// Moves the time of the processor forward to `time`, incrementing the timings
+    /// for all processes and waking up the processes that have finished sleeping.
+    fn advance(&mut self, time: usize) {
+        let Some(elapsed) = time.checked_sub(self.time).filter(|elapsed| *elapsed != 0) else {
+            return;
+        };
+        self.time = time;
+
+        for process in self.cpus.iter_mut().filter_map(|cpu| cpu.running_process.as_mut()) {
+            process.increment_timings(elapsed, 0, 0);
+        }
+
+        for process in self.ready_processes.iter_mut() {
+            process.increment_timings(elapsed, 0, 0);
+        }
+
+        for process in self.waiting_processes.iter_mut() {
+            process.increment_timings(elapsed, 0, 0);
+            if let WakeupCondition::Sleep(sleep_time) = process.wakeup() {
+                match sleep_time.checked_sub(elapsed).filter(|remaining_time| *remaining_time != 0) {
+                    Some(remaining_time) =>
+                        process.set_wakeup(WakeupCondition::Sleep(remaining_time)),
+                    None => {
+                        process.set_wakeup(WakeupCondition::None);
+                        process.set_state(ProcessState::Ready);
+                    }
+                }
+            }
+        }
+        self.wakeup_processes();
+    }
+
+    /// Moves processes that have waked up into the list of ready processes.
+    fn wakeup_processes(&mut self) {
+        let mut still_waiting_processes = Vec::<Pcb>::new();
+        for process in self.waiting_processes.drain(..) {
+            if matches!(process.state(), ProcessState::Ready) {
+                self.ready_processes.push(process);
+            } else {
+                still_waiting_processes.push(process);
+            }
+        }
+        self.waiting_processes = still_waiting_processes;
+    }
+
+    /// Forks a new process with the given priority.
+    fn new_process(&mut self, priority: i8, parent: Option<Pid>) {
+        self.highest_pid += 1;
+        let mut process = Pcb::new(Pid::new(self.highest_pid), priority, 0);
+        process.set_parent(parent);
+        self.ready_processes.push(process);
+    }
+
+    /// Sets a process into the ready state.
+    fn set_ready(&mut self, mut process: Pcb) {
+        process.set_state(ProcessState::Ready);
+        process.set_wakeup(WakeupCondition::None);
+        self.ready_processes.push(process);
+    }
+
+    /// Returns `true` if any CPU is running a process, `false` otherwise.
+    fn is_running(&self) -> bool {
+        self.cpus.iter().any(|cpu| cpu.running_process.is_some())
+    }
+
+    /// Returns `true` if there are no more processes, `false` otherwise.
+    fn is_done(&self) -> bool {
+        !self.is_running() && self.ready_processes.is_empty() && self.waiting_processes.is_empty()
+    }
+
+    /// Returns `true` if the process with pid 1 exists, `false` otherwise.
+    fn pid_1_exists(&self) -> bool {
+        self.get_all_processes().iter().any(|element| element.pid() == 1)
+    }
+
+    /// Returns the minimal amount of time the processor needs to sleep for a process to become ready for scheduling.
+    fn find_sleep_time(&self) -> Option<usize> {
+        self.waiting_processes.iter().filter_map(|element|
+            match element.wakeup() {WakeupCondition::Sleep(sleep_time) => Some(sleep_time), _ => None})
+            .min()
+    }
+
+    /// Return an vector of refrences to all processes.
+    fn get_all_processes(&self) -> Vec<&Pcb> {
+        let mut processes = Vec::<&Pcb>::new();
+        processes.extend(self.ready_processes.iter());
+        processes.extend(self.waiting_processes.iter());
+        processes.extend(self.cpus.iter().filter_map(|cpu| cpu.running_process.as_ref()));
+        processes
+    }
+
+    /// Returns the CPU that runs the process with the PID `pid`.
+    fn running_cpu(&self, pid: Pid) -> Option<usize> {
+        self.cpus.iter().position(|cpu| cpu.running_process.as_ref().is_some_and(|process| process.pid() == pid))
+    }
+
+    /// Schedules the stopped process again on its CPU if it has enough time left from its quanta,
+    /// or puts it back in the ready queue otherwise.
+    fn resume(&mut self, cpu: usize, stopped_process: Option<Pcb>, remaining_time: usize) {
+        if let Some(mut stopped_process) = stopped_process {
+            if remaining_time >= self.minimum_remaining_timeslice {
+                stopped_process.set_state(ProcessState::Running);
+                let cpu = self.cpu(cpu);
+                cpu.running_process = Some(stopped_process);
+                cpu.remaining_time = remaining_time;
+            } else {
+                self.set_ready(stopped_process);
+            }
+        }
+    }
+
+    /// Handles syscalls recievied from the process running on the CPU `cpu`.
+    fn syscall_handler(&mut self, cpu: usize, stopped_process: Option<Pcb>, syscall: Syscall, remaining_time: usize) -> SyscallResult {
+        match syscall {
+            Syscall::Fork(priority) | Syscall::ForkDeadline { priority, deadline: _, period: _ }
+            | Syscall::ForkBurst { priority, burst: _ } => {
+                // only the first process is forked without a running process, a process
+                // killed while it ran on another CPU cannot fork anymore
+                if stopped_process.is_none() && self.highest_pid != 0 {
+                    return SyscallResult::NoRunningProcess;
+                }
+                self.new_process(priority, stopped_process.as_ref().map(|process| process.pid()));
+                self.resume(cpu, stopped_process, remaining_time);
+                return SyscallResult::Pid(Pid::new(self.highest_pid));
+            }
+            Syscall::Signal(event) => {
+                for process in self.waiting_processes.iter_mut()
+                    .filter(|element| matches!(element.wakeup(), WakeupCondition::Signal(x) if x == event)) {
+                    process.set_state(ProcessState::Ready);
+                    process.set_wakeup(WakeupCondition::None);
+                }
+
+                self.wakeup_processes();
+                self.resume(cpu, stopped_process, remaining_time);
+            },
+            Syscall::Sleep(sleep_time) => {
+                match stopped_process {
+                    Some(mut stopped_process) => {
+                        stopped_process.set_state(ProcessState::Waiting { event: None });
+                        stopped_process.set_wakeup(WakeupCondition::Sleep(sleep_time));
+                        self.waiting_processes.push(stopped_process);
+                    },
+                    None => return SyscallResult::NoRunningProcess
+                }
+            },
+            Syscall::Wait(event) => {
+                match stopped_process {
+                    Some(mut stopped_process) => {
+                        stopped_process.set_state(ProcessState::Waiting { event: Some(event) });
+                        stopped_process.set_wakeup(WakeupCondition::Signal(event));
+                        self.waiting_processes.push(stopped_process);
+                    },
+                    None => return SyscallResult::NoRunningProcess
+                }
+            },
+            Syscall::Exit(status) => {
+                if let Some(stopped_process) = stopped_process {
+                    self.shared().exit(stopped_process, status);
+                }
+                self.wakeup_processes();
+            },
+            Syscall::Kill(pid) if self.running_cpu(pid).is_some() => {
+                if let Some(running) = self.running_cpu(pid) {
+                    self.cpus[running].killed = true;
+                }
+
+                self.wakeup_processes();
+                self.resume(cpu, stopped_process, remaining_time);
+                return SyscallResult::Success;
+            },
+            syscall => {
+                let mut stopped_process = stopped_process;
+                let outcome = self.shared_syscall(syscall, &mut stopped_process);
+                return match outcome {
+                    Outcome::Blocked => {
+                        self.wakeup_processes();
+                        SyscallResult::Pending
+                    },
+                    Outcome::Resume { result, wakeup } => {
+                        if wakeup {
+                            self.wakeup_processes();
+                        }
+                        self.resume(cpu, stopped_process, remaining_time);
+                        result
+                    },
+                    Outcome::NoRunningProcess => SyscallResult::NoRunningProcess
+                };
+            }
+        };
+
+        SyscallResult::Success
+    }
+
+}
+
+impl SharedSyscalls for SmpRoundRobinScheduler {
+    fn shared(&mut self) -> Shared<'_> {
+        Shared {
+            waiting_processes: &mut self.waiting_processes,
+            exited_processes: &mut self.exited_processes,
+            sync_objects: &mut self.sync_objects
+        }
+    }
+
+    fn processes(&self) -> Vec<&Pcb> {
+        self.get_all_processes()
+    }
+
+    fn remove_process(&mut self, pid: Pid) -> Option<Pcb> {
+        if let Some(index) = self.ready_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.ready_processes.remove(index));
+        }
+        if let Some(index) = self.waiting_processes.iter().position(|element| element.pid() == pid) {
+            return Some(self.waiting_processes.remove(index));
+        }
+        None
+    }
+}
+
+impl SmpScheduler for SmpRoundRobinScheduler {
+    fn next_cpu(&mut self, cpu: usize) -> SchedulingDecision {
+        let sleep = self.cpu(cpu);
+        if sleep.sleep_time != 0 {
+            let time = sleep.decision_time + sleep.sleep_time;
+            sleep.sleep_time = 0;
+            self.advance(time);
+        }
+        let time = self.time;
+        self.cpu(cpu).decision_time = time;
+
+        if self.is_done() {
+            return SchedulingDecision::Done;
+        }
+
+        if !self.pid_1_exists() {
+            return SchedulingDecision::Panic;
+        }
+
+        if let Some(scheduled_process) = &self.cpus[cpu].running_process {
+            return SchedulingDecision::Run { pid: scheduled_process.pid(), timeslice:
+                match NonZeroUsize::new(self.cpus[cpu].remaining_time) {Some(time) => time, None => exit(-1)}};
+        }
+
+        if !self.ready_processes.is_empty() {
+            let mut scheduled_process = self.ready_processes.remove(0);
+            scheduled_process.set_state(ProcessState::Running);
+            let pid = scheduled_process.pid();
+            let timeslice = self.timeslice;
+            let cpu = self.cpu(cpu);
+            cpu.running_process = Some(scheduled_process);
+            cpu.remaining_time = timeslice.get();
+            return SchedulingDecision::Run { pid, timeslice };
+        }
+
+        // While other CPUs are running processes, the idle CPU checks
+        // for ready processes after every time unit.
+        let sleep_time = if self.is_running() {
+            Some(1)
+        } else {
+            self.find_sleep_time()
+        };
+
+        match sleep_time {
+            Some(sleep_time) => {
+                self.cpu(cpu).sleep_time = sleep_time;
+                SchedulingDecision::Sleep(match NonZeroUsize::new(sleep_time)
+                    {Some(sleep_time) => sleep_time, None => exit(-1)})
+            },
+            None => SchedulingDecision::Deadlock
+        }
+    }
+
+    fn stop_cpu(&mut self, cpu: usize, reason: StopReason) -> SyscallResult {
+        let stopped = self.cpu(cpu);
+        let time = match reason {
+            StopReason::Expired => stopped.remaining_time,
+            StopReason::Syscall { syscall: _, remaining } => stopped.remaining_time - remaining
+        };
+        let stop_time = stopped.decision_time + time;
+        self.advance(stop_time);
+
+        let stopped = self.cpu(cpu);
+        stopped.remaining_time = 0;
+        let mut stopped_process = stopped.running_process.take();
+        if std::mem::take(&mut stopped.killed) {
+            if let Some(killed) = stopped_process.take() {
+                self.shared().exit(killed, KILLED);
+                self.wakeup_processes();
+            }
+        }
+        if let Some(stopped_process) = &mut stopped_process {
+            match reason {
+                StopReason::Syscall { syscall: _, remaining: _ } => stopped_process.increment_timings(0, 1, time - 1),
+                StopReason::Expired => stopped_process.increment_timings(0, 0, time)
+            }
+        }
+
+        match reason {
+            StopReason::Expired => {
+                match stopped_process {
+                    Some(stopped_process) => {
+                        self.set_ready(stopped_process);
+                        SyscallResult::Success
+                    },
+                    None => {
+                        SyscallResult::NoRunningProcess
+                    }
+                }
+            },
+            StopReason::Syscall{ syscall, remaining } => {
+                self.syscall_handler(cpu, stopped_process, syscall, remaining)
+            }
+        }
+    }
+}
+
+impl Scheduler for SmpRoundRobinScheduler {
+    fn next(&mut self) -> SchedulingDecision {
+        self.next_cpu(0)
+    }
+
+    fn stop(&mut self, reason: StopReason) -> SyscallResult {
+        self.stop_cpu(0, reason)
+    }
+
+    fn list(&mut self) -> Vec<&dyn Process> {
+        let mut processes = self.get_all_processes();
+        processes.extend(self.exited_processes.zombies());
+
+        processes.sort_by_key(|element|  element.pid());
+
+        processes.into_iter().map(|element| element as &dyn Process).collect()
+    }
+
+    fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        self.shared_completions()
+    }
+
+    fn blockers(&self) -> Vec<(Pid, Blocker)> {
+        self.sync_objects.blockers(&self.waiting_processes)
+    }
+}
@@ -0,0 +1,312 @@
+use std::{collections::HashMap, mem::take};
+use crate::{Blocker, Pid, Process, ProcessState, SyncObject, Syscall, SyscallResult};
+use super::pcb::{Pcb, WakeupCondition};
+use super::exited::{ExitedProcesses, KILLED};
+
+/// The locks and the semaphores that processes use to synchronise.
+///
+/// A process blocked on a lock or on a semaphore is kept with the waiting processes until
+/// another process releases the lock or posts the semaphore, the result of its system call
+/// being returned to it as a completion.
+pub struct SyncObjects {
+    /// The PIDs of the processes that hold the locks.
+    owners: HashMap<usize, Pid>,
+    /// The values of the semaphores.
+    semaphores: HashMap<usize, usize>,
+    /// The results of the system calls that have completed since the last call to
+    /// [`SyncObjects::completions`].
+    completions: Vec<(Pid, Syscall, SyscallResult)>
+}
+
+impl SyncObjects {
+
+    /// Creates a new [`SyncObjects`].
+    pub fn new() -> Self {
+        Self { owners: HashMap::new(), semaphores: HashMap::new(), completions: Vec::new() }
+    }
+
+    /// Acquires the lock `id` for `process`.
+    ///
+    /// Returns the process if it has acquired the lock. Otherwise the process is blocked
+    /// and added to the `waiting_processes`.
+    pub fn lock(&mut self, id: usize, process: Pcb, waiting_processes: &mut Vec<Pcb>) -> Option<Pcb> {
+        if self.owners.contains_key(&id) {
+            block(process, SyncObject::Lock(id), WakeupCondition::Lock(id), waiting_processes);
+            return None;
+        }
+        self.owners.insert(id, process.pid());
+        Some(process)
+    }
+
+    /// Releases the lock `id` held by the process `pid`.
+    ///
+    /// The lock is handed to the first of the `waiting_processes` blocked on it,
+    /// which is set into the ready state.
+    pub fn unlock(&mut self, id: usize, pid: Pid, waiting_processes: &mut [Pcb]) -> SyscallResult {
+        if self.owners.get(&id) != Some(&pid) {
+            return SyscallResult::NotOwner;
+        }
+        self.release(id, waiting_processes);
+        SyscallResult::Success
+    }
+
+    /// Releases all the locks held by the process `pid`, which has exited or has been killed.
+    ///
+    /// Each lock is handed to the first of the `waiting_processes` blocked on it, as
+    /// [`SyncObjects::unlock`] does, or is freed if no process waits for it.
+    pub fn release_all(&mut self, pid: Pid, waiting_processes: &mut [Pcb]) {
+        let mut locks = self.owners.iter()
+            .filter(|(_, owner)| **owner == pid)
+            .map(|(id, _)| *id)
+            .collect::<Vec<usize>>();
+        locks.sort();
+        for id in locks {
+            self.release(id, waiting_processes);
+        }
+    }
+
+    /// Hands the lock `id` to the first of the `waiting_processes` blocked on it, which is
+    /// set into the ready state, or frees it.
+    fn release(&mut self, id: usize, waiting_processes: &mut [Pcb]) {
+        match wake(WakeupCondition::Lock(id), waiting_processes) {
+            Some(next_owner) => {
+                self.owners.insert(id, next_owner);
+                self.completions.push((next_owner, Syscall::Lock(id), SyscallResult::Success));
+            },
+            None => {
+                self.owners.remove(&id);
+            }
+        }
+    }
+
+    /// Decrements the semaphore `id` for `process`.
+    ///
+    /// Returns the process if the semaphore was not `0`. Otherwise the process is blocked
+    /// and added to the `waiting_processes`.
+    pub fn sem_wait(&mut self, id: usize, process: Pcb, waiting_processes: &mut Vec<Pcb>) -> Option<Pcb> {
+        match self.semaphores.get_mut(&id).filter(|value| **value > 0) {
+            Some(value) => {
+                *value -= 1;
+                Some(process)
+            },
+            None => {
+                block(process, SyncObject::Semaphore(id), WakeupCondition::Semaphore(id), waiting_processes);
+                None
+            }
+        }
+    }
+
+    /// Increments the semaphore `id`.
+    ///
+    /// If one of the `waiting_processes` is blocked on the semaphore, the first one
+    /// is set into the ready state instead.
+    pub fn sem_post(&mut self, id: usize, waiting_processes: &mut [Pcb]) {
+        match wake(WakeupCondition::Semaphore(id), waiting_processes) {
+            Some(pid) => self.completions.push((pid, Syscall::SemWait(id), SyscallResult::Success)),
+            None => *self.semaphores.entry(id).or_insert(0) += 1
+        }
+    }
+
+    /// Returns the PID of the process that holds the lock `id`.
+    pub fn owner(&self, id: usize) -> Option<Pid> {
+        self.owners.get(&id).copied()
+    }
+
+    /// Returns the highest priority of the `processes` blocked on the locks held by the process `pid`.
+    pub fn inherited_priority<'a>(&self, pid: Pid, processes: impl Iterator<Item = &'a Pcb>) -> Option<i8> {
+        processes
+            .filter(|element| matches!(element.wakeup(), WakeupCondition::Lock(id) if self.owner(id) == Some(pid)))
+            .map(|element| element.priority())
+            .max()
+    }
+
+    /// Returns a description of every priority inversion that takes place while the process
+    /// `running` runs.
+    ///
+    /// A priority inversion takes place when one of the `processes` is blocked on a lock held
+    /// by a process with a lower priority than the running process, while its own priority is
+    /// higher than the one of the running process.
+    pub fn inversions(&self, running: &Pcb, processes: &[&Pcb]) -> Vec<String> {
+        let mut inversions = Vec::<String>::new();
+        for blocked in processes.iter() {
+            let WakeupCondition::Lock(id) = blocked.wakeup() else {
+                continue;
+            };
+            let Some(owner) = self.owner(id)
+                .and_then(|owner| processes.iter().find(|element| element.pid() == owner)) else {
+                continue;
+            };
+            if blocked.priority() > running.priority() && running.priority() > owner.priority() {
+                inversions.push(format!("Priority inversion, process {} (priority {}) runs while process {} (priority {}) \
+                    is blocked on lock {} held by process {} (priority {})",
+                    running.pid(), running.priority(), blocked.pid(), blocked.priority(), id, owner.pid(), owner.priority()));
+            }
+        }
+        inversions
+    }
+
+    /// Returns what each of the `waiting_processes` that does not sleep is blocked on.
+    pub fn blockers(&self, waiting_processes: &[Pcb]) -> Vec<(Pid, Blocker)> {
+        waiting_processes.iter()
+            .filter_map(|element| {
+                let blocker = match element.wakeup() {
+                    WakeupCondition::Signal(event) => Blocker::Event(event),
+                    WakeupCondition::Join(pid) => Blocker::Child(pid),
+                    WakeupCondition::Lock(lock) => Blocker::Lock { lock, owner: self.owner(lock)? },
+                    WakeupCondition::Semaphore(semaphore) => Blocker::Semaphore(semaphore),
+                    WakeupCondition::Sleep(_) | WakeupCondition::None => return None
+                };
+                Some((element.pid(), blocker))
+            })
+            .collect()
+    }
+
+    /// Returns the results of the lock and semaphore system calls that have completed since the last call.
+    pub fn completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        take(&mut self.completions)
+    }
+}
+
+/// The state of a scheduler that the [`SharedSyscalls`] change.
+pub struct Shared<'a> {
+    /// The processes waiting for an event, sleeping or blocked.
+    pub waiting_processes: &'a mut Vec<Pcb>,
+    /// The processes that have exited and have not been joined by their parents yet.
+    pub exited_processes: &'a mut ExitedProcesses,
+    /// The locks and the semaphores.
+    pub sync_objects: &'a mut SyncObjects
+}
+
+impl Shared<'_> {
+    /// Records that `process` has exited with the exit status `status`, releasing the
+    /// locks it holds.
+    pub fn exit(self, process: Pcb, status: i32) {
+        self.sync_objects.release_all(process.pid(), self.waiting_processes);
+        self.exited_processes.exit(process, status, self.waiting_processes);
+    }
+}
+
+/// What a scheduler does after a system call handled by [`SharedSyscalls::shared_syscall`].
+pub enum Outcome {
+    /// The stopped process is blocked. The scheduler wakes up the processes and
+    /// returns [`SyscallResult::Pending`].
+    Blocked,
+    /// The scheduler resumes the stopped process, if there still is one, and returns `result`.
+    /// The processes are woken up before if `wakeup` is `true`.
+    Resume { result: SyscallResult, wakeup: bool },
+    /// There is no stopped process, the scheduler returns [`SyscallResult::NoRunningProcess`].
+    NoRunningProcess
+}
+
+/// The system calls that all the schedulers handle in the same way: [`Syscall::Kill`],
+/// [`Syscall::Join`] and the lock and semaphore system calls.
+pub trait SharedSyscalls {
+    /// Returns the state that the shared system calls change.
+    fn shared(&mut self) -> Shared<'_>;
+
+    /// Returns all the processes that have not exited, except the stopped one.
+    fn processes(&self) -> Vec<&Pcb>;
+
+    /// Removes the process with the PID `pid` from the ready or waiting processes.
+    fn remove_process(&mut self, pid: Pid) -> Option<Pcb>;
+
+    /// Handles a shared system call made by the `stopped_process`. The process is taken
+    /// out of `stopped_process` if it is killed or blocked.
+    ///
+    /// ## Panics
+    ///
+    /// Panics if `syscall` is not a shared system call.
+    fn shared_syscall(&mut self, syscall: Syscall, stopped_process: &mut Option<Pcb>) -> Outcome {
+        match syscall {
+            Syscall::Kill(pid) => {
+                let killed = if stopped_process.as_ref().is_some_and(|process| process.pid() == pid) {
+                    stopped_process.take()
+                } else {
+                    self.remove_process(pid)
+                };
+
+                let result = match killed {
+                    Some(killed) => {
+                        self.shared().exit(killed, KILLED);
+                        SyscallResult::Success
+                    },
+                    None => SyscallResult::NoSuchProcess
+                };
+                Outcome::Resume { result, wakeup: true }
+            },
+            Syscall::Join(pid) => {
+                let parent = match stopped_process {
+                    Some(stopped_process) => stopped_process.pid(),
+                    None => return Outcome::NoRunningProcess
+                };
+                match self.shared().exited_processes.join(parent, pid) {
+                    Some(status) => Outcome::Resume { result: SyscallResult::ExitStatus(status), wakeup: false },
+                    None if self.processes().iter()
+                        .any(|element| element.pid() == pid && element.ppid() == Some(parent)) => {
+                        if let Some(stopped_process) = stopped_process.take() {
+                            block(stopped_process, SyncObject::Child(pid), WakeupCondition::Join(pid),
+                                self.shared().waiting_processes);
+                        }
+                        Outcome::Blocked
+                    },
+                    None => Outcome::Resume { result: SyscallResult::NoSuchProcess, wakeup: false }
+                }
+            },
+            Syscall::Lock(id) | Syscall::SemWait(id) => {
+                let process = match stopped_process.take() {
+                    Some(process) => process,
+                    None => return Outcome::NoRunningProcess
+                };
+                let shared = self.shared();
+                *stopped_process = match syscall {
+                    Syscall::Lock(_) => shared.sync_objects.lock(id, process, shared.waiting_processes),
+                    _ => shared.sync_objects.sem_wait(id, process, shared.waiting_processes)
+                };
+                match stopped_process {
+                    Some(_) => Outcome::Resume { result: SyscallResult::Success, wakeup: false },
+                    None => Outcome::Blocked
+                }
+            },
+            Syscall::Unlock(id) => {
+                let pid = match stopped_process {
+                    Some(stopped_process) => stopped_process.pid(),
+                    None => return Outcome::NoRunningProcess
+                };
+                let shared = self.shared();
+                let result = shared.sync_objects.unlock(id, pid, shared.waiting_processes);
+                Outcome::Resume { result, wakeup: true }
+            },
+            Syscall::SemPost(id) => {
+                let shared = self.shared();
+                shared.sync_objects.sem_post(id, shared.waiting_processes);
+                Outcome::Resume { result: SyscallResult::Success, wakeup: true }
+            },
+            syscall => panic!("{:?} is not a shared system call", syscall)
+        }
+    }
+
+    /// Returns the results of the join, lock and semaphore system calls that have
+    /// completed since the last call.
+    fn shared_completions(&mut self) -> Vec<(Pid, Syscall, SyscallResult)> {
+        let shared = self.shared();
+        let mut completions = shared.exited_processes.completions();
+        completions.extend(shared.sync_objects.completions());
+        completions
+    }
+}
+
+/// Blocks `process` on the synchronisation object `object` and adds it to the `waiting_processes`.
+fn block(mut process: Pcb, object: SyncObject, wakeup: WakeupCondition, waiting_processes: &mut Vec<Pcb>) {
+    process.set_state(ProcessState::Blocked { object });
+    process.set_wakeup(wakeup);
+    waiting_processes.push(process);
+}
+
+/// Sets into the ready state the first of the `waiting_processes` that waits for `wakeup`
+/// and returns its PID.
+fn wake(wakeup: WakeupCondition, waiting_processes: &mut [Pcb]) -> Option<Pid> {
+    let process = waiting_processes.iter_mut().find(|element| element.wakeup() == wakeup)?;
+    process.set_state(ProcessState::Ready);
+    process.set_wakeup(WakeupCondition::None);
+    Some(process.pid())
+}